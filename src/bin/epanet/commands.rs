@@ -0,0 +1,483 @@
+//! Subcommand implementations for the `epanet` command-line tool.
+
+use crate::{Args, CliError, CliResult};
+use epanet::epanet_error::Result;
+use epanet::types::analysis::InitHydOption;
+use epanet::types::diff::DiffOptions;
+use epanet::types::export::{ExportFormat, ExportLayout, ExportOptions, TimeIndex};
+use epanet::types::link::LinkProperty;
+use epanet::types::node::NodeProperty;
+use epanet::types::options::{self, FlowUnits, HeadLossType, QualityType, TimeParameter};
+use epanet::types::validation::Severity;
+use epanet::types::CountType;
+use epanet::EPANET;
+use num_traits::FromPrimitive;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::process::ExitCode;
+
+/// Report file for commands that do not produce one. An empty path would send the
+/// report to standard output.
+const NULL_REPORT: &str = if cfg!(windows) { "NUL" } else { "/dev/null" };
+
+/// Flow unit names accepted by `convert --flow-units`, as written in INP files.
+const FLOW_UNITS: [(&str, FlowUnits); 11] = [
+    ("CFS", FlowUnits::Cfs),
+    ("GPM", FlowUnits::Gpm),
+    ("MGD", FlowUnits::Mgd),
+    ("IMGD", FlowUnits::Imgd),
+    ("AFD", FlowUnits::Afd),
+    ("LPS", FlowUnits::Lps),
+    ("LPM", FlowUnits::Lpm),
+    ("MLD", FlowUnits::Mld),
+    ("CMH", FlowUnits::Cmh),
+    ("CMD", FlowUnits::Cmd),
+    ("CMS", FlowUnits::Cms),
+];
+
+/// Node result properties accepted by `export --nodes`.
+const NODE_PROPERTIES: [(&str, NodeProperty); 4] = [
+    ("pressure", NodeProperty::Pressure),
+    ("head", NodeProperty::Head),
+    ("demand", NodeProperty::Demand),
+    ("quality", NodeProperty::Quality),
+];
+
+/// Link result properties accepted by `export --links`.
+const LINK_PROPERTIES: [(&str, LinkProperty); 6] = [
+    ("flow", LinkProperty::Flow),
+    ("velocity", LinkProperty::Velocity),
+    ("headloss", LinkProperty::HeadLoss),
+    ("status", LinkProperty::Status),
+    ("setting", LinkProperty::Setting),
+    ("quality", LinkProperty::LinkQual),
+];
+
+/// `epanet run`: full simulation with a progress bar, then the EPANET report.
+pub fn run(args: impl Iterator<Item = String>) -> CliResult {
+    let args = Args::parse(args, &["report", "output"], &["quiet"])?;
+    let inp = args.positional(0, "input file")?;
+    args.expect_positionals(1)?;
+    let report = match args.option("report") {
+        Some(path) => path.to_string(),
+        None => Path::new(inp)
+            .with_extension("rpt")
+            .to_string_lossy()
+            .into_owned(),
+    };
+    let output = args.option("output").unwrap_or("");
+    let quiet = args.flag("quiet");
+
+    let ph = open(inp, &report, output)?;
+    let duration = ph.get_time_parameter(TimeParameter::Duration)?;
+    let mut bar = Progress::new("hydraulics", duration, quiet);
+    let mut warnings = simulate(&ph, InitHydOption::Save, |t| {
+        bar.update(t);
+        Ok(())
+    })?;
+    bar.finish();
+
+    if ph.get_quality_info()?.quality_type != QualityType::None {
+        if !quiet {
+            eprintln!("quality...");
+        }
+        warnings += allow_warning(ph.solve_q())?;
+    }
+    ph.report()?;
+    if !quiet {
+        eprintln!("{warnings} warning(s); report written to {report}");
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `epanet info`: element counts, options and time parameters.
+pub fn info(args: impl Iterator<Item = String>) -> CliResult {
+    let args = Args::parse(args, &[], &[])?;
+    let inp = args.positional(0, "input file")?;
+    args.expect_positionals(1)?;
+    let ph = open(inp, NULL_REPORT, "")?;
+
+    let nodes = ph.nodes()?;
+    let links = ph.links()?;
+    let junctions = nodes.iter().filter(|n| n.is_junction()).count();
+    let tanks = nodes.iter().filter(|n| n.is_tank()).count();
+    let pipes = links.iter().filter(|l| l.is_pipe()).count();
+    let pumps = links.iter().filter(|l| l.is_pump()).count();
+    let quality = ph.get_quality_info()?;
+
+    println!("Title:          {}", ph.get_title()?.trim());
+    println!(
+        "Nodes:          {} ({} junctions, {} tanks, {} reservoirs)",
+        nodes.len(),
+        junctions,
+        tanks,
+        nodes.len() - junctions - tanks
+    );
+    println!(
+        "Links:          {} ({} pipes, {} pumps, {} valves)",
+        links.len(),
+        pipes,
+        pumps,
+        links.len() - pipes - pumps
+    );
+    println!("Patterns:       {}", ph.get_count(CountType::PatternCount)?);
+    println!("Curves:         {}", ph.get_count(CountType::CurveCount)?);
+    println!("Controls:       {}", ph.get_count(CountType::ControlCount)?);
+    println!("Rules:          {}", ph.get_count(CountType::RuleCount)?);
+    println!("Flow units:     {}", flow_units_name(ph.get_flow_units()?));
+    let head_loss = ph.get_option(options::Option::HeadLossForm)? as i32;
+    match HeadLossType::from_i32(head_loss) {
+        Some(form) => println!("Head loss:      {form:?}"),
+        None => println!("Head loss:      {head_loss}"),
+    }
+    match quality.quality_type {
+        QualityType::Chem => println!(
+            "Quality:        Chem ({}, {})",
+            quality.chem_name, quality.chem_units
+        ),
+        QualityType::Trace => println!(
+            "Quality:        Trace from {}",
+            ph.get_node_id(quality.trace_node_index)?
+        ),
+        other => println!("Quality:        {other:?}"),
+    }
+    for (label, parameter) in [
+        ("Duration", TimeParameter::Duration),
+        ("Hydraulic step", TimeParameter::HydStep),
+        ("Quality step", TimeParameter::QualStep),
+        ("Pattern step", TimeParameter::PatternStep),
+        ("Pattern start", TimeParameter::PatternStart),
+        ("Report step", TimeParameter::ReportStep),
+        ("Report start", TimeParameter::ReportStart),
+        ("Start time", TimeParameter::StartTime),
+    ] {
+        println!(
+            "{:<16}{}",
+            format!("{label}:"),
+            clock(ph.get_time_parameter(parameter)?)
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Output file formats of `epanet export`.
+#[derive(Debug, Clone, Copy)]
+enum OutputFormat {
+    Csv,
+    Json,
+    Parquet,
+}
+
+/// `epanet export`: results at each reporting time as CSV or newline-delimited
+/// JSON, in long (`time,element,id,property,value`) or wide layout.
+pub fn export(args: impl Iterator<Item = String>) -> CliResult {
+    let args = Args::parse(
+        args,
+        &["output", "format", "layout", "time", "nodes", "links"],
+        &[],
+    )?;
+    let inp = args.positional(0, "input file")?;
+    args.expect_positionals(1)?;
+    let path = args
+        .option("output")
+        .ok_or_else(|| CliError::Usage("export requires --output".into()))?;
+    let format = match args.option("format").unwrap_or("csv") {
+        "csv" => OutputFormat::Csv,
+        "json" => OutputFormat::Json,
+        "parquet" => OutputFormat::Parquet,
+        other => return Err(CliError::Usage(format!("unknown format {other}"))),
+    };
+    let layout = match args.option("layout").unwrap_or("long") {
+        "long" => ExportLayout::Long,
+        "wide" => ExportLayout::Wide,
+        other => return Err(CliError::Usage(format!("unknown layout {other}"))),
+    };
+    let time_index = match args.option("time").unwrap_or("seconds") {
+        "seconds" => TimeIndex::Seconds,
+        "clock" => TimeIndex::Clock,
+        other => return Err(CliError::Usage(format!("unknown time index {other}"))),
+    };
+    let options = ExportOptions {
+        layout,
+        time_index,
+        node_properties: select(
+            args.option("nodes").unwrap_or("pressure,head,demand"),
+            &NODE_PROPERTIES,
+        )?,
+        link_properties: select(
+            args.option("links").unwrap_or("flow,velocity"),
+            &LINK_PROPERTIES,
+        )?,
+        ..ExportOptions::default()
+    };
+
+    let ph = open(inp, NULL_REPORT, "")?;
+    let format = match format {
+        OutputFormat::Csv => ExportFormat::Csv,
+        OutputFormat::Json => ExportFormat::NdJson,
+        OutputFormat::Parquet => {
+            export_parquet(&ph, path, &options)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+    };
+    ph.export_results(
+        BufWriter::new(File::create(path)?),
+        ExportOptions { format, ..options },
+    )?;
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(feature = "arrow")]
+fn export_parquet(
+    ph: &EPANET,
+    path: &str,
+    options: &ExportOptions,
+) -> std::result::Result<(), CliError> {
+    let batch = ph
+        .record_results(options)?
+        .to_record_batch(options.layout, options.time_index)?;
+    epanet::types::arrow::write_parquet(&batch, File::create(path)?)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "arrow"))]
+fn export_parquet(
+    _ph: &EPANET,
+    _path: &str,
+    _options: &ExportOptions,
+) -> std::result::Result<(), CliError> {
+    Err(CliError::Failed(
+        "parquet output requires building with the `arrow` feature".into(),
+    ))
+}
+
+/// `epanet validate`: input errors, suspicious data and hydraulic warnings.
+pub fn validate(args: impl Iterator<Item = String>) -> CliResult {
+    let args = Args::parse(args, &[], &[])?;
+    let inp = args.positional(0, "input file")?;
+    args.expect_positionals(1)?;
+    let ph = match EPANET::with_inp_file_allow_errors(inp, NULL_REPORT, "") {
+        Ok(ph) => ph,
+        Err(e) => {
+            println!("error: {e}");
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+
+    let mut errors = 0;
+    let mut warnings = 0;
+    for finding in ph.validate()? {
+        println!("{finding}");
+        match finding.severity {
+            Severity::Error => errors += 1,
+            Severity::Warning => warnings += 1,
+            Severity::Info => {}
+        }
+    }
+    if errors == 0 {
+        match simulate(&ph, InitHydOption::NoSave, |_| Ok(())) {
+            Ok(count) if count > 0 => {
+                println!("warning: hydraulic solver raised {count} warning(s)");
+                warnings += 1;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                println!("error: {e}");
+                errors += 1;
+            }
+        }
+    }
+    println!("{errors} error(s), {warnings} warning(s)");
+    Ok(if errors > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// `epanet convert`: re-saves a model, optionally in different flow units.
+pub fn convert(args: impl Iterator<Item = String>) -> CliResult {
+    let args = Args::parse(args, &["flow-units"], &[])?;
+    let inp = args.positional(0, "input file")?;
+    let out = args.positional(1, "output file")?;
+    args.expect_positionals(2)?;
+    let ph = open(inp, NULL_REPORT, "")?;
+    if let Some(name) = args.option("flow-units") {
+        let units = FLOW_UNITS
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, u)| *u)
+            .ok_or_else(|| CliError::Usage(format!("unknown flow units {name}")))?;
+        ph.set_flow_units(units)?;
+    }
+    ph.save_inp_file(out)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `epanet diff`: added, removed and changed elements between two models.
+/// Exits with 1 when differences are found.
+pub fn diff(args: impl Iterator<Item = String>) -> CliResult {
+    let args = Args::parse(args, &["format", "tolerance"], &["no-renames"])?;
+    let a_path = args.positional(0, "first input file")?;
+    let b_path = args.positional(1, "second input file")?;
+    args.expect_positionals(2)?;
+    let json = match args.option("format").unwrap_or("text") {
+        "text" => false,
+        "json" => true,
+        other => return Err(CliError::Usage(format!("unknown format {other}"))),
+    };
+    let mut options = DiffOptions {
+        detect_renames: !args.flag("no-renames"),
+        ..DiffOptions::default()
+    };
+    if let Some(tolerance) = args.option("tolerance") {
+        options.tolerance = tolerance
+            .parse()
+            .map_err(|_| CliError::Usage(format!("invalid tolerance {tolerance}")))?;
+    }
+    let a = open(a_path, NULL_REPORT, "")?;
+    let b = open(b_path, NULL_REPORT, "")?;
+
+    let diff = a.diff(&b, &options)?;
+    if json {
+        println!("{}", diff.to_json());
+    } else {
+        print!("{diff}");
+    }
+    Ok(if diff.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+// Helpers
+
+/// Opens `inp`, naming the file in any error.
+fn open(inp: &str, report: &str, output: &str) -> std::result::Result<EPANET, CliError> {
+    EPANET::with_inp_file(inp, report, output).map_err(|e| CliError::Failed(format!("{inp}: {e}")))
+}
+
+/// Maps a warning to `Ok(1)` and success to `Ok(0)`, passing real errors through.
+fn allow_warning<T>(result: Result<T>) -> Result<u32> {
+    match result {
+        Ok(_) => Ok(0),
+        Err(e) if e.is_warning() => Ok(1),
+        Err(e) => Err(e),
+    }
+}
+
+/// Runs an extended period hydraulic simulation, calling `observe` with the time
+/// of every solution. Returns the number of solver warnings.
+fn simulate<F: FnMut(i64) -> Result<()>>(
+    ph: &EPANET,
+    init: InitHydOption,
+    mut observe: F,
+) -> Result<u32> {
+    let duration = ph.get_time_parameter(TimeParameter::Duration)?;
+    ph.open_h()?;
+    let mut run = || -> Result<u32> {
+        ph.init_h(init)?;
+        let mut warnings = 0;
+        loop {
+            warnings += allow_warning(ph.run_h())?;
+            let t = ph.get_time_parameter(TimeParameter::HTime)? as i64;
+            observe(t)?;
+            let done = match ph.next_h() {
+                Ok(dt) => dt == 0,
+                Err(e) if e.is_warning() => {
+                    warnings += 1;
+                    ph.get_time_parameter(TimeParameter::HTime)? >= duration
+                }
+                Err(e) => return Err(e),
+            };
+            if done {
+                return Ok(warnings);
+            }
+        }
+    };
+    let result = run();
+    ph.close_h()?;
+    result
+}
+
+/// Splits a comma-separated property list and looks each name up in `table`.
+fn select<T: Copy>(list: &str, table: &[(&str, T)]) -> std::result::Result<Vec<T>, CliError> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            table
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, property)| *property)
+                .ok_or_else(|| CliError::Usage(format!("unknown property {name}")))
+        })
+        .collect()
+}
+
+fn flow_units_name(units: FlowUnits) -> &'static str {
+    FLOW_UNITS
+        .iter()
+        .find(|(_, u)| *u == units)
+        .map_or("?", |(n, _)| n)
+}
+
+/// Formats seconds as `H:MM:SS`.
+fn clock(seconds: i32) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// A single-line progress bar on stderr.
+struct Progress {
+    label: &'static str,
+    total: i64,
+    hidden: bool,
+    shown: i64,
+}
+
+impl Progress {
+    const WIDTH: i64 = 30;
+
+    fn new(label: &'static str, total: i32, hidden: bool) -> Self {
+        Progress {
+            label,
+            total: total as i64,
+            hidden,
+            shown: -1,
+        }
+    }
+
+    fn update(&mut self, t: i64) {
+        let percent = if self.total > 0 {
+            (t * 100 / self.total).clamp(0, 100)
+        } else {
+            100
+        };
+        if self.hidden || percent == self.shown {
+            return;
+        }
+        self.shown = percent;
+        let filled = (percent * Self::WIDTH / 100) as usize;
+        eprint!(
+            "\r{} [{}{}] {:3}%",
+            self.label,
+            "#".repeat(filled),
+            "-".repeat(Self::WIDTH as usize - filled),
+            percent
+        );
+    }
+
+    fn finish(&mut self) {
+        if !self.hidden {
+            self.update(self.total);
+            eprintln!();
+        }
+    }
+}
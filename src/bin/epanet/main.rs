@@ -0,0 +1,172 @@
+//! `epanet` command-line tool.
+//!
+//! A thin front end over the library for users who do not write Rust. Each
+//! subcommand lives in [`commands`]; this file handles argument parsing, usage
+//! text and exit codes (0 success, 1 failure or differences found, 2 usage error).
+
+mod commands;
+
+use epanet::epanet_error::EPANETError;
+use std::fmt::{self, Display};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: epanet <COMMAND> [ARGS]
+
+Commands:
+  run <INP> [--report FILE] [--output FILE] [--quiet]
+      Run a hydraulic and water quality simulation and write the report.
+  info <INP>
+      Print element counts, options and time parameters.
+  export <INP> --output FILE [--format csv|json|parquet] [--layout long|wide]
+         [--time seconds|clock] [--nodes PROPS] [--links PROPS]
+      Simulate and export results at each reporting time.
+      Node properties: pressure, head, demand, quality (default pressure,head,demand).
+      Link properties: flow, velocity, headloss, status, setting, quality
+      (default flow,velocity).
+  validate <INP>
+      Check the model for input errors and hydraulic warnings.
+  convert <INP> <OUT_INP> [--flow-units UNITS]
+      Re-save a model, optionally converting its flow units (CFS, GPM, MGD, IMGD,
+      AFD, LPS, LPM, MLD, CMH, CMD, CMS).
+  diff <A_INP> <B_INP> [--format text|json] [--tolerance REL] [--no-renames]
+      Compare two models element by element. Numbers within the relative
+      tolerance (default 1e-6) are equal; elements at the same position with new
+      IDs are reported as renamed unless --no-renames is given.
+  help
+      Print this message.";
+
+/// Errors returned by subcommands.
+#[derive(Debug)]
+pub enum CliError {
+    /// The command line was malformed; usage is printed and the exit code is 2.
+    Usage(String),
+    /// The command failed; the exit code is 1.
+    Failed(String),
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(msg) | CliError::Failed(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl From<EPANETError> for CliError {
+    fn from(error: EPANETError) -> Self {
+        CliError::Failed(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(error: std::io::Error) -> Self {
+        CliError::Failed(error.to_string())
+    }
+}
+
+pub type CliResult = Result<ExitCode, CliError>;
+
+/// Parsed arguments of a subcommand: positionals, `--name value` options and
+/// `--name` flags.
+pub struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    /// Parses `args`, accepting only the given option and flag names (without the
+    /// leading `--`).
+    pub fn parse(
+        args: impl Iterator<Item = String>,
+        options: &[&str],
+        flags: &[&str],
+    ) -> Result<Args, CliError> {
+        let mut parsed = Args {
+            positional: Vec::new(),
+            options: Vec::new(),
+            flags: Vec::new(),
+        };
+        let mut args = args;
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                parsed.positional.push(arg);
+                continue;
+            };
+            if flags.contains(&name) {
+                parsed.flags.push(name.to_string());
+            } else if options.contains(&name) {
+                let value = args
+                    .next()
+                    .ok_or_else(|| CliError::Usage(format!("--{name} requires a value")))?;
+                parsed.options.push((name.to_string(), value));
+            } else {
+                return Err(CliError::Usage(format!("unknown option --{name}")));
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Returns the positional argument at `index`, or a usage error naming `what`.
+    pub fn positional(&self, index: usize, what: &str) -> Result<&str, CliError> {
+        self.positional
+            .get(index)
+            .map(String::as_str)
+            .ok_or_else(|| CliError::Usage(format!("missing {what}")))
+    }
+
+    /// Fails if more than `count` positional arguments were given.
+    pub fn expect_positionals(&self, count: usize) -> Result<(), CliError> {
+        match self.positional.get(count) {
+            Some(extra) => Err(CliError::Usage(format!("unexpected argument {extra}"))),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the last value given for option `name`.
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns `true` if flag `name` was given.
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let Some(command) = args.next() else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+    let result = match command.as_str() {
+        "run" => commands::run(args),
+        "info" => commands::info(args),
+        "export" => commands::export(args),
+        "validate" => commands::validate(args),
+        "convert" => commands::convert(args),
+        "diff" => commands::diff(args),
+        "help" | "-h" | "--help" => {
+            println!("{USAGE}");
+            Ok(ExitCode::SUCCESS)
+        }
+        other => Err(CliError::Usage(format!("unknown command {other}"))),
+    };
+    match result {
+        Ok(code) => code,
+        Err(CliError::Usage(msg)) => {
+            eprintln!("error: {msg}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+        Err(CliError::Failed(msg)) => {
+            eprintln!("error: {msg}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -5,10 +5,7 @@ mod tests {
     use super::*;
     use std::ffi::c_char;
     use std::sync::atomic::{AtomicU32, Ordering};
-    use std::{
-        ffi::{CStr, CString},
-        i32,
-    };
+    use std::ffi::{CStr, CString};
 
     static BINDINGS_TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
 
@@ -49,39 +46,39 @@ mod tests {
             assert_eq!(error, 0);
 
             let rpt = temp_rpt_path();
-            let netPtr = CString::new(DATA_PATH_NET1).unwrap();
-            let pathPtr = CString::new(rpt).unwrap();
-            let outPtr = CString::new(DATA_PATH_OUT).unwrap();
-            let error = EN_open(ph, netPtr.as_ptr(), pathPtr.as_ptr(), outPtr.as_ptr());
+            let net_ptr = CString::new(DATA_PATH_NET1).unwrap();
+            let path_ptr = CString::new(rpt).unwrap();
+            let out_ptr = CString::new(DATA_PATH_OUT).unwrap();
+            let error = EN_open(ph, net_ptr.as_ptr(), path_ptr.as_ptr(), out_ptr.as_ptr());
             assert_eq!(error, 0);
 
             let node1 = "N1";
             let node2 = "N2";
-            let node1CStr = CString::new(node1).unwrap();
-            let node2CStr = CString::new(node2).unwrap();
+            let node1_cstr = CString::new(node1).unwrap();
+            let node2_cstr = CString::new(node2).unwrap();
             let mut index: i32 = 0;
             let error = EN_addnode(
                 ph,
-                node1CStr.as_ptr(),
+                node1_cstr.as_ptr(),
                 EN_NodeType_EN_JUNCTION as i32,
                 &mut index as *mut i32,
             );
             assert_eq!(error, 0);
             let error = EN_addnode(
                 ph,
-                node2CStr.as_ptr(),
+                node2_cstr.as_ptr(),
                 EN_NodeType_EN_JUNCTION as i32,
                 &mut index as *mut i32,
             );
             assert_eq!(error, 0);
 
-            let error = EN_getnodeindex(ph, node1CStr.as_ptr(), &mut index as *mut i32);
+            let error = EN_getnodeindex(ph, node1_cstr.as_ptr(), &mut index as *mut i32);
             let mut out_id: Vec<c_char> = vec![0; EN_SizeLimits_EN_MAXMSG as usize];
             EN_getnodeid(ph, 10, out_id.as_mut_ptr());
             assert_eq!(error, 0);
             assert_eq!(index, 10);
             assert_eq!(CStr::from_ptr(out_id.as_ptr()).to_str().unwrap(), "N1");
-            let error = EN_getnodeindex(ph, node2CStr.as_ptr(), &mut index as *mut i32);
+            let error = EN_getnodeindex(ph, node2_cstr.as_ptr(), &mut index as *mut i32);
             assert_eq!(error, 0);
             let error = EN_deletenode(ph, index, EN_ActionCodeType_EN_UNCONDITIONAL as i32);
             assert_eq!(error, 0);
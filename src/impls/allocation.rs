@@ -0,0 +1,358 @@
+//! Demand allocation API methods for EPANET.
+//!
+//! This module assigns customer billing records to the nearest junction or
+//! pipe and adds their consumption as named demand categories.
+
+use crate::epanet_error::*;
+use crate::impls::gis::{distance, lerp, project};
+use crate::types::allocation::*;
+use crate::types::index::NodeIndex;
+use crate::types::node::NodeType;
+use crate::EPANET;
+use std::collections::BTreeMap;
+
+/// Seconds in the 365-day year over which annual consumption is averaged.
+const SECONDS_PER_YEAR: f64 = 365.0 * 86400.0;
+
+/// A pipe as a path between its end nodes; ends that are not junctions are
+/// `None`.
+struct PipePath {
+    points: Vec<(f64, f64)>,
+    start: Option<NodeIndex>,
+    end: Option<NodeIndex>,
+}
+
+/// ## Demand Allocation APIs
+impl EPANET {
+    /// Allocates the average flow of each billing record to the nearest
+    /// junction or pipe ends, adding it to a demand category named after the
+    /// record's customer category.
+    ///
+    /// A junction that already has a demand category of that name has its base
+    /// demand increased, keeping its pattern; otherwise the category is added
+    /// with the pattern given for it in [`AllocationOptions::patterns`].
+    /// Junctions and pipes whose ends have no coordinates are not considered.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 202 if a record has a negative or non-finite consumption
+    /// or coordinate, or error 205 if a category pattern does not exist; in
+    /// both cases no demand is added. If updating a junction's demands fails,
+    /// the demands already allocated are restored and the error is returned.
+    pub fn allocate_demands(
+        &self,
+        records: &[BillingRecord],
+        options: &AllocationOptions,
+    ) -> Result<AllocationReport> {
+        if let Some(record) = records.iter().find(|r| {
+            !(r.annual_consumption.is_finite() && r.x.is_finite() && r.y.is_finite())
+                || r.annual_consumption < 0.0
+        }) {
+            return Err(EPANETError::from(202).with_context(format!(
+                "invalid billing record '{}': consumption {} at ({}, {})",
+                record.account, record.annual_consumption, record.x, record.y
+            )));
+        }
+        for (category, pattern) in &options.patterns {
+            if !pattern.is_empty() {
+                self.get_pattern_index(pattern).map_err(|e| {
+                    e.with_context(format!("pattern '{pattern}' of category '{category}'"))
+                })?;
+            }
+        }
+
+        let scale = self.get_flow_units()?.seconds_per_unit() / SECONDS_PER_YEAR;
+        let junctions: Vec<(NodeIndex, (f64, f64))> = self
+            .iter_nodes_of_type(NodeType::Junction)?
+            .filter_map(|node| {
+                let index = match node {
+                    Ok(node) => node.index(),
+                    Err(e) => return Some(Err(e)),
+                };
+                match self.get_coordinates(index) {
+                    Ok(position) => Some(Ok((index, position))),
+                    Err(e) if e.code == 254 => None,
+                    Err(e) => Some(Err(e)),
+                }
+            })
+            .collect::<Result<_>>()?;
+        let pipes = match options.method {
+            AllocationMethod::NearestJunction => Vec::new(),
+            AllocationMethod::NearestPipe => self.pipe_paths()?,
+        };
+
+        let mut shares: BTreeMap<(NodeIndex, String), (f64, usize)> = BTreeMap::new();
+        let mut unallocated = Vec::new();
+        for record in records {
+            let point = (record.x, record.y);
+            let placement = match options.method {
+                AllocationMethod::NearestJunction => nearest_junction(&junctions, point),
+                AllocationMethod::NearestPipe => nearest_pipe(&pipes, point),
+            };
+            let placement = placement.filter(|(d, _)| options.max_distance.is_none_or(|m| *d <= m));
+            let Some((_, nodes)) = placement else {
+                unallocated.push(record.account.clone());
+                continue;
+            };
+            for (node, share) in nodes {
+                let entry = shares.entry((node, record.category.clone())).or_default();
+                entry.0 += record.annual_consumption * scale * share;
+                entry.1 += 1;
+            }
+        }
+
+        let mut demands = Vec::with_capacity(shares.len());
+        let mut applied = Vec::with_capacity(shares.len());
+        for ((node, category), (demand, records)) in shares {
+            let result = self.get_node_id(node).and_then(|node_id| {
+                Ok((
+                    node_id,
+                    self.apply_demand(node, &category, demand, options)?,
+                ))
+            });
+            let (node_id, (index, base)) = match result {
+                Ok(done) => done,
+                Err(error) => {
+                    for (node, index, base) in applied.into_iter().rev() {
+                        let _ = match base {
+                            Some(base) => self.set_base_demand(node, index, base),
+                            None => self.delete_demand(node, index),
+                        };
+                    }
+                    return Err(error.with_context(format!(
+                        "failed to allocate category '{category}' to node {}; earlier demands restored",
+                        node.0
+                    )));
+                }
+            };
+            applied.push((node, index, base));
+            demands.push(AllocatedDemand {
+                node,
+                node_id,
+                category,
+                demand,
+                records,
+            });
+        }
+        Ok(AllocationReport {
+            demands,
+            unallocated,
+        })
+    }
+
+    // Helper methods - Internal API
+
+    /// Adds `demand` to the `category` demand of `node`, adding the category if
+    /// the node has none of that name. Returns the demand index with the
+    /// previous base demand, or `None` if the category was added.
+    fn apply_demand(
+        &self,
+        node: NodeIndex,
+        category: &str,
+        demand: f64,
+        options: &AllocationOptions,
+    ) -> Result<(i32, Option<f64>)> {
+        match self.get_demand_index(node, category) {
+            Ok(existing) => {
+                let base = self.get_base_demand(node, existing)?;
+                self.set_base_demand(node, existing, base + demand)?;
+                Ok((existing, Some(base)))
+            }
+            // No demand category of that name
+            Err(e) if e.code == 253 => {
+                let pattern = options
+                    .patterns
+                    .iter()
+                    .find(|(c, _)| c == category)
+                    .map_or("", |(_, p)| p.as_str());
+                let added = self.get_demand_count(node)? + 1;
+                self.add_demand(node, demand, pattern, category)?;
+                Ok((added, None))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Paths of the pipes with at least one junction end, through their
+    /// vertices.
+    fn pipe_paths(&self) -> Result<Vec<PipePath>> {
+        let mut paths = Vec::new();
+        for pipe in self.pipes()? {
+            let (start, end) = match (
+                self.get_coordinates(pipe.from_node),
+                self.get_coordinates(pipe.to_node),
+            ) {
+                (Ok(start), Ok(end)) => (start, end),
+                (Err(e), _) | (_, Err(e)) if e.code != 254 => return Err(e),
+                _ => continue,
+            };
+            let junction = |node: NodeIndex| -> Result<Option<NodeIndex>> {
+                Ok((self.get_node_type(node)? == NodeType::Junction).then_some(node))
+            };
+            let (start_node, end_node) = (junction(pipe.from_node)?, junction(pipe.to_node)?);
+            if start_node.is_none() && end_node.is_none() {
+                continue;
+            }
+            let mut points = vec![start];
+            points.extend(pipe.vertices()?);
+            points.push(end);
+            paths.push(PipePath {
+                points,
+                start: start_node,
+                end: end_node,
+            });
+        }
+        Ok(paths)
+    }
+}
+
+/// Distance to the junction closest to `point`, with that junction taking the
+/// whole demand.
+fn nearest_junction(
+    junctions: &[(NodeIndex, (f64, f64))],
+    point: (f64, f64),
+) -> Option<(f64, Vec<(NodeIndex, f64)>)> {
+    junctions
+        .iter()
+        .map(|(node, position)| (distance(*position, point), *node))
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(d, node)| (d, vec![(node, 1.0)]))
+}
+
+/// Distance to the pipe closest to `point`, with the share of the demand taken
+/// by each of its junction ends.
+fn nearest_pipe(pipes: &[PipePath], point: (f64, f64)) -> Option<(f64, Vec<(NodeIndex, f64)>)> {
+    let mut best: Option<(f64, &PipePath, f64)> = None;
+    for pipe in pipes {
+        let (mut along, mut length) = (0.0, 0.0);
+        let mut closest = f64::INFINITY;
+        for segment in pipe.points.windows(2) {
+            let t = project(segment[0], segment[1], point);
+            let d = distance(lerp(segment[0], segment[1], t), point);
+            let segment_length = distance(segment[0], segment[1]);
+            if d < closest {
+                closest = d;
+                along = length + t * segment_length;
+            }
+            length += segment_length;
+        }
+        let fraction = if length > 0.0 { along / length } else { 0.5 };
+        if best.is_none_or(|(d, _, _)| closest < d) {
+            best = Some((closest, pipe, fraction));
+        }
+    }
+    let (d, pipe, fraction) = best?;
+    let nodes = match (pipe.start, pipe.end) {
+        (Some(start), Some(end)) => vec![(start, 1.0 - fraction), (end, fraction)],
+        (Some(node), None) | (None, Some(node)) => vec![(node, 1.0)],
+        (None, None) => return None,
+    };
+    Some((d, nodes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use crate::types::index::PatternIndex;
+    use rstest::rstest;
+
+    /// A record consuming one gallon per minute on average.
+    fn record(account: &str, x: f64, y: f64, category: &str) -> BillingRecord {
+        BillingRecord {
+            account: account.into(),
+            x,
+            y,
+            annual_consumption: 525_600.0,
+            category: category.into(),
+        }
+    }
+
+    fn records() -> Vec<BillingRecord> {
+        vec![
+            record("A", 31.0, 69.0, "Residential"),
+            record("B", 35.0, 71.0, "Commercial"),
+            record("C", 500.0, 500.0, "Residential"),
+            record("D", 49.0, 85.0, "Residential"),
+        ]
+    }
+
+    #[rstest]
+    fn test_allocate_to_nearest_junction(ph: EPANET) {
+        let options = AllocationOptions {
+            patterns: vec![("Residential".into(), "1".into())],
+            max_distance: Some(20.0),
+            ..AllocationOptions::default()
+        };
+        let report = ph.allocate_demands(&records(), &options).unwrap();
+        assert_eq!(report.unallocated, vec!["C"]);
+        assert!((report.total() - 3.0).abs() < 1e-9);
+        assert!((report.node_total("11") - 2.0).abs() < 1e-9);
+        assert!((report.node_total("12") - 1.0).abs() < 1e-9);
+        assert!((report.category_total("Residential") - 2.0).abs() < 1e-9);
+
+        let node = ph.get_node_index("11").unwrap();
+        let residential = ph.get_demand_index(node, "Residential").unwrap();
+        assert!(residential > 1);
+        assert_eq!(
+            ph.get_demand_pattern(node, residential).unwrap(),
+            ph.get_pattern_index("1").unwrap()
+        );
+        let commercial = ph.get_demand_index(node, "Commercial").unwrap();
+        assert_eq!(
+            ph.get_demand_pattern(node, commercial).unwrap(),
+            PatternIndex(0)
+        );
+
+        // A second allocation adds to the existing categories
+        let report = ph.allocate_demands(&records()[..1], &options).unwrap();
+        assert_eq!(report.demands.len(), 1);
+        assert_eq!(ph.get_demand_count(node).unwrap(), 3);
+        assert!((ph.get_base_demand(node, residential).unwrap() - 2.0).abs() < 1e-9);
+    }
+
+    #[rstest]
+    fn test_allocate_to_nearest_pipe(ph: EPANET) {
+        let options = AllocationOptions {
+            method: AllocationMethod::NearestPipe,
+            ..AllocationOptions::default()
+        };
+        let report = ph.allocate_demands(&records(), &options).unwrap();
+        assert!(report.unallocated.is_empty());
+        // B is a quarter of the way along pipe 11 from junction 11 to 12
+        let commercial: Vec<(&str, f64)> = report
+            .demands
+            .iter()
+            .filter(|d| d.category == "Commercial")
+            .map(|d| (d.node_id.as_str(), d.demand))
+            .collect();
+        assert_eq!(commercial.len(), 2);
+        assert_eq!(commercial[0].0, "11");
+        assert!((commercial[0].1 - 0.75).abs() < 1e-9);
+        assert!((commercial[1].1 - 0.25).abs() < 1e-9);
+        // D is next to pipe 110, whose other end is tank 2
+        assert!(report.node_total("2") == 0.0);
+        assert!((report.total() - 4.0).abs() < 1e-9);
+    }
+
+    #[rstest]
+    fn test_allocate_rejects_invalid_input(ph: EPANET) {
+        let mut invalid = records();
+        invalid[1].annual_consumption = -1.0;
+        let err = ph
+            .allocate_demands(&invalid, &AllocationOptions::default())
+            .unwrap_err();
+        assert_eq!(err.code, 202);
+
+        let options = AllocationOptions {
+            patterns: vec![("Residential".into(), "missing".into())],
+            ..AllocationOptions::default()
+        };
+        assert_eq!(
+            ph.allocate_demands(&records(), &options).unwrap_err().code,
+            205
+        );
+        let node = ph.get_node_index("11").unwrap();
+        assert_eq!(ph.get_demand_count(node).unwrap(), 1);
+    }
+}
@@ -0,0 +1,150 @@
+//! Columnar network snapshot API methods for EPANET.
+//!
+//! This module reads the whole network into a [`NetworkArrays`] snapshot, using
+//! the bulk `EN_getnodevalues` / `EN_getlinkvalues` calls where the engine
+//! offers them.
+
+use crate::epanet_error::*;
+use crate::types::arrays::*;
+use crate::types::link::LinkProperty;
+use crate::types::node::NodeProperty;
+use crate::types::CountType;
+use crate::EPANET;
+
+/// ## Network Array APIs
+impl EPANET {
+    /// Reads the network into a struct-of-arrays snapshot aligned by element
+    /// index, with the node-link incidence matrix in CSR form.
+    ///
+    /// The snapshot is a copy; later changes to the model are not reflected.
+    pub fn network_arrays(&self) -> Result<NetworkArrays> {
+        let node_count = self.get_count(CountType::NodeCount)?;
+        let link_count = self.get_count(CountType::LinkCount)?;
+
+        let mut arrays = NetworkArrays {
+            elevations: self.get_node_values(NodeProperty::Elevation)?,
+            base_demands: self.get_node_values(NodeProperty::BaseDemand)?,
+            lengths: self.get_link_values(LinkProperty::Length)?,
+            diameters: self.get_link_values(LinkProperty::Diameter)?,
+            roughness: self.get_link_values(LinkProperty::Roughness)?,
+            statuses: self
+                .get_link_values(LinkProperty::InitStatus)?
+                .into_iter()
+                .map(|status| status as i32)
+                .collect(),
+            ..NetworkArrays::default()
+        };
+        for index in 1..=node_count {
+            arrays.node_ids.push(self.get_node_id(index)?);
+            arrays.node_types.push(self.get_node_type(index)? as i32);
+            let (x, y) = match self.get_coordinates(index) {
+                Ok(position) => position,
+                // Node with no coordinates
+                Err(e) if e.code == 254 => (f64::NAN, f64::NAN),
+                Err(e) => return Err(e),
+            };
+            arrays.x.push(x);
+            arrays.y.push(y);
+        }
+
+        let mut entries = Vec::with_capacity(2 * link_count as usize);
+        for index in 1..=link_count {
+            arrays.link_ids.push(self.get_link_id(index)?);
+            arrays.link_types.push(self.get_link_type(index)? as i32);
+            let (start, end) = self.get_link_nodes(index)?;
+            arrays.start_nodes.push(start.0 - 1);
+            arrays.end_nodes.push(end.0 - 1);
+            let link = index as usize - 1;
+            entries.push((start.offset(), link, -1.0));
+            entries.push((end.offset(), link, 1.0));
+        }
+        arrays.incidence =
+            CsrMatrix::from_triplets(node_count as usize, link_count as usize, entries)?;
+        Ok(arrays)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use crate::types::link::LinkType;
+    use crate::types::node::NodeType;
+    use rstest::rstest;
+
+    #[rstest]
+    fn test_network_arrays(ph: EPANET) {
+        let arrays = ph.network_arrays().unwrap();
+        assert_eq!(arrays.node_count(), 11);
+        assert_eq!(arrays.link_count(), 13);
+        assert_eq!(arrays.node_ids[10], "2");
+        assert_eq!(arrays.node_types[10], NodeType::Tank as i32);
+        assert_eq!(
+            arrays.elevations,
+            ph.get_node_values(NodeProperty::Elevation).unwrap()
+        );
+        assert_eq!(arrays.base_demands[1], 150.0);
+        assert_eq!((arrays.x[0], arrays.y[0]), ph.get_coordinates(1).unwrap());
+
+        // Pipe 110 runs from tank 2 to junction 12
+        let pipe = ph.get_link_index("110").unwrap().offset();
+        assert_eq!(arrays.link_ids[pipe], "110");
+        assert_eq!(arrays.link_types[pipe], LinkType::Pipe as i32);
+        assert_eq!(arrays.start_nodes[pipe], 10);
+        assert_eq!(arrays.end_nodes[pipe], 2);
+        assert_eq!(
+            (arrays.lengths[pipe], arrays.diameters[pipe]),
+            (200.0, 18.0)
+        );
+
+        // A node without coordinates is placed at NaN
+        ph.add_node("J99", NodeType::Junction).unwrap();
+        let arrays = ph.network_arrays().unwrap();
+        let node = ph.get_node_index("J99").unwrap().offset();
+        assert!(arrays.x[node].is_nan() && arrays.y[node].is_nan());
+    }
+
+    #[rstest]
+    fn test_incidence_matrix(ph: EPANET) {
+        let arrays = ph.network_arrays().unwrap();
+        let incidence = &arrays.incidence;
+        assert_eq!((incidence.rows, incidence.cols), (11, 13));
+        assert_eq!(incidence.nnz(), 26);
+        for link in 0..arrays.link_count() {
+            assert_eq!(incidence.get(arrays.start_nodes[link] as usize, link), -1.0);
+            assert_eq!(incidence.get(arrays.end_nodes[link] as usize, link), 1.0);
+        }
+        // Junction 12 joins pipes 11, 12, 110 and 112
+        let (links, values) = incidence.row(2);
+        let ids: Vec<&str> = links
+            .iter()
+            .map(|&l| arrays.link_ids[l as usize].as_str())
+            .collect();
+        assert_eq!(ids, vec!["11", "12", "110", "112"]);
+        assert_eq!(values, [1.0, -1.0, 1.0, -1.0]);
+        // Every column sums to zero
+        let sums = (0..incidence.rows).fold(vec![0.0; incidence.cols], |mut sums, row| {
+            let (links, values) = incidence.row(row);
+            links
+                .iter()
+                .zip(values)
+                .for_each(|(&l, v)| sums[l as usize] += v);
+            sums
+        });
+        assert!(sums.iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_csr_from_triplets() {
+        let matrix =
+            CsrMatrix::from_triplets(2, 3, vec![(1, 2, 1.0), (0, 1, 2.0), (1, 2, 0.5)]).unwrap();
+        assert_eq!(matrix.nnz(), 2);
+        assert_eq!(matrix.row_offsets, vec![0, 1, 2]);
+        assert_eq!(matrix.get(1, 2), 1.5);
+        assert_eq!(matrix.get(0, 0), 0.0);
+
+        let err = CsrMatrix::from_triplets(2, 3, vec![(2, 0, 1.0)]).unwrap_err();
+        assert_eq!(err.code, 250);
+        assert!(CsrMatrix::from_triplets(2, 3, vec![(0, 3, 1.0)]).is_err());
+    }
+}
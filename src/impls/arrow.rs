@@ -0,0 +1,376 @@
+//! Apache Arrow API methods for EPANET.
+//!
+//! This module contains methods, available with the `arrow` feature, for
+//! recording simulation results and converting them and the static network
+//! attributes to Arrow [`RecordBatch`]es tagged with the project's units.
+
+use crate::epanet_error::*;
+use crate::types::arrow::*;
+use crate::types::export::{is_report_time, ExportOptions};
+use crate::types::link::LinkKind;
+use crate::types::node::NodeKind;
+use crate::types::options::{FlowUnits, Option, PressUnits, QualityType};
+use crate::EPANET;
+use arrow_array::{ArrayRef, Float64Array, Int32Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field};
+use num_traits::FromPrimitive;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// ## Arrow APIs
+impl EPANET {
+    /// Runs an extended period simulation and records the properties and elements
+    /// selected by `options` at each step.
+    ///
+    /// Steps are filtered by [`ExportOptions::report_times_only`] exactly as
+    /// [`export_results`](EPANET::export_results) does; the format, layout and time
+    /// index are chosen later in [`RecordedResults::to_record_batch`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if an ID does not exist or the solver fails.
+    pub fn record_results(&self, options: &ExportOptions) -> Result<RecordedResults> {
+        let (nodes, links) = self.export_elements(options)?;
+        let (report_start, report_step) = self.report_times()?;
+        let mut results = RecordedResults {
+            times: Vec::new(),
+            node_ids: nodes.iter().map(|(_, id)| id.clone()).collect(),
+            link_ids: links.iter().map(|(_, id)| id.clone()).collect(),
+            node_properties: options.node_properties.clone(),
+            link_properties: options.link_properties.clone(),
+            node_values: Vec::new(),
+            link_values: Vec::new(),
+            metadata: self.units_metadata()?,
+        };
+        self.run_export_simulation(self.exports_quality(options)?, |project, t| {
+            if options.report_times_only && !is_report_time(t, report_start, report_step) {
+                return Ok(());
+            }
+            results.times.push(t);
+            for (index, _) in &nodes {
+                for property in &options.node_properties {
+                    results
+                        .node_values
+                        .push(project.get_node_value(*index, *property)?);
+                }
+            }
+            for (index, _) in &links {
+                for property in &options.link_properties {
+                    results
+                        .link_values
+                        .push(project.get_link_value(*index, *property)?);
+                }
+            }
+            Ok(())
+        })?;
+        Ok(results)
+    }
+
+    /// Static node attributes, one row per node in index order.
+    ///
+    /// Columns: `index`, `id`, `type`, `elevation`, `x`, `y`, and the nullable
+    /// `base_demand`, `demand_pattern` (junctions) and `init_level`, `min_level`,
+    /// `max_level`, `diameter` (tanks). Coordinates are null when undefined.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if a node cannot be read.
+    pub fn node_attributes_batch(&self) -> Result<RecordBatch> {
+        let nodes = self.nodes()?;
+        let mut index = Vec::new();
+        let mut id = Vec::new();
+        let mut kind = Vec::new();
+        let mut elevation = Vec::new();
+        let (mut x, mut y) = (Vec::new(), Vec::new());
+        let (mut base_demand, mut demand_pattern) = (Vec::new(), Vec::new());
+        let (mut init_level, mut min_level, mut max_level, mut diameter) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for node in &nodes {
+            index.push(node.index().0);
+            id.push(node.id.clone());
+            kind.push(format!("{:?}", node.node_type()));
+            let coordinates = self.get_coordinates(node.index()).ok();
+            x.push(coordinates.map(|c| c.0));
+            y.push(coordinates.map(|c| c.1));
+            let (mut demand, mut pattern) = (None, None);
+            let mut tank = [None; 4];
+            match &node.kind {
+                NodeKind::Junction(data) => {
+                    elevation.push(data.elevation);
+                    demand = Some(data.demand);
+                    pattern = Some(data.demand_pattern.clone());
+                }
+                NodeKind::Tank(data) => {
+                    elevation.push(data.elevation);
+                    tank = [
+                        Some(data.init_level),
+                        Some(data.min_level),
+                        Some(data.max_level),
+                        Some(data.diameter),
+                    ];
+                }
+                NodeKind::Reservoir(data) => elevation.push(data.elevation),
+            }
+            base_demand.push(demand);
+            demand_pattern.push(pattern);
+            init_level.push(tank[0]);
+            min_level.push(tank[1]);
+            max_level.push(tank[2]);
+            diameter.push(tank[3]);
+        }
+
+        let fields = vec![
+            Field::new("index", DataType::Int32, false),
+            Field::new("id", DataType::Utf8, false),
+            Field::new("type", DataType::Utf8, false),
+            Field::new("elevation", DataType::Float64, false),
+            Field::new("x", DataType::Float64, true),
+            Field::new("y", DataType::Float64, true),
+            Field::new("base_demand", DataType::Float64, true),
+            Field::new("demand_pattern", DataType::Utf8, true),
+            Field::new("init_level", DataType::Float64, true),
+            Field::new("min_level", DataType::Float64, true),
+            Field::new("max_level", DataType::Float64, true),
+            Field::new("diameter", DataType::Float64, true),
+        ];
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int32Array::from(index)),
+            Arc::new(StringArray::from(id)),
+            Arc::new(StringArray::from(kind)),
+            Arc::new(Float64Array::from(elevation)),
+            Arc::new(Float64Array::from(x)),
+            Arc::new(Float64Array::from(y)),
+            Arc::new(Float64Array::from(base_demand)),
+            Arc::new(StringArray::from(demand_pattern)),
+            Arc::new(Float64Array::from(init_level)),
+            Arc::new(Float64Array::from(min_level)),
+            Arc::new(Float64Array::from(max_level)),
+            Arc::new(Float64Array::from(diameter)),
+        ];
+        record_batch(fields, columns, &self.units_metadata()?)
+    }
+
+    /// Static link attributes, one row per link in index order.
+    ///
+    /// Columns: `index`, `id`, `type`, `from_node`, `to_node` (node IDs),
+    /// `initial_status`, and the nullable `diameter` (pipes and valves), `length`,
+    /// `roughness`, `minor_loss` (pipes), `power`, `speed` (pumps) and `setting`
+    /// (valves).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if a link cannot be read.
+    pub fn link_attributes_batch(&self) -> Result<RecordBatch> {
+        let links = self.links()?;
+        let mut index = Vec::new();
+        let mut id = Vec::new();
+        let mut kind = Vec::new();
+        let (mut from_node, mut to_node) = (Vec::new(), Vec::new());
+        let mut status = Vec::new();
+        let mut diameter = Vec::new();
+        let (mut length, mut roughness, mut minor_loss) = (Vec::new(), Vec::new(), Vec::new());
+        let (mut power, mut speed, mut setting) = (Vec::new(), Vec::new(), Vec::new());
+        for link in &links {
+            index.push(link.index().0);
+            id.push(link.id.clone());
+            kind.push(format!("{:?}", self.get_link_type(link.index())?));
+            from_node.push(self.get_node_id(link.from_node)?);
+            to_node.push(self.get_node_id(link.to_node)?);
+            status.push(format!("{:?}", link.status));
+            let mut pipe = [None; 3];
+            let mut pump = [None; 2];
+            let mut valve = None;
+            match &link.kind {
+                LinkKind::Pipe(data) | LinkKind::CvPipe(data) => {
+                    diameter.push(Some(data.diameter));
+                    pipe = [
+                        Some(data.length),
+                        Some(data.roughness),
+                        Some(data.minor_loss),
+                    ];
+                }
+                LinkKind::Pump(data) => {
+                    diameter.push(None);
+                    pump = [Some(data.power), Some(data.speed)];
+                }
+                LinkKind::Valve(data) => {
+                    diameter.push(Some(data.diameter));
+                    valve = Some(data.setting);
+                }
+            }
+            length.push(pipe[0]);
+            roughness.push(pipe[1]);
+            minor_loss.push(pipe[2]);
+            power.push(pump[0]);
+            speed.push(pump[1]);
+            setting.push(valve);
+        }
+
+        let fields = vec![
+            Field::new("index", DataType::Int32, false),
+            Field::new("id", DataType::Utf8, false),
+            Field::new("type", DataType::Utf8, false),
+            Field::new("from_node", DataType::Utf8, false),
+            Field::new("to_node", DataType::Utf8, false),
+            Field::new("initial_status", DataType::Utf8, false),
+            Field::new("diameter", DataType::Float64, true),
+            Field::new("length", DataType::Float64, true),
+            Field::new("roughness", DataType::Float64, true),
+            Field::new("minor_loss", DataType::Float64, true),
+            Field::new("power", DataType::Float64, true),
+            Field::new("speed", DataType::Float64, true),
+            Field::new("setting", DataType::Float64, true),
+        ];
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int32Array::from(index)),
+            Arc::new(StringArray::from(id)),
+            Arc::new(StringArray::from(kind)),
+            Arc::new(StringArray::from(from_node)),
+            Arc::new(StringArray::from(to_node)),
+            Arc::new(StringArray::from(status)),
+            Arc::new(Float64Array::from(diameter)),
+            Arc::new(Float64Array::from(length)),
+            Arc::new(Float64Array::from(roughness)),
+            Arc::new(Float64Array::from(minor_loss)),
+            Arc::new(Float64Array::from(power)),
+            Arc::new(Float64Array::from(speed)),
+            Arc::new(Float64Array::from(setting)),
+        ];
+        record_batch(fields, columns, &self.units_metadata()?)
+    }
+
+    // Helper methods - Internal API
+
+    /// Schema metadata describing the project's flow, pressure and quality units.
+    fn units_metadata(&self) -> Result<HashMap<String, String>> {
+        let flow_units = match self.get_flow_units()? {
+            FlowUnits::Cfs => "CFS",
+            FlowUnits::Gpm => "GPM",
+            FlowUnits::Mgd => "MGD",
+            FlowUnits::Imgd => "IMGD",
+            FlowUnits::Afd => "AFD",
+            FlowUnits::Lps => "LPS",
+            FlowUnits::Lpm => "LPM",
+            FlowUnits::Mld => "MLD",
+            FlowUnits::Cmh => "CMH",
+            FlowUnits::Cmd => "CMD",
+            FlowUnits::Cms => "CMS",
+        };
+        let pressure_units = match PressUnits::from_i32(self.get_option(Option::PressUnits)? as i32)
+        {
+            Some(PressUnits::Psi) => "psi",
+            Some(PressUnits::Kpa) => "kPa",
+            Some(PressUnits::Meters) => "m",
+            None => "",
+        };
+        let quality = self.get_quality_info()?;
+        let quality_units = match quality.quality_type {
+            QualityType::None => String::new(),
+            QualityType::Chem => quality.chem_units.clone(),
+            QualityType::Age => "hrs".to_string(),
+            QualityType::Trace => "%".to_string(),
+        };
+        Ok(HashMap::from([
+            ("epanet:flow_units".to_string(), flow_units.to_string()),
+            (
+                "epanet:pressure_units".to_string(),
+                pressure_units.to_string(),
+            ),
+            (
+                "epanet:quality_type".to_string(),
+                format!("{:?}", quality.quality_type),
+            ),
+            ("epanet:quality_units".to_string(), quality_units),
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use crate::types::export::{ExportLayout, TimeIndex};
+    use crate::types::link::LinkProperty;
+    use crate::types::node::NodeProperty;
+    use arrow_array::Array;
+    use rstest::rstest;
+
+    fn options() -> ExportOptions {
+        ExportOptions {
+            node_properties: vec![NodeProperty::Pressure, NodeProperty::Quality],
+            link_properties: vec![LinkProperty::Flow],
+            nodes: Some(vec!["10".into(), "22".into()]),
+            links: Some(vec!["10".into()]),
+            ..ExportOptions::default()
+        }
+    }
+
+    #[rstest]
+    fn test_record_results_batches(ph: EPANET) {
+        let results = ph.record_results(&options()).unwrap();
+        assert_eq!(results.times.len(), 25);
+        assert_eq!(results.node_values.len(), 25 * 2 * 2);
+        assert!(results.node_value(24, 1, 1) > 0.0);
+
+        let wide = results
+            .to_record_batch(ExportLayout::Wide, TimeIndex::Seconds)
+            .unwrap();
+        assert_eq!(wide.num_rows(), 25);
+        assert_eq!(wide.num_columns(), 1 + 4 + 1);
+        assert_eq!(wide.schema().field(1).name(), "node:10:pressure");
+        assert_eq!(wide.schema().field(5).name(), "link:10:flow");
+
+        let long = results
+            .to_record_batch(ExportLayout::Long, TimeIndex::Clock)
+            .unwrap();
+        assert_eq!(long.num_rows(), 25 * 5);
+        assert_eq!(
+            long.schema().field(0).data_type(),
+            &DataType::Duration(arrow_schema::TimeUnit::Second)
+        );
+        let metadata = long.schema().metadata().clone();
+        assert_eq!(metadata["epanet:flow_units"], "GPM");
+        assert_eq!(metadata["epanet:pressure_units"], "psi");
+        assert_eq!(metadata["epanet:quality_type"], "Chem");
+        assert_eq!(metadata["epanet:quality_units"], "mg/L");
+    }
+
+    #[rstest]
+    fn test_attribute_batches(ph: EPANET) {
+        let nodes = ph.node_attributes_batch().unwrap();
+        assert_eq!(nodes.num_rows(), 11);
+        let demand = nodes.column_by_name("base_demand").unwrap();
+        // Junctions have a base demand; the reservoir and tank do not.
+        assert_eq!(demand.null_count(), 2);
+
+        let links = ph.link_attributes_batch().unwrap();
+        assert_eq!(links.num_rows(), 13);
+        let power = links.column_by_name("power").unwrap();
+        assert_eq!(power.len() - power.null_count(), 1);
+        assert_eq!(links.schema().metadata()["epanet:flow_units"], "GPM");
+    }
+
+    #[rstest]
+    fn test_write_parquet_round_trip(ph: EPANET) {
+        let batch = ph
+            .record_results(&options())
+            .unwrap()
+            .to_record_batch(ExportLayout::Long, TimeIndex::Seconds)
+            .unwrap();
+        let path =
+            std::env::temp_dir().join(format!("epanet_arrow_{}.parquet", std::process::id()));
+        write_parquet(&batch, std::fs::File::create(&path).unwrap()).unwrap();
+
+        let file = std::fs::File::open(&path).unwrap();
+        let reader =
+            parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+        assert_eq!(reader.schema().metadata()["epanet:quality_units"], "mg/L");
+        let rows: usize = reader
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum();
+        assert_eq!(rows, batch.num_rows());
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -7,8 +7,8 @@
 use crate::epanet_error::*;
 use crate::types::control::Control;
 use crate::types::curve::Curve;
-use crate::types::link::Link;
-use crate::types::node::Node;
+use crate::types::link::{Link, LinkType};
+use crate::types::node::{Node, NodeType};
 use crate::types::pattern::Pattern;
 use crate::types::rule::Rule;
 use crate::types::CountType;
@@ -59,7 +59,13 @@ impl EPANET {
     ///
     /// Returns an [`EPANETError`] if any link cannot be retrieved.
     pub fn pipes(&self) -> Result<Vec<Link<'_>>> {
-        Ok(self.links()?.into_iter().filter(|l| l.is_pipe()).collect())
+        self.iter_links()?
+            .filter_map(|l| match l.link_type() {
+                Ok(LinkType::Pipe | LinkType::CvPipe) => Some(l.get()),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            })
+            .collect()
     }
 
     /// Fetches all pump links in the model.
@@ -70,7 +76,13 @@ impl EPANET {
     ///
     /// Returns an [`EPANETError`] if any link cannot be retrieved.
     pub fn pumps(&self) -> Result<Vec<Link<'_>>> {
-        Ok(self.links()?.into_iter().filter(|l| l.is_pump()).collect())
+        self.iter_links()?
+            .filter_map(|l| match l.link_type() {
+                Ok(LinkType::Pump) => Some(l.get()),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            })
+            .collect()
     }
 
     /// Fetches all valve links in the model.
@@ -81,7 +93,13 @@ impl EPANET {
     ///
     /// Returns an [`EPANETError`] if any link cannot be retrieved.
     pub fn valves(&self) -> Result<Vec<Link<'_>>> {
-        Ok(self.links()?.into_iter().filter(|l| l.is_valve()).collect())
+        self.iter_links()?
+            .filter_map(|l| match l.link_type() {
+                Ok(LinkType::Pipe | LinkType::CvPipe | LinkType::Pump) => None,
+                Ok(_) => Some(l.get()),
+                Err(e) => Some(Err(e)),
+            })
+            .collect()
     }
 
     /// Fetches all junction nodes in the model.
@@ -92,11 +110,13 @@ impl EPANET {
     ///
     /// Returns an [`EPANETError`] if any node cannot be retrieved.
     pub fn junctions(&self) -> Result<Vec<Node<'_>>> {
-        Ok(self
-            .nodes()?
-            .into_iter()
-            .filter(|n| n.is_junction())
-            .collect())
+        self.iter_nodes()?
+            .filter_map(|n| match n.node_type() {
+                Ok(NodeType::Junction) => Some(n.get()),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            })
+            .collect()
     }
 
     /// Fetches all tank nodes in the model.
@@ -107,11 +127,13 @@ impl EPANET {
     ///
     /// Returns an [`EPANETError`] if any node cannot be retrieved.
     pub fn tanks(&self) -> Result<Vec<Node<'_>>> {
-        Ok(self
-            .nodes()?
-            .into_iter()
-            .filter(|n| n.is_tank())
-            .collect())
+        self.iter_nodes()?
+            .filter_map(|n| match n.node_type() {
+                Ok(NodeType::Tank) => Some(n.get()),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            })
+            .collect()
     }
 
     /// Fetches all time patterns in the model.
@@ -4,6 +4,7 @@
 use crate::bindings as ffi;
 use crate::epanet_error::*;
 use crate::types::control::{Control, ControlType};
+use crate::types::index::{ControlIndex, LinkIndex, NodeIndex};
 use crate::EPANET;
 use num_traits::FromPrimitive;
 
@@ -12,12 +13,13 @@ impl EPANET {
     /// Retrieves a control by its index.
     ///
     /// Convenience method that calls [`get_control_by_index`](Self::get_control_by_index).
-    pub fn get_control(&self, index: i32) -> Result<Control<'_>> {
+    pub fn get_control(&self, index: impl Into<ControlIndex>) -> Result<Control<'_>> {
         self.get_control_by_index(index)
     }
 
     /// Retrieves a control by its index.
-    pub fn get_control_by_index(&self, index: i32) -> Result<Control<'_>> {
+    pub fn get_control_by_index(&self, index: impl Into<ControlIndex>) -> Result<Control<'_>> {
+        let index = index.into();
         let mut out_type = 0;
         let mut out_link_index = 0;
         let mut out_setting = 0.0;
@@ -27,7 +29,7 @@ impl EPANET {
         check_error(unsafe {
             ffi::EN_getcontrol(
                 self.ph,
-                index,
+                index.0,
                 &mut out_type,
                 &mut out_link_index,
                 &mut out_setting,
@@ -36,15 +38,15 @@ impl EPANET {
             )
         })?;
 
-        let enabled = self.get_control_enabled(index)?;
+        let enabled = self.get_control_enabled(index.0)?;
 
         Ok(Control {
             project: self,
             index,
             control_type: ControlType::from_i32(out_type).unwrap(),
-            link_index: out_link_index,
+            link_index: LinkIndex(out_link_index),
             setting: out_setting,
-            node_index: out_node_index,
+            node_index: NodeIndex(out_node_index),
             level: out_level,
             enabled,
         })
@@ -56,39 +58,40 @@ impl EPANET {
         check_error(unsafe {
             ffi::EN_setcontrol(
                 self.ph,
-                control.index,
+                control.index.0,
                 control.control_type as i32,
-                control.link_index,
+                control.link_index.0,
                 control.setting,
-                control.node_index,
+                control.node_index.0,
                 control.level,
             )
         })?;
 
-        self.set_control_enabled(control.index, control.enabled)
+        self.set_control_enabled(control.index.0, control.enabled)
     }
 
     pub fn delete_control(&self, control: Control) -> Result<()> {
-        self.delete_control_by_index(control.index)
+        self.delete_control_by_index(control.index.0)
     }
 
     pub fn add_control(
         &self,
         control_type: ControlType,
-        link_index: i32,
+        link_index: impl Into<LinkIndex>,
         setting: f64,
-        node_index: i32,
+        node_index: impl Into<NodeIndex>,
         level: f64,
         enabled: bool,
     ) -> Result<Control<'_>> {
+        let (link_index, node_index) = (link_index.into(), node_index.into());
         let mut out_index = 0;
         check_error(unsafe {
             ffi::EN_addcontrol(
                 self.ph,
                 control_type as i32,
-                link_index,
+                link_index.0,
                 setting,
-                node_index,
+                node_index.0,
                 level,
                 &mut out_index,
             )
@@ -97,7 +100,7 @@ impl EPANET {
         self.set_control_enabled(out_index, enabled)?;
         Ok(Control {
             project: self,
-            index: out_index,
+            index: ControlIndex(out_index),
             control_type,
             link_index,
             setting,
@@ -119,7 +122,7 @@ impl EPANET {
         Ok(out_enabled != 0)
     }
 
-    fn set_control_enabled(&self, control_index: i32, enabled: bool) -> Result<()> {
+    pub(crate) fn set_control_enabled(&self, control_index: i32, enabled: bool) -> Result<()> {
         check_error(unsafe { ffi::EN_setcontrolenabled(self.ph, control_index, enabled as i32) })
     }
 }
@@ -4,6 +4,7 @@
 use crate::bindings as ffi;
 use crate::epanet_error::*;
 use crate::types::curve::{Curve, CurveType};
+use crate::types::index::CurveIndex;
 use crate::types::MAX_ID_SIZE;
 use crate::EPANET;
 use num_traits::FromPrimitive;
@@ -55,7 +56,8 @@ impl EPANET {
     }
 
     /// Retrieves a curve by its index.
-    pub fn get_curve_by_index(&self, index: i32) -> Result<Curve<'_>> {
+    pub fn get_curve_by_index(&self, index: impl Into<CurveIndex>) -> Result<Curve<'_>> {
+        let index = index.into();
         let id = self.get_curve_id(index)?;
         let curve_type = self.get_curve_type(index)?;
         let points = self.get_curve_points(index)?;
@@ -76,20 +78,22 @@ impl EPANET {
         check_error(unsafe { ffi::EN_addcurve(self.ph, c_id.as_ptr()) })
     }
 
-    pub fn delete_curve_by_id(&self, index: i32) -> Result<()> {
-        check_error(unsafe { ffi::EN_deletecurve(self.ph, index) })
+    pub fn delete_curve_by_id(&self, index: impl Into<CurveIndex>) -> Result<()> {
+        let index = index.into();
+        check_error(unsafe { ffi::EN_deletecurve(self.ph, index.0) })
     }
 
-    pub fn get_curve_index(&self, id: &str) -> Result<i32> {
+    pub fn get_curve_index(&self, id: &str) -> Result<CurveIndex> {
         let c_id = std::ffi::CString::new(id).unwrap();
         let mut out_index = 0;
         check_error(unsafe { ffi::EN_getcurveindex(self.ph, c_id.as_ptr(), &mut out_index) })?;
-        Ok(out_index)
+        Ok(CurveIndex(out_index))
     }
 
-    pub fn get_curve_id(&self, index: i32) -> Result<String> {
+    pub fn get_curve_id(&self, index: impl Into<CurveIndex>) -> Result<String> {
+        let index = index.into();
         let mut out_id: Vec<std::ffi::c_char> = vec![0; MAX_ID_SIZE as usize + 1];
-        check_error(unsafe { ffi::EN_getcurveid(self.ph, index, out_id.as_mut_ptr()) })?;
+        check_error(unsafe { ffi::EN_getcurveid(self.ph, index.0, out_id.as_mut_ptr()) })?;
         let id = unsafe { std::ffi::CStr::from_ptr(out_id.as_ptr()) }
             .to_str()
             .unwrap_or("")
@@ -98,28 +102,33 @@ impl EPANET {
         Ok(id)
     }
 
-    pub fn set_curve_id(&self, index: i32, id: &str) -> Result<()> {
+    pub fn set_curve_id(&self, index: impl Into<CurveIndex>, id: &str) -> Result<()> {
+        let index = index.into();
         let c_id = std::ffi::CString::new(id).unwrap();
-        check_error(unsafe { ffi::EN_setcurveid(self.ph, index, c_id.as_ptr()) })
+        check_error(unsafe { ffi::EN_setcurveid(self.ph, index.0, c_id.as_ptr()) })
     }
 
-    pub fn get_curve_len(&self, index: i32) -> Result<i32> {
+    pub fn get_curve_len(&self, index: impl Into<CurveIndex>) -> Result<i32> {
+        let index = index.into();
         let mut out_len = 0;
-        check_error(unsafe { ffi::EN_getcurvelen(self.ph, index, &mut out_len) })?;
+        check_error(unsafe { ffi::EN_getcurvelen(self.ph, index.0, &mut out_len) })?;
         Ok(out_len)
     }
 
-    pub fn get_curve_type(&self, index: i32) -> Result<CurveType> {
+    pub fn get_curve_type(&self, index: impl Into<CurveIndex>) -> Result<CurveType> {
+        let index = index.into();
         let mut out_type = 0;
-        check_error(unsafe { ffi::EN_getcurvetype(self.ph, index, &mut out_type) })?;
+        check_error(unsafe { ffi::EN_getcurvetype(self.ph, index.0, &mut out_type) })?;
         Ok(CurveType::from_i32(out_type).unwrap())
     }
 
-    pub fn set_curve_type(&self, index: i32, curve_type: CurveType) -> Result<()> {
-        check_error(unsafe { ffi::EN_setcurvetype(self.ph, index, curve_type as i32) })
+    pub fn set_curve_type(&self, index: impl Into<CurveIndex>, curve_type: CurveType) -> Result<()> {
+        let index = index.into();
+        check_error(unsafe { ffi::EN_setcurvetype(self.ph, index.0, curve_type as i32) })
     }
 
-    pub fn get_curve_points(&self, index: i32) -> Result<Vec<(f64, f64)>> {
+    pub fn get_curve_points(&self, index: impl Into<CurveIndex>) -> Result<Vec<(f64, f64)>> {
+        let index = index.into();
         let len = self.get_curve_len(index)?;
         let mut out_id: Vec<std::ffi::c_char> = vec![0; MAX_ID_SIZE as usize + 1];
         let mut out_x = vec![0.0; len as usize];
@@ -129,7 +138,7 @@ impl EPANET {
         check_error(unsafe {
             ffi::EN_getcurve(
                 self.ph,
-                index,
+                index.0,
                 out_id.as_mut_ptr(),
                 &mut out_len,
                 out_x.as_mut_ptr(),
@@ -143,13 +152,14 @@ impl EPANET {
             .collect())
     }
 
-    pub fn set_curve(&self, index: i32, values: &[(f64, f64)]) -> Result<()> {
+    pub fn set_curve(&self, index: impl Into<CurveIndex>, values: &[(f64, f64)]) -> Result<()> {
+        let index = index.into();
         let (mut x_vec, mut y_vec): (Vec<f64>, Vec<f64>) = values.iter().cloned().unzip();
 
         check_error(unsafe {
             ffi::EN_setcurve(
                 self.ph,
-                index,
+                index.0,
                 x_vec.as_mut_ptr(),
                 y_vec.as_mut_ptr(),
                 values.len() as i32,
@@ -157,14 +167,16 @@ impl EPANET {
         })
     }
 
-    pub fn get_curve_value(&self, index: i32, point_index: i32) -> Result<(f64, f64)> {
+    pub fn get_curve_value(&self, index: impl Into<CurveIndex>, point_index: i32) -> Result<(f64, f64)> {
+        let index = index.into();
         let (mut out_x, mut out_y) = (0f64, 0f64);
-        check_error( unsafe { EN_getcurvevalue(self.ph, index, point_index, &mut out_x, &mut out_y)})?;
+        check_error( unsafe { EN_getcurvevalue(self.ph, index.0, point_index, &mut out_x, &mut out_y)})?;
         Ok((out_x, out_y))
     }
 
-    pub fn set_curve_value(&self, index: i32, point_index: i32, point: (f64, f64)) -> Result<()> {
-        check_error(unsafe{ EN_setcurvevalue(self.ph, index, point_index, point.0, point.1) })
+    pub fn set_curve_value(&self, index: impl Into<CurveIndex>, point_index: i32, point: (f64, f64)) -> Result<()> {
+        let index = index.into();
+        check_error(unsafe{ EN_setcurvevalue(self.ph, index.0, point_index, point.0, point.1) })
     }
 }
 
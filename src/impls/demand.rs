@@ -6,6 +6,7 @@
 use crate::bindings as ffi;
 use crate::epanet_error::*;
 use crate::types::demand::*;
+use crate::types::index::{NodeIndex, PatternIndex};
 use crate::types::MAX_ID_SIZE;
 use crate::EPANET;
 use num_traits::FromPrimitive;
@@ -31,11 +32,12 @@ impl EPANET {
     /// Returns an [`EPANETError`] if the demand cannot be created.
     pub fn create_demand(
         &self,
-        node_index: i32,
+        node_index: impl Into<NodeIndex>,
         base_demand: f64,
         demand_pattern: &str,
         name: &str,
     ) -> Result<Demand<'_>> {
+        let node_index = node_index.into();
         self.add_demand(node_index, base_demand, demand_pattern, name)?;
         let demand_index = self.get_demand_count(node_index)?;
         let pattern_index = self.get_demand_pattern(node_index, demand_index)?;
@@ -64,7 +66,8 @@ impl EPANET {
     /// # Errors
     ///
     /// Returns an [`EPANETError`] if the demand does not exist.
-    pub fn get_demand(&self, node_index: i32, name: &str) -> Result<Demand<'_>> {
+    pub fn get_demand(&self, node_index: impl Into<NodeIndex>, name: &str) -> Result<Demand<'_>> {
+        let node_index = node_index.into();
         let demand_index = self.get_demand_index(node_index, name)?;
         self.get_demand_by_index(node_index, demand_index)
     }
@@ -83,7 +86,8 @@ impl EPANET {
     /// # Errors
     ///
     /// Returns an [`EPANETError`] if the indices are invalid.
-    pub fn get_demand_by_index(&self, node_index: i32, demand_index: i32) -> Result<Demand<'_>> {
+    pub fn get_demand_by_index(&self, node_index: impl Into<NodeIndex>, demand_index: i32) -> Result<Demand<'_>> {
+        let node_index = node_index.into();
         let base_demand = self.get_base_demand(node_index, demand_index)?;
         let pattern_index = self.get_demand_pattern(node_index, demand_index)?;
         let name = self.get_demand_name(node_index, demand_index)?;
@@ -141,18 +145,19 @@ impl EPANET {
     /// Adds a new demand category to a node.
     pub fn add_demand(
         &self,
-        node_index: i32,
+        node_index: impl Into<NodeIndex>,
         base_demand: f64,
         demand_pattern: &str,
         demand_name: &str,
     ) -> Result<()> {
+        let node_index = node_index.into();
         let c_demand_pattern = CString::new(demand_pattern).unwrap();
         let c_demand_name = CString::new(demand_name).unwrap();
 
         check_error(unsafe {
             ffi::EN_adddemand(
                 self.ph,
-                node_index,
+                node_index.0,
                 base_demand,
                 c_demand_pattern.as_ptr(),
                 c_demand_name.as_ptr(),
@@ -161,68 +166,77 @@ impl EPANET {
     }
 
     /// Deletes a demand category from a node.
-    pub fn delete_demand(&self, node_index: i32, demand_index: i32) -> Result<()> {
-        check_error(unsafe { ffi::EN_deletedemand(self.ph, node_index, demand_index) })
+    pub fn delete_demand(&self, node_index: impl Into<NodeIndex>, demand_index: i32) -> Result<()> {
+        let node_index = node_index.into();
+        check_error(unsafe { ffi::EN_deletedemand(self.ph, node_index.0, demand_index) })
     }
 
     /// Returns the 1-based index of a demand category given its name on a node.
-    pub fn get_demand_index(&self, node_index: i32, demand_name: &str) -> Result<i32> {
+    pub fn get_demand_index(&self, node_index: impl Into<NodeIndex>, demand_name: &str) -> Result<i32> {
+        let node_index = node_index.into();
         let mut out_index = 0;
         let c_demand_name = CString::new(demand_name).unwrap();
 
         check_error(unsafe {
-            ffi::EN_getdemandindex(self.ph, node_index, c_demand_name.as_ptr(), &mut out_index)
+            ffi::EN_getdemandindex(self.ph, node_index.0, c_demand_name.as_ptr(), &mut out_index)
         })?;
         Ok(out_index)
     }
 
     /// Returns the number of demand categories on a node.
-    pub fn get_demand_count(&self, node_index: i32) -> Result<i32> {
+    pub fn get_demand_count(&self, node_index: impl Into<NodeIndex>) -> Result<i32> {
+        let node_index = node_index.into();
         let mut out_count = 0;
-        check_error(unsafe { ffi::EN_getnumdemands(self.ph, node_index, &mut out_count) })?;
+        check_error(unsafe { ffi::EN_getnumdemands(self.ph, node_index.0, &mut out_count) })?;
         Ok(out_count)
     }
 
     /// Returns the base demand value of a demand category on a node.
-    pub fn get_base_demand(&self, node_index: i32, demand_index: i32) -> Result<f64> {
+    pub fn get_base_demand(&self, node_index: impl Into<NodeIndex>, demand_index: i32) -> Result<f64> {
+        let node_index = node_index.into();
         let mut out_demand = 0.0;
         check_error(unsafe {
-            ffi::EN_getbasedemand(self.ph, node_index, demand_index, &mut out_demand)
+            ffi::EN_getbasedemand(self.ph, node_index.0, demand_index, &mut out_demand)
         })?;
         Ok(out_demand)
     }
 
     /// Sets the base demand value of a demand category on a node.
-    pub fn set_base_demand(&self, node_index: i32, demand_index: i32, demand: f64) -> Result<()> {
-        check_error(unsafe { ffi::EN_setbasedemand(self.ph, node_index, demand_index, demand) })
+    pub fn set_base_demand(&self, node_index: impl Into<NodeIndex>, demand_index: i32, demand: f64) -> Result<()> {
+        let node_index = node_index.into();
+        check_error(unsafe { ffi::EN_setbasedemand(self.ph, node_index.0, demand_index, demand) })
     }
 
     /// Returns the pattern index for a demand category on a node (0 = no pattern).
-    pub fn get_demand_pattern(&self, node_index: i32, demand_index: i32) -> Result<i32> {
+    pub fn get_demand_pattern(&self, node_index: impl Into<NodeIndex>, demand_index: i32) -> Result<PatternIndex> {
+        let node_index = node_index.into();
         let mut out_pattern = 0;
         check_error(unsafe {
-            ffi::EN_getdemandpattern(self.ph, node_index, demand_index, &mut out_pattern)
+            ffi::EN_getdemandpattern(self.ph, node_index.0, demand_index, &mut out_pattern)
         })?;
-        Ok(out_pattern)
+        Ok(PatternIndex(out_pattern))
     }
 
     /// Sets the pattern index for a demand category on a node (0 = no pattern).
     pub fn set_demand_pattern(
         &self,
-        node_index: i32,
+        node_index: impl Into<NodeIndex>,
         demand_index: i32,
-        pattern_index: i32,
+        pattern_index: impl Into<PatternIndex>,
     ) -> Result<()> {
+        let node_index = node_index.into();
+        let pattern_index = pattern_index.into();
         check_error(unsafe {
-            ffi::EN_setdemandpattern(self.ph, node_index, demand_index, pattern_index)
+            ffi::EN_setdemandpattern(self.ph, node_index.0, demand_index, pattern_index.0)
         })
     }
 
     /// Returns the name of a demand category on a node.
-    pub fn get_demand_name(&self, node_index: i32, demand_index: i32) -> Result<String> {
+    pub fn get_demand_name(&self, node_index: impl Into<NodeIndex>, demand_index: i32) -> Result<String> {
+        let node_index = node_index.into();
         let mut out_name: Vec<c_char> = vec![0; MAX_ID_SIZE as usize + 1usize];
         check_error(unsafe {
-            ffi::EN_getdemandname(self.ph, node_index, demand_index, out_name.as_mut_ptr())
+            ffi::EN_getdemandname(self.ph, node_index.0, demand_index, out_name.as_mut_ptr())
         })?;
         let name = unsafe { std::ffi::CStr::from_ptr(out_name.as_ptr()) }
             .to_string_lossy()
@@ -234,13 +248,14 @@ impl EPANET {
     /// Sets the name of a demand category on a node.
     pub fn set_demand_name(
         &self,
-        node_index: i32,
+        node_index: impl Into<NodeIndex>,
         demand_index: i32,
         name: &str,
     ) -> Result<()> {
+        let node_index = node_index.into();
         let c_name = CString::new(name).expect("Demand name contains null bytes");
         check_error(unsafe {
-            ffi::EN_setdemandname(self.ph, node_index, demand_index, c_name.as_ptr())
+            ffi::EN_setdemandname(self.ph, node_index.0, demand_index, c_name.as_ptr())
         })
     }
 }
@@ -280,7 +295,7 @@ mod tests {
 
         assert_eq!(demand.base_demand, 75.0);
         assert_eq!(demand.name, "PatternedDemand");
-        assert!(demand.pattern_index > 0);
+        assert!(demand.pattern_index.0 > 0);
     }
 
     #[rstest]
@@ -0,0 +1,371 @@
+//! Pipe sizing API methods for EPANET.
+//!
+//! This module contains methods for checking a network against a set of
+//! steady-state loading conditions and for choosing pipe diameters from a
+//! cost catalogue that minimize capital cost while meeting minimum pressures.
+
+use crate::epanet_error::*;
+use crate::types::design::*;
+use crate::types::index::NodeIndex;
+use crate::types::link::{LinkProperty, LinkType};
+use crate::types::node::{NodeProperty, NodeType};
+use crate::types::options::Option;
+use crate::types::CountType;
+use crate::EPANET;
+
+/// ## Pipe Sizing APIs
+impl EPANET {
+    /// Solves a single loading condition and returns the lowest pressure found at
+    /// `pressure_nodes` (every junction when empty).
+    ///
+    /// The condition is solved as a steady-state snapshot with patterns evaluated at
+    /// [`LoadingCondition::time`] and tanks at their initial levels. The project's
+    /// time parameters, demand multiplier and demands are restored before returning.
+    /// Returns `f64::NEG_INFINITY` when the hydraulic solver raises a warning (for
+    /// example when the network cannot deliver the demand).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if a node index is invalid or the solver fails.
+    pub fn loading_condition_min_pressure(
+        &self,
+        condition: &LoadingCondition,
+        pressure_nodes: &[NodeIndex],
+    ) -> Result<f64> {
+        let nodes = self.design_pressure_nodes(pressure_nodes)?;
+
+        let demand_mult = self.get_option(Option::DemandMult)?;
+
+        let mut added = Vec::new();
+        let mut run = || -> Result<f64> {
+            self.set_option(Option::DemandMult, condition.demand_multiplier)?;
+            for &(node, flow) in &condition.extra_demands {
+                self.add_demand(node, flow, "", "")?;
+                added.push(node);
+            }
+
+            let mut min_pressure = f64::INFINITY;
+            let warnings = self.solve_snapshot_with(condition.time, || {
+                for &node in &nodes {
+                    min_pressure =
+                        min_pressure.min(self.get_node_value(node, NodeProperty::Pressure)?);
+                }
+                Ok(())
+            })?;
+            Ok(if warnings > 0 {
+                f64::NEG_INFINITY
+            } else {
+                min_pressure
+            })
+        };
+        let result = run();
+
+        for &node in added.iter().rev() {
+            self.delete_demand(node, self.get_demand_count(node)?)?;
+        }
+        self.set_option(Option::DemandMult, demand_mult)?;
+        result
+    }
+
+    /// Chooses a diameter from [`PipeSizingOptions::catalogue`] for every candidate
+    /// pipe so that capital cost is minimized while every loading condition meets
+    /// its minimum pressure.
+    ///
+    /// The search starts with every candidate pipe at the largest catalogue diameter.
+    /// If that design is infeasible it is returned as is with `feasible == false`.
+    /// Otherwise pipes are repeatedly reduced by one catalogue size, largest cost
+    /// saving first, keeping every reduction that leaves all conditions satisfied,
+    /// until no pipe can be reduced or [`PipeSizingOptions::max_evaluations`] designs
+    /// have been solved. Each design is applied with [`set_pipe_data`](Self::set_pipe_data).
+    ///
+    /// The original diameters are restored before returning. Use
+    /// [`apply_pipe_design`](Self::apply_pipe_design) to keep the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if the catalogue or condition list is empty, a
+    /// candidate link is not a pipe, or a hydraulic solution fails.
+    pub fn optimize_pipe_sizes(&self, options: &PipeSizingOptions) -> Result<PipeSizingResult> {
+        if options.catalogue.is_empty() || options.conditions.is_empty() {
+            return Err(EPANETError::from(202)
+                .with_context("pipe sizing needs a catalogue and at least one loading condition"));
+        }
+        let mut catalogue = options.catalogue.clone();
+        catalogue.sort_by(|a, b| a.diameter.total_cmp(&b.diameter));
+
+        let mut original = Vec::new();
+        for &pipe in &options.pipes {
+            match self.get_link_type(pipe)? {
+                LinkType::Pipe | LinkType::CvPipe => {}
+                _ => {
+                    return Err(
+                        EPANETError::from(204).with_context(format!("link {pipe} is not a pipe"))
+                    )
+                }
+            }
+            original.push(PipeDesign {
+                link_index: pipe,
+                diameter: self.get_link_value(pipe, LinkProperty::Diameter)?,
+                cost: 0.0,
+            });
+        }
+
+        let result = self.search_pipe_sizes(options, &catalogue);
+        let restored = self.apply_pipe_design(&original);
+        let result = result?;
+        restored?;
+        Ok(result)
+    }
+
+    /// Sets the diameter of each pipe in `design` with [`set_pipe_data`](Self::set_pipe_data),
+    /// keeping its length, roughness and minor loss coefficient.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if a link index is invalid or is not a pipe.
+    pub fn apply_pipe_design(&self, design: &[PipeDesign]) -> Result<()> {
+        for pipe in design {
+            self.set_pipe_data(
+                pipe.link_index,
+                self.get_link_value(pipe.link_index, LinkProperty::Length)?,
+                pipe.diameter,
+                self.get_link_value(pipe.link_index, LinkProperty::Roughness)?,
+                self.get_link_value(pipe.link_index, LinkProperty::MinorLoss)?,
+            )?;
+        }
+        Ok(())
+    }
+
+    // Helper methods - Internal API
+
+    fn design_pressure_nodes(&self, pressure_nodes: &[NodeIndex]) -> Result<Vec<NodeIndex>> {
+        if !pressure_nodes.is_empty() {
+            return Ok(pressure_nodes.to_vec());
+        }
+        let mut nodes = Vec::new();
+        for index in 1..=self.get_count(CountType::NodeCount)? {
+            if self.get_node_type(index)? == NodeType::Junction {
+                nodes.push(NodeIndex(index));
+            }
+        }
+        Ok(nodes)
+    }
+
+    fn search_pipe_sizes(
+        &self,
+        options: &PipeSizingOptions,
+        catalogue: &[PipeSize],
+    ) -> Result<PipeSizingResult> {
+        let nodes = self.design_pressure_nodes(&options.pressure_nodes)?;
+        let mut lengths = Vec::new();
+        for &pipe in &options.pipes {
+            lengths.push(self.get_link_value(pipe, LinkProperty::Length)?);
+        }
+
+        let design_of = |sizes: &[usize]| -> Vec<PipeDesign> {
+            options
+                .pipes
+                .iter()
+                .zip(sizes)
+                .zip(&lengths)
+                .map(|((&link_index, &size), &length)| PipeDesign {
+                    link_index,
+                    diameter: catalogue[size].diameter,
+                    cost: catalogue[size].unit_cost * length,
+                })
+                .collect()
+        };
+
+        // Solves the conditions for a design, stopping at the first violated one
+        // unless `all` is set.
+        let evaluate = |sizes: &[usize], all: bool| -> Result<(bool, Vec<f64>)> {
+            self.apply_pipe_design(&design_of(sizes))?;
+            let mut feasible = true;
+            let mut min_pressures = Vec::new();
+            for condition in &options.conditions {
+                let p = self.loading_condition_min_pressure(condition, &nodes)?;
+                min_pressures.push(p);
+                if p < condition.min_pressure {
+                    feasible = false;
+                    if !all {
+                        break;
+                    }
+                }
+            }
+            Ok((feasible, min_pressures))
+        };
+
+        let mut sizes = vec![catalogue.len() - 1; options.pipes.len()];
+        let (feasible, mut min_pressures) = evaluate(&sizes, true)?;
+        let mut evaluations = 1;
+
+        if feasible {
+            // Largest saving from a one-size reduction first.
+            let mut order: Vec<usize> = (0..sizes.len()).collect();
+            let saving = |i: usize, size: usize| {
+                (catalogue[size].unit_cost - catalogue[size - 1].unit_cost) * lengths[i]
+            };
+            let mut improved = true;
+            'search: while improved {
+                improved = false;
+                order.retain(|&i| sizes[i] > 0);
+                order.sort_by(|&a, &b| saving(b, sizes[b]).total_cmp(&saving(a, sizes[a])));
+                for &i in &order {
+                    if sizes[i] == 0 {
+                        continue;
+                    }
+                    if evaluations >= options.max_evaluations {
+                        break 'search;
+                    }
+                    sizes[i] -= 1;
+                    let (ok, pressures) = evaluate(&sizes, false)?;
+                    evaluations += 1;
+                    if ok {
+                        min_pressures = pressures;
+                        improved = true;
+                    } else {
+                        sizes[i] += 1;
+                    }
+                }
+            }
+        }
+
+        let pipes = design_of(&sizes);
+        Ok(PipeSizingResult {
+            total_cost: pipes.iter().map(|p| p.cost).sum(),
+            pipes,
+            min_pressures,
+            feasible,
+            evaluations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use crate::types::options::TimeParameter;
+    use rstest::rstest;
+
+    fn catalogue() -> Vec<PipeSize> {
+        [
+            (6.0, 20.0),
+            (8.0, 30.0),
+            (10.0, 42.0),
+            (12.0, 55.0),
+            (14.0, 70.0),
+            (18.0, 95.0),
+        ]
+        .iter()
+        .map(|&(diameter, unit_cost)| PipeSize {
+            diameter,
+            unit_cost,
+        })
+        .collect()
+    }
+
+    fn options(ph: &EPANET) -> PipeSizingOptions {
+        let pipes = [
+            "10", "11", "12", "21", "22", "31", "110", "111", "112", "113", "121", "122",
+        ]
+        .iter()
+        .map(|id| ph.get_link_index(id).unwrap())
+        .collect();
+        let mut fire = LoadingCondition::new("fire flow at 32", 20.0);
+        fire.extra_demands
+            .push((ph.get_node_index("32").unwrap(), 1000.0));
+        PipeSizingOptions {
+            pipes,
+            catalogue: catalogue(),
+            conditions: vec![
+                LoadingCondition {
+                    time: 4 * 3600,
+                    demand_multiplier: 1.5,
+                    ..LoadingCondition::new("peak hour", 40.0)
+                },
+                fire,
+            ],
+            pressure_nodes: Vec::new(),
+            max_evaluations: 200,
+        }
+    }
+
+    #[rstest]
+    fn test_loading_condition_min_pressure(ph: EPANET) {
+        let duration = ph.get_time_parameter(TimeParameter::Duration).unwrap();
+        let node = ph.get_node_index("32").unwrap();
+        let demands = ph.get_demand_count(node).unwrap();
+
+        let base = ph
+            .loading_condition_min_pressure(&LoadingCondition::new("base", 0.0), &[])
+            .unwrap();
+        let mut fire = LoadingCondition::new("fire", 0.0);
+        fire.extra_demands.push((node, 1000.0));
+        let with_fire = ph.loading_condition_min_pressure(&fire, &[node]).unwrap();
+        assert!(base > 0.0);
+        assert!(with_fire < base);
+
+        assert_eq!(
+            ph.get_time_parameter(TimeParameter::Duration).unwrap(),
+            duration
+        );
+        assert_eq!(ph.get_demand_count(node).unwrap(), demands);
+    }
+
+    #[rstest]
+    fn test_optimize_pipe_sizes(ph: EPANET) {
+        let options = options(&ph);
+        let before: Vec<f64> = options
+            .pipes
+            .iter()
+            .map(|&p| ph.get_link_value(p, LinkProperty::Diameter).unwrap())
+            .collect();
+
+        let result = ph.optimize_pipe_sizes(&options).unwrap();
+        assert!(result.feasible);
+        assert_eq!(result.pipes.len(), options.pipes.len());
+        assert_eq!(result.min_pressures.len(), 2);
+        assert!(result.min_pressures[0] >= 40.0);
+        assert!(result.min_pressures[1] >= 20.0);
+
+        let largest: f64 = options
+            .pipes
+            .iter()
+            .map(|&p| ph.get_link_value(p, LinkProperty::Length).unwrap() * 95.0)
+            .sum();
+        assert!(result.total_cost < largest);
+
+        // Original diameters are restored.
+        for (&p, &d) in options.pipes.iter().zip(&before) {
+            assert_eq!(ph.get_link_value(p, LinkProperty::Diameter).unwrap(), d);
+        }
+
+        ph.apply_pipe_design(&result.pipes).unwrap();
+        for pipe in &result.pipes {
+            assert!(approx_eq(
+                ph.get_link_value(pipe.link_index, LinkProperty::Diameter)
+                    .unwrap(),
+                pipe.diameter,
+                1e-9
+            ));
+        }
+    }
+
+    #[rstest]
+    fn test_optimize_pipe_sizes_infeasible(ph: EPANET) {
+        let mut options = options(&ph);
+        options.conditions[0].min_pressure = 10_000.0;
+        let result = ph.optimize_pipe_sizes(&options).unwrap();
+        assert!(!result.feasible);
+        assert_eq!(result.evaluations, 1);
+        assert!(result.pipes.iter().all(|p| p.diameter == 18.0));
+    }
+
+    #[rstest]
+    fn test_optimize_pipe_sizes_rejects_pump(ph: EPANET) {
+        let mut options = options(&ph);
+        options.pipes.push(ph.get_link_index("9").unwrap());
+        assert_eq!(ph.optimize_pipe_sizes(&options).unwrap_err().code, 204);
+    }
+}
@@ -0,0 +1,602 @@
+//! Model diff API methods for EPANET.
+//!
+//! This module compares two projects element by element: options and time
+//! parameters, nodes, links, patterns, curves, simple controls and rules.
+
+use crate::epanet_error::*;
+use crate::types::control::{Control, ControlType};
+use crate::types::diff::*;
+use crate::types::index::CurveIndex;
+use crate::types::link::{Link, LinkKind};
+use crate::types::node::{Node, NodeKind};
+use crate::types::options::TimeParameter;
+use crate::types::rule::{ActionClause, Premise, PremiseObject};
+use crate::EPANET;
+use std::collections::HashMap;
+
+/// Time parameters compared by [`EPANET::diff`], with their property names.
+const TIME_PARAMETERS: [(TimeParameter, &str); 7] = [
+    (TimeParameter::Duration, "duration"),
+    (TimeParameter::HydStep, "hydraulic step"),
+    (TimeParameter::QualStep, "quality step"),
+    (TimeParameter::PatternStep, "pattern step"),
+    (TimeParameter::PatternStart, "pattern start"),
+    (TimeParameter::ReportStep, "report step"),
+    (TimeParameter::ReportStart, "report start"),
+];
+
+/// ## Model Diff APIs
+impl EPANET {
+    /// Compares this project (the old model) with `other` (the new model).
+    ///
+    /// Elements are matched by ID, or by position when
+    /// [`detect_renames`](DiffOptions::detect_renames) is set and the IDs at a
+    /// position exist in one model only. Simple controls, which have no IDs, are
+    /// always matched by position. References to renamed elements, such as link
+    /// end nodes or demand patterns, are compared under their new IDs.
+    ///
+    /// Matched elements are compared property by property: numbers within
+    /// [`tolerance`](DiffOptions::tolerance) are equal, and elements whose type
+    /// changed report only the type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if either model cannot be read.
+    pub fn diff(&self, other: &EPANET, options: &DiffOptions) -> Result<ModelDiff> {
+        let mut changes = Changes {
+            tolerance: options.tolerance,
+            list: Vec::new(),
+            renames: HashMap::new(),
+        };
+        self.diff_options(other, &mut changes)?;
+
+        // Patterns and curves first, so that references to them are renamed.
+        let (a_patterns, b_patterns) = (self.patterns()?, other.patterns()?);
+        let (a_ids, b_ids) = (ids(&a_patterns, |p| &p.id), ids(&b_patterns, |p| &p.id));
+        for (i, j) in changes.matching(DiffObject::Pattern, &a_ids, &b_ids, options) {
+            let (a, b) = (&a_patterns[i].multipliers, &b_patterns[j].multipliers);
+            let id = &b_ids[j];
+            changes.number(
+                DiffObject::Pattern,
+                id,
+                "length",
+                a.len() as f64,
+                b.len() as f64,
+            );
+            for (period, (x, y)) in a.iter().zip(b).enumerate() {
+                let property = format!("factor {}", period + 1);
+                changes.number(DiffObject::Pattern, id, &property, *x, *y);
+            }
+        }
+        let (a_curves, b_curves) = (self.curves()?, other.curves()?);
+        let (a_ids, b_ids) = (ids(&a_curves, |c| &c.id), ids(&b_curves, |c| &c.id));
+        for (i, j) in changes.matching(DiffObject::Curve, &a_ids, &b_ids, options) {
+            let (a, b) = (&a_curves[i], &b_curves[j]);
+            let id = &b_ids[j];
+            let (old, new) = (format!("{:?}", a.curve_type), format!("{:?}", b.curve_type));
+            changes.text(DiffObject::Curve, id, "type", old, new);
+            let (len_a, len_b) = (a.points.len() as f64, b.points.len() as f64);
+            changes.number(DiffObject::Curve, id, "points", len_a, len_b);
+            for (n, (p, q)) in a.points.iter().zip(&b.points).enumerate() {
+                changes.number(DiffObject::Curve, id, &format!("x {}", n + 1), p.0, q.0);
+                changes.number(DiffObject::Curve, id, &format!("y {}", n + 1), p.1, q.1);
+            }
+        }
+
+        let (a_nodes, b_nodes) = (self.nodes()?, other.nodes()?);
+        let (a_node_ids, b_node_ids) = (ids(&a_nodes, |n| &n.id), ids(&b_nodes, |n| &n.id));
+        for (i, j) in changes.matching(DiffObject::Node, &a_node_ids, &b_node_ids, options) {
+            self.diff_node(&a_nodes[i], other, &b_nodes[j], &mut changes)?;
+        }
+        let a_node_ids = changes.renamed(DiffObject::Node, a_node_ids);
+
+        let (a_links, b_links) = (self.links()?, other.links()?);
+        let (a_link_ids, b_link_ids) = (ids(&a_links, |l| &l.id), ids(&b_links, |l| &l.id));
+        for (i, j) in changes.matching(DiffObject::Link, &a_link_ids, &b_link_ids, options) {
+            let ends = |link: &Link, names: &[String]| {
+                format!(
+                    "{}-{}",
+                    name(names, link.from_node),
+                    name(names, link.to_node)
+                )
+            };
+            let id = &b_link_ids[j];
+            let (old, new) = (
+                ends(&a_links[i], &a_node_ids),
+                ends(&b_links[j], &b_node_ids),
+            );
+            changes.text(DiffObject::Link, id, "nodes", old, new);
+            self.diff_link(&a_links[i], other, &b_links[j], &mut changes)?;
+        }
+        let a_link_ids = changes.renamed(DiffObject::Link, a_link_ids);
+
+        let (a_controls, b_controls) = (self.controls()?, other.controls()?);
+        let control_text = |ids: (&[String], &[String]), c: &Control| {
+            let node = match c.control_type {
+                ControlType::LowLevel | ControlType::HiLevel => name(ids.0, c.node_index),
+                ControlType::Timer | ControlType::TimeOfDay => String::new(),
+            };
+            [
+                format!("{:?}", c.control_type),
+                name(ids.1, c.link_index),
+                node,
+                c.enabled.to_string(),
+            ]
+        };
+        for (position, (a, b)) in a_controls.iter().zip(&b_controls).enumerate() {
+            let id = (position + 1).to_string();
+            let old = control_text((&a_node_ids, &a_link_ids), a);
+            let new = control_text((&b_node_ids, &b_link_ids), b);
+            for (property, (x, y)) in ["type", "link", "node", "enabled"]
+                .iter()
+                .zip(old.into_iter().zip(new))
+            {
+                changes.text(DiffObject::Control, &id, property, x, y);
+            }
+            changes.number(DiffObject::Control, &id, "setting", a.setting, b.setting);
+            changes.number(DiffObject::Control, &id, "level", a.level, b.level);
+        }
+        for position in b_controls.len()..a_controls.len() {
+            changes.push(Change::Removed {
+                object: DiffObject::Control,
+                id: (position + 1).to_string(),
+            });
+        }
+        for position in a_controls.len()..b_controls.len() {
+            changes.push(Change::Added {
+                object: DiffObject::Control,
+                id: (position + 1).to_string(),
+            });
+        }
+
+        let (a_rules, b_rules) = (self.rules()?, other.rules()?);
+        let (a_ids, b_ids) = (ids(&a_rules, |r| &r.rule_id), ids(&b_rules, |r| &r.rule_id));
+        for (i, j) in changes.matching(DiffObject::Rule, &a_ids, &b_ids, options) {
+            let (a, b) = (&a_rules[i], &b_rules[j]);
+            let id = &b_ids[j];
+            changes.number(DiffObject::Rule, id, "priority", a.priority, b.priority);
+            changes.text(
+                DiffObject::Rule,
+                id,
+                "enabled",
+                a.enabled.to_string(),
+                b.enabled.to_string(),
+            );
+            let premises = |premises: &[Premise], nodes: &[String], links: &[String]| {
+                premises
+                    .iter()
+                    .map(|p| premise_text(p, nodes, links))
+                    .collect::<Vec<_>>()
+            };
+            let actions = |actions: &[ActionClause], links: &[String]| {
+                actions
+                    .iter()
+                    .map(|a| action_text(a, links))
+                    .collect::<Vec<_>>()
+            };
+            let clauses = [
+                (
+                    "premise",
+                    premises(&a.premises, &a_node_ids, &a_link_ids),
+                    premises(&b.premises, &b_node_ids, &b_link_ids),
+                ),
+                (
+                    "then",
+                    actions(&a.then_actions, &a_link_ids),
+                    actions(&b.then_actions, &b_link_ids),
+                ),
+                (
+                    "else",
+                    actions(&a.else_actions, &a_link_ids),
+                    actions(&b.else_actions, &b_link_ids),
+                ),
+            ];
+            for (clause, old, new) in clauses {
+                let count = format!("{clause} count");
+                changes.number(
+                    DiffObject::Rule,
+                    id,
+                    &count,
+                    old.len() as f64,
+                    new.len() as f64,
+                );
+                for (n, (x, y)) in old.into_iter().zip(new).enumerate() {
+                    changes.text(DiffObject::Rule, id, &format!("{clause} {}", n + 1), x, y);
+                }
+            }
+        }
+
+        Ok(ModelDiff {
+            changes: changes.list,
+        })
+    }
+
+    // Helper methods - Internal API
+
+    fn diff_options(&self, other: &EPANET, changes: &mut Changes) -> Result<()> {
+        let units = |ph: &EPANET| -> Result<String> {
+            Ok(format!("{:?}", ph.get_flow_units()?).to_uppercase())
+        };
+        changes.text(
+            DiffObject::Options,
+            "",
+            "flow units",
+            units(self)?,
+            units(other)?,
+        );
+        let (old, new) = (self.head_loss_type()?, other.head_loss_type()?);
+        changes.text(
+            DiffObject::Options,
+            "",
+            "head loss",
+            format!("{old:?}"),
+            format!("{new:?}"),
+        );
+        let (old, new) = (self.get_quality_type()?, other.get_quality_type()?);
+        changes.text(
+            DiffObject::Options,
+            "",
+            "quality",
+            format!("{old:?}"),
+            format!("{new:?}"),
+        );
+        for (parameter, property) in TIME_PARAMETERS {
+            let old = self.get_time_parameter(parameter)? as f64;
+            let new = other.get_time_parameter(parameter)? as f64;
+            changes.number(DiffObject::Options, "", property, old, new);
+        }
+        Ok(())
+    }
+
+    fn diff_node(&self, a: &Node, other: &EPANET, b: &Node, changes: &mut Changes) -> Result<()> {
+        let id = b.id.as_str();
+        let mut number = |property, x, y| changes.number(DiffObject::Node, id, property, x, y);
+        match (&a.kind, &b.kind) {
+            (NodeKind::Junction(x), NodeKind::Junction(y)) => {
+                number("elevation", x.elevation, y.elevation);
+                number("demand", x.demand, y.demand);
+                number("emitter", x.emitter, y.emitter);
+                let pattern = changes.rename(DiffObject::Pattern, &x.demand_pattern);
+                changes.text(
+                    DiffObject::Node,
+                    id,
+                    "pattern",
+                    pattern,
+                    y.demand_pattern.clone(),
+                );
+            }
+            (NodeKind::Tank(x), NodeKind::Tank(y)) => {
+                number("elevation", x.elevation, y.elevation);
+                number("init_level", x.init_level, y.init_level);
+                number("min_level", x.min_level, y.min_level);
+                number("max_level", x.max_level, y.max_level);
+                number("diameter", x.diameter, y.diameter);
+                number("min_volume", x.min_volume, y.min_volume);
+                let curve = changes.rename(DiffObject::Curve, &x.volume_curve);
+                changes.text(
+                    DiffObject::Node,
+                    id,
+                    "volume_curve",
+                    curve,
+                    y.volume_curve.clone(),
+                );
+            }
+            (NodeKind::Reservoir(x), NodeKind::Reservoir(y)) => {
+                number("elevation", x.elevation, y.elevation);
+            }
+            _ => {
+                let (old, new) = (
+                    format!("{:?}", a.node_type()),
+                    format!("{:?}", b.node_type()),
+                );
+                changes.text(DiffObject::Node, id, "type", old, new);
+                return Ok(());
+            }
+        }
+        if let (Ok(p), Ok(q)) = (
+            self.get_coordinates(a.index()),
+            other.get_coordinates(b.index()),
+        ) {
+            changes.number(DiffObject::Node, id, "x", p.0, q.0);
+            changes.number(DiffObject::Node, id, "y", p.1, q.1);
+        }
+        Ok(())
+    }
+
+    fn diff_link(&self, a: &Link, other: &EPANET, b: &Link, changes: &mut Changes) -> Result<()> {
+        let id = b.id.as_str();
+        let (a_type, b_type) = (a.link_type(), b.link_type());
+        if a_type != b_type {
+            changes.text(
+                DiffObject::Link,
+                id,
+                "type",
+                format!("{a_type:?}"),
+                format!("{b_type:?}"),
+            );
+            return Ok(());
+        }
+        let (old, new) = (format!("{:?}", a.status), format!("{:?}", b.status));
+        changes.text(DiffObject::Link, id, "status", old, new);
+        let mut number = |property, x, y| changes.number(DiffObject::Link, id, property, x, y);
+        match (&a.kind, &b.kind) {
+            (LinkKind::Pipe(x), LinkKind::Pipe(y)) | (LinkKind::CvPipe(x), LinkKind::CvPipe(y)) => {
+                number("length", x.length, y.length);
+                number("diameter", x.diameter, y.diameter);
+                number("roughness", x.roughness, y.roughness);
+                number("minor_loss", x.minor_loss, y.minor_loss);
+                number("leak_area", x.leak_area, y.leak_area);
+                number("leak_expansion", x.leak_expansion, y.leak_expansion);
+            }
+            (LinkKind::Pump(x), LinkKind::Pump(y)) => {
+                number("power", x.power, y.power);
+                number("speed", x.speed, y.speed);
+                let curve = |ph: &EPANET, index: std::option::Option<CurveIndex>| match index {
+                    Some(index) if index.0 > 0 => ph.get_curve_id(index),
+                    _ => Ok(String::new()),
+                };
+                let old = changes.rename(DiffObject::Curve, &curve(self, x.head_curve_index)?);
+                let new = curve(other, y.head_curve_index)?;
+                changes.text(DiffObject::Link, id, "head_curve", old, new);
+            }
+            (LinkKind::Valve(x), LinkKind::Valve(y)) => {
+                number("diameter", x.diameter, y.diameter);
+                number("setting", x.setting, y.setting);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Collects changes and the renames detected so far.
+struct Changes {
+    tolerance: f64,
+    list: Vec<Change>,
+    /// New ID of each renamed element, keyed by its old ID.
+    renames: HashMap<(DiffObject, String), String>,
+}
+
+impl Changes {
+    fn push(&mut self, change: Change) {
+        self.list.push(change);
+    }
+
+    fn number(&mut self, object: DiffObject, id: &str, property: &str, old: f64, new: f64) {
+        let scale = old.abs().max(new.abs()).max(1.0);
+        let equal = (old - new).abs() <= self.tolerance * scale || (old.is_nan() && new.is_nan());
+        if !equal {
+            self.modified(
+                object,
+                id,
+                property,
+                DiffValue::Number(old),
+                DiffValue::Number(new),
+            );
+        }
+    }
+
+    fn text(&mut self, object: DiffObject, id: &str, property: &str, old: String, new: String) {
+        if old != new {
+            self.modified(
+                object,
+                id,
+                property,
+                DiffValue::Text(old),
+                DiffValue::Text(new),
+            );
+        }
+    }
+
+    fn modified(
+        &mut self,
+        object: DiffObject,
+        id: &str,
+        property: &str,
+        old: DiffValue,
+        new: DiffValue,
+    ) {
+        self.push(Change::Modified {
+            object,
+            id: id.to_string(),
+            property: property.to_string(),
+            old,
+            new,
+        });
+    }
+
+    /// The ID `id` of the old model has in the new model.
+    fn rename(&self, object: DiffObject, id: &str) -> String {
+        self.renames
+            .get(&(object, id.to_string()))
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// Old-model IDs translated to the new model.
+    fn renamed(&self, object: DiffObject, ids: Vec<String>) -> Vec<String> {
+        ids.iter().map(|id| self.rename(object, id)).collect()
+    }
+
+    /// Matches elements by ID, then by position for unmatched IDs if renames are
+    /// detected. Records removed, renamed and added elements and returns the
+    /// `(old, new)` position of each matched pair, in old-model order.
+    fn matching(
+        &mut self,
+        object: DiffObject,
+        a: &[String],
+        b: &[String],
+        options: &DiffOptions,
+    ) -> Vec<(usize, usize)> {
+        let positions: HashMap<&str, usize> = b
+            .iter()
+            .enumerate()
+            .map(|(j, id)| (id.as_str(), j))
+            .collect();
+        let mut partners: Vec<_> = a
+            .iter()
+            .map(|id| positions.get(id.as_str()).copied())
+            .collect();
+        let mut matched = vec![false; b.len()];
+        for j in partners.iter().flatten() {
+            matched[*j] = true;
+        }
+        if options.detect_renames {
+            for (i, partner) in partners.iter_mut().enumerate() {
+                if partner.is_none() && i < b.len() && !matched[i] {
+                    *partner = Some(i);
+                    matched[i] = true;
+                    self.renames.insert((object, a[i].clone()), b[i].clone());
+                }
+            }
+        }
+        let mut pairs = Vec::new();
+        for (i, partner) in partners.into_iter().enumerate() {
+            match partner {
+                None => self.push(Change::Removed {
+                    object,
+                    id: a[i].clone(),
+                }),
+                Some(j) => {
+                    if a[i] != b[j] {
+                        self.push(Change::Renamed {
+                            object,
+                            old_id: a[i].clone(),
+                            new_id: b[j].clone(),
+                        });
+                    }
+                    pairs.push((i, j));
+                }
+            }
+        }
+        for (id, _) in b.iter().zip(&matched).filter(|(_, matched)| !**matched) {
+            self.push(Change::Added {
+                object,
+                id: id.clone(),
+            });
+        }
+        pairs
+    }
+}
+
+fn ids<T>(elements: &[T], id: impl Fn(&T) -> &String) -> Vec<String> {
+    elements.iter().map(|e| id(e).clone()).collect()
+}
+
+/// ID of the element at 1-based `index`, or the index itself if out of range.
+fn name(ids: &[String], index: impl Into<i32>) -> String {
+    let index = index.into();
+    usize::try_from(index - 1)
+        .ok()
+        .and_then(|i| ids.get(i))
+        .cloned()
+        .unwrap_or_else(|| index.to_string())
+}
+
+fn premise_text(premise: &Premise, nodes: &[String], links: &[String]) -> String {
+    let object = match premise.object {
+        PremiseObject::Node(index) => name(nodes, index),
+        PremiseObject::Link(index) => name(links, index),
+        PremiseObject::System => String::new(),
+    };
+    let value = match premise.status {
+        Some(status) => format!("{status:?}"),
+        None => premise.value.to_string(),
+    };
+    format!(
+        "{:?} {:?} {object} {:?} {:?} {value}",
+        premise.logical_operator,
+        premise.object.rule_object(),
+        premise.variable, premise.rule_operator
+    )
+}
+
+fn action_text(action: &ActionClause, links: &[String]) -> String {
+    format!(
+        "{} {:?} {}",
+        name(links, action.link_index),
+        action.status,
+        action.setting
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use crate::types::link::LinkProperty;
+    use crate::types::node::{NodeProperty, NodeType};
+    use crate::types::ActionCodeType::Unconditional;
+    use rstest::rstest;
+
+    #[rstest]
+    fn test_diff_identical(ph: EPANET, #[from(ph)] other: EPANET) {
+        let diff = ph.diff(&other, &DiffOptions::default()).unwrap();
+        assert!(diff.is_empty(), "{diff}");
+        assert_eq!(diff.to_json(), "{\"changes\":[]}");
+    }
+
+    #[rstest]
+    fn test_diff_changes(ph: EPANET, #[from(ph)] other: EPANET) {
+        let node = other.get_node_index("22").unwrap();
+        other
+            .set_node_value(node, NodeProperty::Elevation, 700.0)
+            .unwrap();
+        let link = other.get_link_index("112").unwrap();
+        // Below the tolerance.
+        other
+            .set_link_value(link, LinkProperty::Diameter, 12.0 + 1e-9)
+            .unwrap();
+        other
+            .delete_link(other.get_link_index("122").unwrap(), Unconditional)
+            .unwrap();
+        other.add_node("NEW", NodeType::Junction).unwrap();
+        other
+            .set_time_parameter(TimeParameter::Duration, 48 * 3600)
+            .unwrap();
+
+        let diff = ph.diff(&other, &DiffOptions::default()).unwrap();
+        assert_eq!(
+            diff.to_string(),
+            "~ options duration: 86400 -> 172800\n\
+             + node NEW\n\
+             ~ node 22 elevation: 695 -> 700\n\
+             - link 122\n"
+        );
+        assert!(diff.to_json().contains(
+            "{\"change\":\"modified\",\"object\":\"node\",\"id\":\"22\",\
+             \"property\":\"elevation\",\"old\":695,\"new\":700}"
+        ));
+    }
+
+    #[rstest]
+    fn test_diff_renames(ph: EPANET, #[from(ph)] other: EPANET) {
+        other
+            .set_node_id(other.get_node_index("10").unwrap(), "10A")
+            .unwrap();
+        other.set_pattern_id(1, "DAY").unwrap();
+
+        let diff = ph.diff(&other, &DiffOptions::default()).unwrap();
+        assert_eq!(diff.to_string(), "> pattern 1 -> DAY\n> node 10 -> 10A\n");
+        assert!(diff.to_json().contains(
+            "{\"change\":\"renamed\",\"object\":\"node\",\"id\":\"10A\",\"old_id\":\"10\"}"
+        ));
+
+        let options = DiffOptions {
+            detect_renames: false,
+            ..DiffOptions::default()
+        };
+        let diff = ph.diff(&other, &options).unwrap();
+        assert!(diff.changes.contains(&Change::Removed {
+            object: DiffObject::Node,
+            id: "10".into()
+        }));
+        assert!(diff.changes.contains(&Change::Modified {
+            object: DiffObject::Link,
+            id: "10".into(),
+            property: "nodes".into(),
+            old: DiffValue::Text("10-11".into()),
+            new: DiffValue::Text("10A-11".into()),
+        }));
+    }
+}
@@ -0,0 +1,264 @@
+//! Emitter API methods for EPANET.
+//!
+//! This module reads and sets the project-wide emitter discharge law and
+//! estimates, for candidate junctions, the emitter that would explain an
+//! excess minimum night flow, as a first pass at locating leaks.
+
+use crate::epanet_error::*;
+use crate::types::emitter::*;
+use crate::types::index::NodeIndex;
+use crate::types::node::{NodeProperty, NodeType};
+use crate::types::options::Option;
+use crate::EPANET;
+
+/// ## Emitter APIs
+impl EPANET {
+    /// Returns the emitter discharge law shared by all junctions.
+    pub fn get_emitter(&self) -> Result<Emitter> {
+        Ok(Emitter {
+            exponent: self.get_option(Option::EmitExpon)?,
+            backflow: self.get_option(Option::EmitBackflow)? != 0.0,
+        })
+    }
+
+    /// Sets the emitter discharge law shared by all junctions.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 202 if the exponent is not positive and finite.
+    pub fn set_emitter(&self, emitter: Emitter) -> Result<()> {
+        if !(emitter.exponent.is_finite() && emitter.exponent > 0.0) {
+            return Err(EPANETError::from(202)
+                .with_context(format!("invalid emitter exponent {}", emitter.exponent)));
+        }
+        self.set_option(Option::EmitExpon, emitter.exponent)?;
+        self.set_option(Option::EmitBackflow, emitter.backflow as i32 as f64)
+    }
+
+    /// Estimates, for each candidate junction, the emitter coefficient that
+    /// discharges [`excess_flow`](LeakLocalizationOptions::excess_flow) at the
+    /// minimum night flow.
+    ///
+    /// Each candidate is solved as a steady-state snapshot at
+    /// [`time`](LeakLocalizationOptions::time), refining the coefficient until
+    /// the emitter flow matches the excess flow, since the leak itself lowers
+    /// the pressure driving it. With pressure sensors, candidates are returned
+    /// best match first; without, in candidate order. Emitter coefficients are
+    /// restored afterwards.
+    ///
+    /// A single flow measurement can be explained by a leak almost anywhere;
+    /// the sensor ranking is what narrows the search.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 202 if the excess flow is not positive, error 203 if a
+    /// candidate or sensor does not exist or a candidate is not a junction, or
+    /// an [`EPANETError`] if the solver fails.
+    pub fn localize_leak(&self, options: &LeakLocalizationOptions) -> Result<Vec<LeakCandidate>> {
+        if !(options.excess_flow.is_finite() && options.excess_flow > 0.0) {
+            return Err(EPANETError::from(202)
+                .with_context(format!("invalid excess flow {}", options.excess_flow)));
+        }
+        let candidates = match &options.candidates {
+            Some(ids) => ids
+                .iter()
+                .map(|id| {
+                    let index = self.get_node_index(id)?;
+                    if self.get_node_type(index)? != NodeType::Junction {
+                        return Err(EPANETError::from(203)
+                            .with_context(format!("leak candidate '{id}' is not a junction")));
+                    }
+                    Ok((index, id.clone()))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => self
+                .iter_nodes_of_type(NodeType::Junction)?
+                .map(|node| {
+                    let node = node?;
+                    Ok((node.index(), node.id()?.into()))
+                })
+                .collect::<Result<Vec<_>>>()?,
+        };
+        let sensors = options
+            .pressure_sensors
+            .iter()
+            .map(|(id, pressure)| Ok((self.get_node_index(id)?, *pressure)))
+            .collect::<Result<Vec<_>>>()?;
+        let law = self.get_emitter()?;
+
+        let mut baseline = Vec::new();
+        self.solve_snapshot_with(options.time, || {
+            baseline = self.get_node_values(NodeProperty::Pressure)?;
+            Ok(())
+        })?;
+
+        let mut results = Vec::with_capacity(candidates.len());
+        for (node, id) in candidates {
+            let existing = self.get_node_value(node, NodeProperty::Emitter)?;
+            let result = self.fit_leak_emitter(node, existing, &baseline, &sensors, law, options);
+            self.set_node_value(node, NodeProperty::Emitter, existing)?;
+            let (coefficient, pressure, sensor_error, converged) = result?;
+            results.push(LeakCandidate {
+                id,
+                coefficient,
+                pressure,
+                sensor_error,
+                converged,
+            });
+        }
+        if !sensors.is_empty() {
+            results.sort_by(|a, b| {
+                let error = |c: &LeakCandidate| c.sensor_error.unwrap_or(f64::INFINITY);
+                b.converged
+                    .cmp(&a.converged)
+                    .then(error(a).total_cmp(&error(b)))
+            });
+        }
+        Ok(results)
+    }
+
+    // Helper methods - Internal API
+
+    /// Refines the extra emitter coefficient at `node` until it discharges the
+    /// excess flow, returning the coefficient, the node pressure, the sensor
+    /// error and whether the flow converged.
+    fn fit_leak_emitter(
+        &self,
+        node: NodeIndex,
+        existing: f64,
+        baseline: &[f64],
+        sensors: &[(NodeIndex, f64)],
+        law: Emitter,
+        options: &LeakLocalizationOptions,
+    ) -> Result<(f64, f64, std::option::Option<f64>, bool)> {
+        let excess = options.excess_flow;
+        let Some(mut coefficient) = law.coefficient_for(excess, baseline[node.offset()]) else {
+            return Ok((0.0, baseline[node.offset()], None, false));
+        };
+        let (mut pressure, mut sensor_error, mut converged) = (0.0, None, false);
+        for _ in 0..options.max_iterations.max(1) {
+            self.set_node_value(node, NodeProperty::Emitter, existing + coefficient)?;
+            let mut leak_flow = 0.0;
+            self.solve_snapshot_with(options.time, || {
+                pressure = self.get_node_value(node, NodeProperty::Pressure)?;
+                leak_flow = self.get_node_value(node, NodeProperty::EmitterFlow)? * coefficient
+                    / (existing + coefficient);
+                if !sensors.is_empty() {
+                    let mut sum = 0.0;
+                    for (sensor, measured) in sensors {
+                        let simulated = self.get_node_value(*sensor, NodeProperty::Pressure)?;
+                        sum += (simulated - measured).powi(2);
+                    }
+                    sensor_error = Some((sum / sensors.len() as f64).sqrt());
+                }
+                Ok(())
+            })?;
+            if (leak_flow - excess).abs() <= options.tolerance * excess {
+                converged = true;
+                break;
+            }
+            if leak_flow <= 0.0 {
+                break;
+            }
+            coefficient *= excess / leak_flow;
+        }
+        Ok((coefficient, pressure, sensor_error, converged))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use rstest::rstest;
+
+    #[rstest]
+    fn test_emitter_law(ph: EPANET) {
+        assert_eq!(ph.get_emitter().unwrap(), Emitter::default());
+        let law = Emitter {
+            exponent: 0.6,
+            backflow: false,
+        };
+        ph.set_emitter(law).unwrap();
+        assert_eq!(ph.get_emitter().unwrap(), law);
+        assert_eq!(law.flow(2.0, -10.0), 0.0);
+        let coefficient = law.coefficient_for(law.flow(2.0, 50.0), 50.0).unwrap();
+        assert!((coefficient - 2.0).abs() < 1e-12);
+        assert_eq!(
+            ph.set_emitter(Emitter {
+                exponent: 0.0,
+                backflow: true
+            })
+            .unwrap_err()
+            .code,
+            202
+        );
+
+        let mut node = ph.get_node("22").unwrap();
+        node.as_junction_mut().unwrap().emitter = 1.5;
+        node.update().unwrap();
+        assert_eq!(
+            ph.get_node_value(node.index(), NodeProperty::Emitter)
+                .unwrap(),
+            1.5
+        );
+        assert_eq!(
+            ph.get_node("22").unwrap().as_junction().unwrap().emitter,
+            1.5
+        );
+    }
+
+    #[rstest]
+    fn test_localize_leak(ph: EPANET) {
+        // Place a leak at junction 22 and measure the pressures it causes
+        let leak = ph.get_node_index("22").unwrap();
+        let sensors = ["12", "23", "31"];
+        ph.set_node_value(leak, NodeProperty::Emitter, 10.0)
+            .unwrap();
+        let (mut excess, mut measured) = (0.0, Vec::new());
+        ph.solve_snapshot_with(3 * 3600, || {
+            excess = ph.get_node_value(leak, NodeProperty::EmitterFlow)?;
+            for id in sensors {
+                let pressure = ph.get_node_value(ph.get_node_index(id)?, NodeProperty::Pressure)?;
+                measured.push((id.to_string(), pressure));
+            }
+            Ok(())
+        })
+        .unwrap();
+        ph.set_node_value(leak, NodeProperty::Emitter, 0.0).unwrap();
+
+        let options = LeakLocalizationOptions {
+            excess_flow: excess,
+            pressure_sensors: measured,
+            ..LeakLocalizationOptions::default()
+        };
+        let candidates = ph.localize_leak(&options).unwrap();
+        assert_eq!(candidates.len(), 9);
+        assert!(candidates.iter().all(|c| c.converged));
+        let best = &candidates[0];
+        assert_eq!(best.id, "22");
+        assert!((best.coefficient - 10.0).abs() < 0.1);
+        assert!(best.sensor_error.unwrap() < 0.1);
+        assert_eq!(ph.get_node_value(leak, NodeProperty::Emitter).unwrap(), 0.0);
+
+        let options = LeakLocalizationOptions {
+            excess_flow: excess,
+            candidates: Some(vec!["32".into(), "11".into()]),
+            ..LeakLocalizationOptions::default()
+        };
+        let ids: Vec<String> = ph
+            .localize_leak(&options)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(ids, vec!["32", "11"]);
+
+        let options = LeakLocalizationOptions {
+            excess_flow: excess,
+            candidates: Some(vec!["2".into()]),
+            ..LeakLocalizationOptions::default()
+        };
+        assert_eq!(ph.localize_leak(&options).unwrap_err().code, 203);
+    }
+}
@@ -0,0 +1,255 @@
+//! Result export API methods for EPANET.
+//!
+//! This module contains methods for creating a streaming
+//! [`ResultWriter`] and for running a simulation straight to a CSV or NDJSON
+//! sink without collecting results in memory.
+
+use crate::epanet_error::*;
+use crate::types::analysis::{InitHydOption, StepResult};
+use crate::types::export::*;
+use crate::types::link::LinkProperty;
+use crate::types::node::NodeProperty;
+use crate::types::options::{QualityType, TimeParameter};
+use crate::types::CountType;
+use crate::EPANET;
+use std::io::Write;
+
+/// `(index, id)` of each exported node or link.
+pub(crate) type ExportElements = Vec<(i32, String)>;
+
+/// ## Export APIs
+impl EPANET {
+    /// Creates a [`ResultWriter`] that writes to `out`.
+    ///
+    /// Element IDs and reporting times are resolved now, so the network must not
+    /// gain or lose nodes or links while the writer is in use. Feed it with
+    /// [`ResultWriter::write_step`] from any solver loop and call
+    /// [`ResultWriter::finish`] at the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if a node or link ID in the options does not exist.
+    pub fn result_writer<W: Write>(
+        &self,
+        out: W,
+        options: ExportOptions,
+    ) -> Result<ResultWriter<W>> {
+        let (nodes, links) = self.export_elements(&options)?;
+        let (report_start, report_step) = self.report_times()?;
+        Ok(ResultWriter {
+            out,
+            node_names: options.node_properties.iter().map(property_name).collect(),
+            link_names: options.link_properties.iter().map(property_name).collect(),
+            options,
+            nodes,
+            links,
+            report_start,
+            report_step,
+            header_written: false,
+            steps_written: 0,
+        })
+    }
+
+    /// Runs an extended period simulation, streaming results to `out` as each step
+    /// is solved, and returns the sink.
+    ///
+    /// Hydraulics are solved on their own, tolerating solver warnings, unless
+    /// [`NodeProperty::Quality`] or [`LinkProperty::LinkQual`]
+    /// is exported and a quality analysis is defined, in which case hydraulics and
+    /// quality are solved together.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if the solver fails, an ID does not exist, or the
+    /// sink cannot be written (code 308).
+    pub fn export_results<W: Write>(&self, out: W, options: ExportOptions) -> Result<W> {
+        let with_quality = self.exports_quality(&options)?;
+        let mut writer = self.result_writer(out, options)?;
+        self.run_export_simulation(with_quality, |project, t| writer.write_step(project, t))?;
+        writer.finish()
+    }
+
+    // Helper methods - Internal API
+
+    /// Resolves the `(index, id)` of the nodes and links selected by `options`.
+    pub(crate) fn export_elements(
+        &self,
+        options: &ExportOptions,
+    ) -> Result<(ExportElements, ExportElements)> {
+        let nodes = match &options.nodes {
+            Some(ids) => ids
+                .iter()
+                .map(|id| Ok((self.get_node_index(id)?.0, id.clone())))
+                .collect::<Result<Vec<_>>>()?,
+            None => (1..=self.get_count(CountType::NodeCount)?)
+                .map(|i| Ok((i, self.get_node_id(i)?)))
+                .collect::<Result<Vec<_>>>()?,
+        };
+        let links = match &options.links {
+            Some(ids) => ids
+                .iter()
+                .map(|id| Ok((self.get_link_index(id)?.0, id.clone())))
+                .collect::<Result<Vec<_>>>()?,
+            None => (1..=self.get_count(CountType::LinkCount)?)
+                .map(|i| Ok((i, self.get_link_id(i)?)))
+                .collect::<Result<Vec<_>>>()?,
+        };
+        Ok((nodes, links))
+    }
+
+    /// `true` when `options` exports a quality property and the model defines a
+    /// quality analysis.
+    pub(crate) fn exports_quality(&self, options: &ExportOptions) -> Result<bool> {
+        Ok(self.get_quality_info()?.quality_type != QualityType::None
+            && (options.node_properties.contains(&NodeProperty::Quality)
+                || options.link_properties.contains(&LinkProperty::LinkQual)))
+    }
+
+    /// The report start time and report time step, in seconds, for
+    /// [`is_report_time`].
+    pub(crate) fn report_times(&self) -> Result<(i64, i64)> {
+        Ok((
+            self.get_time_parameter(TimeParameter::ReportStart)? as i64,
+            self.get_time_parameter(TimeParameter::ReportStep)?.max(1) as i64,
+        ))
+    }
+
+    /// Runs an extended period simulation, calling `observe` with the project and
+    /// the time of every step. Hydraulics run alone (tolerating warnings) unless
+    /// `with_quality` is set.
+    pub(crate) fn run_export_simulation<F>(&self, with_quality: bool, mut observe: F) -> Result<()>
+    where
+        F: FnMut(&EPANET, i64) -> Result<()>,
+    {
+        if with_quality {
+            let mut solver = self
+                .solver()
+                .init_h(InitHydOption::NoSave)?
+                .init_q(InitHydOption::NoSave)?
+                .run()?;
+            observe(solver.project(), 0)?;
+            while let StepResult::Continue { current_time, .. } = solver.next()? {
+                observe(solver.project(), current_time)?;
+            }
+            solver.close()
+        } else {
+            self.run_hydraulics_with(|t| observe(self, t))?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use rstest::rstest;
+
+    fn options(format: ExportFormat, layout: ExportLayout) -> ExportOptions {
+        ExportOptions {
+            format,
+            layout,
+            node_properties: vec![NodeProperty::Pressure],
+            link_properties: vec![LinkProperty::Flow],
+            nodes: Some(vec!["10".into(), "22".into()]),
+            links: Some(vec!["10".into()]),
+            ..ExportOptions::default()
+        }
+    }
+
+    fn export(ph: &EPANET, options: ExportOptions) -> String {
+        String::from_utf8(ph.export_results(Vec::new(), options).unwrap()).unwrap()
+    }
+
+    #[rstest]
+    fn test_export_csv_long(ph: EPANET) {
+        let text = export(&ph, options(ExportFormat::Csv, ExportLayout::Long));
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "time,element,id,property,value");
+        // 25 hourly reporting times x 3 values.
+        assert_eq!(lines.len(), 1 + 25 * 3);
+        assert!(lines[1].starts_with("0,node,10,pressure,"));
+        assert!(lines[3].starts_with("0,link,10,flow,"));
+        assert!(lines[4].starts_with("3600,node,10,pressure,"));
+    }
+
+    #[rstest]
+    fn test_export_csv_wide_clock(ph: EPANET) {
+        let options = ExportOptions {
+            time_index: TimeIndex::Clock,
+            ..options(ExportFormat::Csv, ExportLayout::Wide)
+        };
+        let text = export(&ph, options);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "time,node:10:pressure,node:22:pressure,link:10:flow"
+        );
+        assert_eq!(lines.len(), 1 + 25);
+        assert!(lines[2].starts_with("1:00:00,"));
+        assert_eq!(lines[25].split(',').count(), 4);
+        assert!(lines[25].starts_with("24:00:00,"));
+    }
+
+    #[rstest]
+    fn test_export_ndjson(ph: EPANET) {
+        let wide = export(&ph, options(ExportFormat::NdJson, ExportLayout::Wide));
+        let first = wide.lines().next().unwrap();
+        assert!(first.starts_with("{\"time\":0,\"nodes\":{\"10\":{\"pressure\":"));
+        assert!(first.contains(",\"links\":{\"10\":{\"flow\":"));
+        assert_eq!(wide.lines().count(), 25);
+
+        let long = export(&ph, options(ExportFormat::NdJson, ExportLayout::Long));
+        assert!(long.lines().next().unwrap().starts_with(
+            "{\"time\":0,\"element\":\"node\",\"id\":\"10\",\"property\":\"pressure\",\"value\":"
+        ));
+        assert_eq!(long.lines().count(), 25 * 3);
+    }
+
+    #[rstest]
+    fn test_export_streams_from_solver(ph: EPANET) {
+        let options = ExportOptions {
+            report_times_only: false,
+            ..options(ExportFormat::Csv, ExportLayout::Wide)
+        };
+        let mut writer = ph.result_writer(Vec::new(), options).unwrap();
+        let mut solver = ph
+            .solver()
+            .init_h(InitHydOption::NoSave)
+            .unwrap()
+            .run_h()
+            .unwrap();
+        writer.write_step(solver.project(), 0).unwrap();
+        while let StepResult::Continue { current_time, .. } = solver.next_h().unwrap() {
+            writer.write_step(solver.project(), current_time).unwrap();
+        }
+        // Every hydraulic step is written, including the intermediate control times.
+        assert!(writer.steps_written() > 25);
+        let steps = writer.steps_written();
+        let text = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(text.lines().count(), 1 + steps);
+    }
+
+    #[rstest]
+    fn test_export_quality(ph: EPANET) {
+        let options = ExportOptions {
+            node_properties: vec![NodeProperty::Quality],
+            link_properties: Vec::new(),
+            ..options(ExportFormat::Csv, ExportLayout::Long)
+        };
+        let text = export(&ph, options);
+        let last = text.lines().last().unwrap();
+        assert!(last.starts_with("86400,node,22,quality,"));
+        let value: f64 = last.rsplit(',').next().unwrap().parse().unwrap();
+        assert!(value > 0.0);
+    }
+
+    #[rstest]
+    fn test_export_unknown_id(ph: EPANET) {
+        let options = ExportOptions {
+            nodes: Some(vec!["NOPE".into()]),
+            ..ExportOptions::default()
+        };
+        assert!(ph.result_writer(Vec::new(), options).is_err());
+    }
+}
@@ -0,0 +1,655 @@
+//! GeoJSON API methods for EPANET.
+//!
+//! This module contains methods, available with the `geojson` feature, for
+//! writing the network geometry and attributes as a GeoJSON `FeatureCollection`
+//! and for creating nodes and links from GeoJSON features.
+
+use crate::epanet_error::*;
+use crate::types::export::{property_name, save_error};
+use crate::types::geojson::*;
+use crate::types::link::{Link, LinkKind, LinkProperty, LinkStatusType, LinkType};
+use crate::types::node::{Node, NodeKind, NodeType};
+use crate::types::CountType;
+use crate::EPANET;
+use serde_json::{json, Map, Value};
+use std::io::Write;
+
+const NODE_TYPES: [NodeType; 3] = [NodeType::Junction, NodeType::Reservoir, NodeType::Tank];
+
+const LINK_TYPES: [LinkType; 10] = [
+    LinkType::CvPipe,
+    LinkType::Pipe,
+    LinkType::Pump,
+    LinkType::Prv,
+    LinkType::Psv,
+    LinkType::Pbv,
+    LinkType::Fcv,
+    LinkType::Tcv,
+    LinkType::Gpv,
+    LinkType::Pcv,
+];
+
+/// ## GeoJSON APIs
+impl EPANET {
+    /// Writes the network to `out` as a GeoJSON `FeatureCollection` and returns the
+    /// sink.
+    ///
+    /// Nodes become `Point` features and links `LineString` features through their
+    /// vertices. Every feature carries the static attributes of its element type
+    /// under the names in [`GeoJsonExportOptions::fields`], followed by the
+    /// requested results. Elements whose coordinates are undefined get a `null`
+    /// geometry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if an element cannot be read, or error 308 if the
+    /// sink fails.
+    pub fn export_geojson<W: Write>(
+        &self,
+        mut out: W,
+        options: &GeoJsonExportOptions,
+    ) -> Result<W> {
+        let fields = &options.fields;
+        let mut features = Vec::new();
+        if options.nodes {
+            for node in self.nodes()? {
+                let mut properties = Map::new();
+                properties.insert(fields.id.clone(), json!(node.id));
+                properties.insert(
+                    fields.kind.clone(),
+                    json!(format!("{:?}", node.node_type())),
+                );
+                match &node.kind {
+                    NodeKind::Junction(data) => {
+                        properties.insert(fields.elevation.clone(), json!(data.elevation));
+                        properties.insert(fields.base_demand.clone(), json!(data.demand));
+                        properties
+                            .insert(fields.demand_pattern.clone(), json!(data.demand_pattern));
+                    }
+                    NodeKind::Tank(data) => {
+                        properties.insert(fields.elevation.clone(), json!(data.elevation));
+                        properties.insert(fields.init_level.clone(), json!(data.init_level));
+                        properties.insert(fields.min_level.clone(), json!(data.min_level));
+                        properties.insert(fields.max_level.clone(), json!(data.max_level));
+                        properties.insert(fields.diameter.clone(), json!(data.diameter));
+                        properties.insert(fields.min_volume.clone(), json!(data.min_volume));
+                        properties.insert(fields.volume_curve.clone(), json!(data.volume_curve));
+                    }
+                    NodeKind::Reservoir(data) => {
+                        properties.insert(fields.elevation.clone(), json!(data.elevation));
+                    }
+                }
+                for property in &options.node_results {
+                    let value = self.get_node_value(node.index(), *property)?;
+                    properties.insert(property_name(property), json!(value));
+                }
+                let geometry = match self.get_coordinates(node.index()) {
+                    Ok((x, y)) => json!({ "type": "Point", "coordinates": [x, y] }),
+                    Err(_) => Value::Null,
+                };
+                features.push(
+                    json!({ "type": "Feature", "geometry": geometry, "properties": properties }),
+                );
+            }
+        }
+        if options.links {
+            for link in self.links()? {
+                let mut properties = Map::new();
+                properties.insert(fields.id.clone(), json!(link.id));
+                properties.insert(
+                    fields.kind.clone(),
+                    json!(format!("{:?}", self.get_link_type(link.index())?)),
+                );
+                properties.insert(
+                    fields.from_node.clone(),
+                    json!(self.get_node_id(link.from_node)?),
+                );
+                properties.insert(
+                    fields.to_node.clone(),
+                    json!(self.get_node_id(link.to_node)?),
+                );
+                properties.insert(
+                    fields.initial_status.clone(),
+                    json!(format!("{:?}", link.status)),
+                );
+                match &link.kind {
+                    LinkKind::Pipe(data) | LinkKind::CvPipe(data) => {
+                        properties.insert(fields.length.clone(), json!(data.length));
+                        properties.insert(fields.diameter.clone(), json!(data.diameter));
+                        properties.insert(fields.roughness.clone(), json!(data.roughness));
+                        properties.insert(fields.minor_loss.clone(), json!(data.minor_loss));
+                    }
+                    LinkKind::Pump(data) => {
+                        properties.insert(fields.power.clone(), json!(data.power));
+                        properties.insert(fields.speed.clone(), json!(data.speed));
+                        let curve = match data.head_curve_index {
+                            Some(index) if index.0 > 0 => json!(self.get_curve_id(index)?),
+                            _ => Value::Null,
+                        };
+                        properties.insert(fields.head_curve.clone(), curve);
+                    }
+                    LinkKind::Valve(data) => {
+                        properties.insert(fields.diameter.clone(), json!(data.diameter));
+                        properties.insert(fields.setting.clone(), json!(data.setting));
+                    }
+                }
+                for property in &options.link_results {
+                    let value = self.get_link_value(link.index(), *property)?;
+                    properties.insert(property_name(property), json!(value));
+                }
+                let geometry = match (
+                    self.get_coordinates(link.from_node),
+                    self.get_coordinates(link.to_node),
+                ) {
+                    (Ok(start), Ok(end)) => {
+                        let mut positions = vec![[start.0, start.1]];
+                        positions.extend(link.vertices()?.into_iter().map(|(x, y)| [x, y]));
+                        positions.push([end.0, end.1]);
+                        json!({ "type": "LineString", "coordinates": positions })
+                    }
+                    _ => Value::Null,
+                };
+                features.push(
+                    json!({ "type": "Feature", "geometry": geometry, "properties": properties }),
+                );
+            }
+        }
+
+        let collection = json!({ "type": "FeatureCollection", "features": features });
+        serde_json::to_writer(&mut out, &collection)
+            .map_err(|e| EPANETError::from(308).with_context(e.to_string()))?;
+        out.flush().map_err(save_error)?;
+        Ok(out)
+    }
+
+    /// Creates nodes and links from a GeoJSON `FeatureCollection` or single
+    /// `Feature`.
+    ///
+    /// `Point` features become nodes and `LineString` features links, with
+    /// attributes read from the properties named in
+    /// [`GeoJsonImportOptions::fields`]. Numbers may also be given as numeric
+    /// strings. All nodes are created before any link, so features may appear in any
+    /// order. A link without start or end node properties is connected to the nodes
+    /// at its first and last positions, and its interior positions become vertices.
+    ///
+    /// Missing attributes default to zero (elevation, demand, minor loss, minimum
+    /// volume, valve setting), 1 (pump speed), the line's planar length (pipe
+    /// length) or open status. Tank levels and diameter, pipe diameter and
+    /// roughness, valve diameter, and either a pump's power or head curve are
+    /// required.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 201 if the text is not valid JSON, 250 if it is not GeoJSON or
+    /// a required attribute is missing, 203 if a link end cannot be matched to a
+    /// node, or the [`EPANETError`] raised while creating an element. Nodes and
+    /// links created before an error remain in the project.
+    pub fn import_geojson(
+        &self,
+        geojson: &str,
+        options: &GeoJsonImportOptions,
+    ) -> Result<GeoJsonImport> {
+        let value: Value = serde_json::from_str(geojson)
+            .map_err(|e| EPANETError::from(201).with_context(e.to_string()))?;
+        let features = match value.get("type").and_then(Value::as_str) {
+            Some("FeatureCollection") => value
+                .get("features")
+                .and_then(Value::as_array)
+                .ok_or_else(|| invalid("FeatureCollection without a features array"))?
+                .iter()
+                .collect(),
+            Some("Feature") => vec![&value],
+            _ => return Err(invalid("expected a GeoJSON Feature or FeatureCollection")),
+        };
+
+        let mut imported = GeoJsonImport::default();
+        let mut lines = Vec::new();
+        for feature in features {
+            let properties = Properties::new(feature, &options.fields)?;
+            let geometry = feature.get("geometry").unwrap_or(&Value::Null);
+            match geometry.get("type").and_then(Value::as_str) {
+                Some("LineString") => lines.push((properties, geometry)),
+                None if properties.text(&options.fields.from_node).is_some() => {
+                    lines.push((properties, geometry))
+                }
+                Some("Point") | None => {
+                    self.import_node(&properties, geometry, options)?;
+                    imported.nodes.push(properties.id);
+                }
+                Some(other) => {
+                    return Err(invalid(format!(
+                        "{}: unsupported geometry {other}",
+                        properties.id
+                    )))
+                }
+            }
+        }
+        for (properties, geometry) in lines {
+            self.import_link(&properties, geometry, options)?;
+            imported.links.push(properties.id);
+        }
+        Ok(imported)
+    }
+
+    // Helper methods - Internal API
+
+    /// Creates the node described by a `Point` (or geometry-less) feature.
+    fn import_node(
+        &self,
+        properties: &Properties,
+        geometry: &Value,
+        options: &GeoJsonImportOptions,
+    ) -> Result<()> {
+        let fields = &options.fields;
+        let id = properties.id.as_str();
+        let kind = match properties.text(&fields.kind) {
+            Some(name) => parse_kind(&name, &NODE_TYPES)
+                .ok_or_else(|| invalid(format!("{id}: unknown node type {name}")))?,
+            None => options.default_node_type,
+        };
+        let elevation = properties.number(&fields.elevation)?.unwrap_or(0.0);
+        let index = match kind {
+            NodeType::Junction => Node::new_junction(
+                self,
+                id,
+                elevation,
+                properties.number(&fields.base_demand)?.unwrap_or(0.0),
+                &properties.text(&fields.demand_pattern).unwrap_or_default(),
+            )?
+            .index(),
+            NodeType::Tank => Node::new_tank(
+                self,
+                id,
+                elevation,
+                properties.required(&fields.init_level)?,
+                properties.required(&fields.min_level)?,
+                properties.required(&fields.max_level)?,
+                properties.required(&fields.diameter)?,
+                properties.number(&fields.min_volume)?.unwrap_or(0.0),
+                &properties.text(&fields.volume_curve).unwrap_or_default(),
+            )?
+            .index(),
+            NodeType::Reservoir => Node::new_reservoir(self, id, elevation)?.index(),
+        };
+        if let Some(&(x, y)) = positions(geometry, id)?.first() {
+            self.set_coordinates(index, x, y)?;
+        }
+        Ok(())
+    }
+
+    /// Creates the link described by a `LineString` (or geometry-less) feature.
+    fn import_link(
+        &self,
+        properties: &Properties,
+        geometry: &Value,
+        options: &GeoJsonImportOptions,
+    ) -> Result<()> {
+        let fields = &options.fields;
+        let id = properties.id.as_str();
+        let positions = positions(geometry, id)?;
+        let from = match properties.text(&fields.from_node) {
+            Some(node) => node,
+            None => self.node_at(positions.first(), options.snap_tolerance, id, "start")?,
+        };
+        let to = match properties.text(&fields.to_node) {
+            Some(node) => node,
+            None => self.node_at(positions.last(), options.snap_tolerance, id, "end")?,
+        };
+        let kind = match properties.text(&fields.kind) {
+            Some(name) => parse_kind(&name, &LINK_TYPES)
+                .ok_or_else(|| invalid(format!("{id}: unknown link type {name}")))?,
+            None => options.default_link_type,
+        };
+
+        let index = match kind {
+            LinkType::Pipe | LinkType::CvPipe => {
+                let length = match properties.number(&fields.length)? {
+                    Some(length) => length,
+                    None if positions.len() > 1 => positions
+                        .windows(2)
+                        .map(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1))
+                        .sum(),
+                    None => properties.required(&fields.length)?,
+                };
+                let diameter = properties.required(&fields.diameter)?;
+                let roughness = properties.required(&fields.roughness)?;
+                let minor_loss = properties.number(&fields.minor_loss)?.unwrap_or(0.0);
+                if kind == LinkType::Pipe {
+                    Link::new_pipe(
+                        self, id, &from, &to, length, diameter, roughness, minor_loss,
+                    )?
+                    .index()
+                } else {
+                    let index = self.add_link(id, LinkType::CvPipe, &from, &to)?;
+                    self.set_pipe_data(index, length, diameter, roughness, minor_loss)?;
+                    index
+                }
+            }
+            LinkType::Pump => {
+                let speed = properties.number(&fields.speed)?.unwrap_or(1.0);
+                let curve = properties
+                    .text(&fields.head_curve)
+                    .map(|curve| self.get_curve_index(&curve))
+                    .transpose()?;
+                match (
+                    properties.number(&fields.power)?.filter(|p| *p > 0.0),
+                    curve,
+                ) {
+                    (Some(power), curve) => {
+                        Link::new_pump(self, id, &from, &to, power, speed, curve)?.index()
+                    }
+                    (None, Some(curve)) => {
+                        let index = self.add_link(id, LinkType::Pump, &from, &to)?;
+                        self.set_head_curve_index(index, curve)?;
+                        self.set_link_value(index, LinkProperty::InitSetting, speed)?;
+                        index
+                    }
+                    (None, None) => {
+                        return Err(invalid(format!(
+                            "{id}: missing {} or {}",
+                            fields.power, fields.head_curve
+                        )))
+                    }
+                }
+            }
+            valve => Link::new_valve(
+                self,
+                id,
+                valve,
+                &from,
+                &to,
+                properties.required(&fields.diameter)?,
+                properties.number(&fields.setting)?.unwrap_or(0.0),
+            )?
+            .index(),
+        };
+
+        let closed = format!("{:?}", LinkStatusType::Closed);
+        if properties
+            .text(&fields.initial_status)
+            .is_some_and(|status| status.eq_ignore_ascii_case(&closed))
+        {
+            self.set_link_value(
+                index,
+                LinkProperty::InitStatus,
+                LinkStatusType::Closed as i32 as f64,
+            )?;
+        }
+        if positions.len() > 2 {
+            self.set_vertices(index, positions[1..positions.len() - 1].to_vec())?;
+        }
+        Ok(())
+    }
+
+    /// ID of the node nearest `position` within `tolerance`.
+    fn node_at(
+        &self,
+        position: std::option::Option<&(f64, f64)>,
+        tolerance: f64,
+        link_id: &str,
+        end: &str,
+    ) -> Result<String> {
+        let missing =
+            || EPANETError::from(203).with_context(format!("{link_id}: no node at {end} of line"));
+        let &(x, y) = position.ok_or_else(missing)?;
+        let mut nearest = None;
+        for index in 1..=self.get_count(CountType::NodeCount)? {
+            let Ok((nx, ny)) = self.get_coordinates(index) else {
+                continue;
+            };
+            let distance = (nx - x).hypot(ny - y);
+            if distance <= tolerance && nearest.is_none_or(|(_, best)| distance < best) {
+                nearest = Some((index, distance));
+            }
+        }
+        let (index, _) = nearest.ok_or_else(missing)?;
+        self.get_node_id(index)
+    }
+}
+
+/// The properties of one feature, with its ID resolved.
+struct Properties<'a> {
+    id: String,
+    values: std::option::Option<&'a Map<String, Value>>,
+}
+
+impl<'a> Properties<'a> {
+    fn new(feature: &'a Value, fields: &GeoJsonFields) -> Result<Self> {
+        let mut properties = Properties {
+            id: String::new(),
+            values: feature.get("properties").and_then(Value::as_object),
+        };
+        properties.id = properties
+            .text(&fields.id)
+            .ok_or_else(|| invalid(format!("feature without {}", fields.id)))?;
+        Ok(properties)
+    }
+
+    /// A string property, or a number written as text.
+    fn text(&self, field: &str) -> std::option::Option<String> {
+        match self.values?.get(field)? {
+            Value::String(text) if !text.is_empty() => Some(text.clone()),
+            Value::Number(number) => Some(number.to_string()),
+            _ => None,
+        }
+    }
+
+    fn number(&self, field: &str) -> Result<std::option::Option<f64>> {
+        match self.values.and_then(|values| values.get(field)) {
+            Some(Value::Number(number)) => Ok(number.as_f64()),
+            Some(Value::String(text)) if !text.trim().is_empty() => {
+                text.trim().parse().map(Some).map_err(|_| {
+                    EPANETError::from(202).with_context(format!("{}: {field} = {text:?}", self.id))
+                })
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn required(&self, field: &str) -> Result<f64> {
+        self.number(field)?
+            .ok_or_else(|| invalid(format!("{}: missing {field}", self.id)))
+    }
+}
+
+/// Positions of a `Point` or `LineString` geometry; empty for a `null` geometry.
+fn positions(geometry: &Value, id: &str) -> Result<Vec<(f64, f64)>> {
+    let position = |value: &Value| match value.as_array().map(Vec::as_slice) {
+        Some([x, y, ..]) => x.as_f64().zip(y.as_f64()),
+        _ => None,
+    };
+    let malformed = || invalid(format!("{id}: malformed coordinates"));
+    match geometry.get("coordinates") {
+        None => Ok(Vec::new()),
+        Some(coordinates) if geometry.get("type").and_then(Value::as_str) == Some("Point") => {
+            Ok(vec![position(coordinates).ok_or_else(malformed)?])
+        }
+        Some(coordinates) => coordinates
+            .as_array()
+            .ok_or_else(malformed)?
+            .iter()
+            .map(|value| position(value).ok_or_else(malformed))
+            .collect(),
+    }
+}
+
+/// Matches `name` case-insensitively against the `Debug` names of `variants`.
+fn parse_kind<T: std::fmt::Debug + Copy>(name: &str, variants: &[T]) -> std::option::Option<T> {
+    variants
+        .iter()
+        .find(|variant| format!("{variant:?}").eq_ignore_ascii_case(name.trim()))
+        .copied()
+}
+
+fn invalid(context: impl Into<String>) -> EPANETError {
+    EPANETError::from(250).with_context(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use crate::types::node::NodeProperty;
+    use rstest::rstest;
+
+    fn export(ph: &EPANET, options: &GeoJsonExportOptions) -> Value {
+        serde_json::from_slice(&ph.export_geojson(Vec::new(), options).unwrap()).unwrap()
+    }
+
+    #[rstest]
+    fn test_export_geojson(ph: EPANET) {
+        ph.solve_h().unwrap();
+        let options = GeoJsonExportOptions {
+            node_results: vec![NodeProperty::Pressure],
+            link_results: vec![LinkProperty::Flow],
+            ..GeoJsonExportOptions::default()
+        };
+        let collection = export(&ph, &options);
+        let features = collection["features"].as_array().unwrap();
+        assert_eq!(features.len(), 11 + 13);
+
+        let junction = &features[0];
+        assert_eq!(junction["geometry"]["type"], "Point");
+        assert_eq!(junction["geometry"]["coordinates"], json!([20.0, 70.0]));
+        assert_eq!(junction["properties"]["id"], "10");
+        assert_eq!(junction["properties"]["type"], "Junction");
+        assert_eq!(junction["properties"]["elevation"], 710.0);
+        assert!(junction["properties"]["pressure"].as_f64().unwrap() > 0.0);
+
+        let pipe = &features[11];
+        assert_eq!(pipe["geometry"]["type"], "LineString");
+        assert_eq!(pipe["geometry"]["coordinates"].as_array().unwrap().len(), 2);
+        assert_eq!(pipe["properties"]["from_node"], "10");
+        assert_eq!(pipe["properties"]["roughness"], 100.0);
+        assert!(pipe["properties"]["flow"].is_number());
+
+        let pump = features
+            .iter()
+            .find(|f| f["properties"]["id"] == "9" && f["geometry"]["type"] == "LineString")
+            .unwrap();
+        assert_eq!(pump["properties"]["type"], "Pump");
+        assert_eq!(pump["properties"]["head_curve"], "1");
+    }
+
+    #[rstest]
+    fn test_import_geojson_round_trip(ph: EPANET, ph_close: EPANET) {
+        let text = String::from_utf8(
+            ph.export_geojson(Vec::new(), &GeoJsonExportOptions::default())
+                .unwrap(),
+        )
+        .unwrap();
+        ph_close.add_pattern("1").unwrap();
+        ph_close.add_curve("1").unwrap();
+        let curve = ph_close.get_curve_index("1").unwrap();
+        ph_close.set_curve(curve, &[(1500.0, 250.0)]).unwrap();
+
+        let imported = ph_close
+            .import_geojson(&text, &GeoJsonImportOptions::default())
+            .unwrap();
+        assert_eq!(imported.nodes.len(), 11);
+        assert_eq!(imported.links.len(), 13);
+        assert_eq!(
+            ph_close
+                .get_coordinates(ph_close.get_node_index("22").unwrap())
+                .unwrap(),
+            (50.0, 40.0)
+        );
+        let pipe = ph_close.get_link_index("112").unwrap();
+        assert_eq!(
+            ph_close
+                .get_link_value(pipe, LinkProperty::Diameter)
+                .unwrap(),
+            12.0
+        );
+        assert_eq!(
+            ph_close
+                .get_link_type(ph_close.get_link_index("9").unwrap())
+                .unwrap(),
+            LinkType::Pump
+        );
+        assert_eq!(
+            ph_close
+                .get_node_type(ph_close.get_node_index("2").unwrap())
+                .unwrap(),
+            NodeType::Tank
+        );
+    }
+
+    #[rstest]
+    fn test_import_geojson_snaps_line_ends(ph_close: EPANET) {
+        let text = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "geometry": {"type": "LineString", "coordinates": [[0, 0], [3, 4], [6, 0]]},
+             "properties": {"PIPE_ID": "P1", "DIAM": "8", "C": 120}},
+            {"type": "Feature", "geometry": {"type": "Point", "coordinates": [0, 0]},
+             "properties": {"PIPE_ID": "R", "type": "reservoir", "elevation": 100}},
+            {"type": "Feature", "geometry": {"type": "Point", "coordinates": [6, 0]},
+             "properties": {"PIPE_ID": "J", "elevation": 50, "base_demand": 2.5}}
+        ]}"#;
+        let options = GeoJsonImportOptions {
+            fields: GeoJsonFields {
+                id: "PIPE_ID".into(),
+                diameter: "DIAM".into(),
+                roughness: "C".into(),
+                ..GeoJsonFields::default()
+            },
+            ..GeoJsonImportOptions::default()
+        };
+        let imported = ph_close.import_geojson(text, &options).unwrap();
+        assert_eq!(imported.nodes.len(), 2);
+        assert_eq!(imported.links, vec!["P1".to_string()]);
+        let pipe = ph_close.get_link_index("P1").unwrap();
+        let reservoir = ph_close.get_node_index("R").unwrap();
+        let junction = ph_close.get_node_index("J").unwrap();
+        assert_eq!(
+            ph_close.get_link_nodes(pipe).unwrap(),
+            (reservoir, junction)
+        );
+        assert!(approx_eq(
+            ph_close.get_link_value(pipe, LinkProperty::Length).unwrap(),
+            10.0,
+            1e-9
+        ));
+        assert_eq!(
+            ph_close
+                .get_link_value(pipe, LinkProperty::Diameter)
+                .unwrap(),
+            8.0
+        );
+        assert_eq!(ph_close.get_vertex_count(pipe).unwrap(), 1);
+        assert_eq!(
+            ph_close.get_node_type(reservoir).unwrap(),
+            NodeType::Reservoir
+        );
+    }
+
+    #[rstest]
+    fn test_import_geojson_errors(ph_close: EPANET) {
+        let options = GeoJsonImportOptions::default();
+        assert_eq!(
+            ph_close.import_geojson("{", &options).unwrap_err().code,
+            201
+        );
+        assert_eq!(
+            ph_close.import_geojson("[]", &options).unwrap_err().code,
+            250
+        );
+        let unmatched = r#"{"type": "Feature", "geometry": {"type": "LineString", "coordinates": [[0, 0], [1, 1]]},
+            "properties": {"id": "P", "diameter": 6, "roughness": 100}}"#;
+        assert_eq!(
+            ph_close
+                .import_geojson(unmatched, &options)
+                .unwrap_err()
+                .code,
+            203
+        );
+        let no_diameter = r#"{"type": "Feature", "geometry": null,
+            "properties": {"id": "T", "type": "Tank", "init_level": 1, "min_level": 0, "max_level": 2}}"#;
+        assert_eq!(
+            ph_close
+                .import_geojson(no_diameter, &options)
+                .unwrap_err()
+                .code,
+            250
+        );
+    }
+}
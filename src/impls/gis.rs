@@ -0,0 +1,442 @@
+//! GIS model building API methods for EPANET.
+//!
+//! This module contains a builder that turns pipe polylines without explicit
+//! nodes into junctions and pipes, snapping line ends together, optionally
+//! splitting lines at intersections, and assigning junction elevations.
+
+use crate::epanet_error::*;
+use crate::types::gis::*;
+use crate::types::link::LinkType;
+use crate::types::node::{NodeProperty, NodeType};
+use crate::types::{CountType, ObjectType};
+use crate::EPANET;
+
+/// ## GIS APIs
+impl EPANET {
+    /// Adds junctions and pipes built from `polylines`.
+    ///
+    /// Line ends within [`PolylineModelOptions::snap_tolerance`] of each other, or
+    /// of an existing node with coordinates, share a node; every other end becomes
+    /// a new junction with an elevation from
+    /// [`PolylineModelOptions::elevations`]. With
+    /// [`PolylineModelOptions::split_at_intersections`], lines are also split
+    /// where they cross and where a line ends on another line's interior. Each
+    /// piece becomes a pipe whose length is its planar length, whose interior
+    /// positions are stored as vertices, and whose roughness comes from its
+    /// material. The material is stored as the link tag and the installation year
+    /// in the link comment.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 250 if a polyline has fewer than two positions, or the
+    /// [`EPANETError`] raised while adding an element (e.g. 215 for a duplicate
+    /// pipe ID). Splitting and snapping are not undone on error: junctions and
+    /// pipes added, and elevations set, before the error remain in the project.
+    pub fn build_from_polylines(
+        &self,
+        polylines: &[Polyline],
+        options: &PolylineModelOptions,
+    ) -> Result<PolylineModel> {
+        if let Some(line) = polylines.iter().find(|line| line.points.len() < 2) {
+            return Err(EPANETError::from(250).with_context(format!(
+                "{}: a polyline needs at least two positions",
+                line.id
+            )));
+        }
+        let tolerance = options.snap_tolerance;
+        let mut model = PolylineModel::default();
+
+        let splits = match options.split_at_intersections {
+            true => split_points(polylines, tolerance),
+            false => vec![Vec::new(); polylines.len()],
+        };
+        model.splits = splits.iter().map(Vec::len).sum();
+
+        // Existing nodes with coordinates are snapping targets too.
+        let mut nodes: Vec<((f64, f64), String)> = Vec::new();
+        for index in 1..=self.get_count(CountType::NodeCount)? {
+            if let Ok(position) = self.get_coordinates(index) {
+                nodes.push((position, self.get_node_id(index)?));
+            }
+        }
+        let mut next_id = 1;
+
+        for (line, line_splits) in polylines.iter().zip(&splits) {
+            let pieces = pieces(&line.points, line_splits, tolerance);
+            for (number, piece) in pieces.iter().enumerate() {
+                let id = match pieces.len() {
+                    1 => line.id.clone(),
+                    _ => format!("{}-{}", line.id, number + 1),
+                };
+                let mut ends = Vec::with_capacity(2);
+                for &(x, y) in [piece[0], piece[piece.len() - 1]].iter() {
+                    let nearest = nodes
+                        .iter()
+                        .map(|(p, node)| ((p.0 - x).hypot(p.1 - y), node))
+                        .filter(|(distance, _)| *distance <= tolerance)
+                        .min_by(|a, b| a.0.total_cmp(&b.0))
+                        .map(|(_, node)| node.clone());
+                    let node = match nearest {
+                        Some(node) => node,
+                        None => {
+                            let node = self.new_junction_id(&options.node_prefix, &mut next_id);
+                            let index = self.add_node(&node, NodeType::Junction)?;
+                            let elevation = options.elevations.elevation(x, y);
+                            self.set_node_value(index, NodeProperty::Elevation, elevation)?;
+                            self.set_coordinates(index, x, y)?;
+                            nodes.push(((x, y), node.clone()));
+                            model.nodes.push(node.clone());
+                            node
+                        }
+                    };
+                    ends.push(node);
+                }
+                if ends[0] == ends[1] {
+                    model.dropped.push(id);
+                    continue;
+                }
+
+                let index = self.add_link(&id, LinkType::Pipe, &ends[0], &ends[1])?;
+                let length = piece
+                    .windows(2)
+                    .map(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1))
+                    .sum();
+                let roughness = line
+                    .material
+                    .as_ref()
+                    .and_then(|material| {
+                        options
+                            .material_roughness
+                            .iter()
+                            .find(|(name, _)| name.eq_ignore_ascii_case(material))
+                    })
+                    .map_or(options.default_roughness, |(_, roughness)| *roughness);
+                self.set_pipe_data(index, length, line.diameter, roughness, 0.0)?;
+                if piece.len() > 2 {
+                    self.set_vertices(index, piece[1..piece.len() - 1].to_vec())?;
+                }
+                if let Some(material) = &line.material {
+                    let tag = material.split_whitespace().collect::<Vec<_>>().join("_");
+                    self.set_tag(ObjectType::Link, index.0, &tag)?;
+                }
+                if let Some(year) = line.install_year {
+                    self.set_comment(ObjectType::Link, index.0, &format!("installed {year}"))?;
+                }
+                model.links.push(id);
+            }
+        }
+        Ok(model)
+    }
+
+    // Helper methods - Internal API
+
+    /// Next `<prefix><n>` not already used by a node.
+    fn new_junction_id(&self, prefix: &str, next: &mut usize) -> String {
+        loop {
+            let id = format!("{prefix}{next}");
+            *next += 1;
+            if self.get_node_index(&id).is_err() {
+                return id;
+            }
+        }
+    }
+}
+
+/// `(segment, parameter, position)` of a point where a polyline is split.
+type SplitPoint = (usize, f64, (f64, f64));
+
+/// For each polyline, every point where it must be split: where it crosses another
+/// line, or where another line ends within `tolerance` of its interior.
+fn split_points(polylines: &[Polyline], tolerance: f64) -> Vec<Vec<SplitPoint>> {
+    let mut splits = vec![Vec::new(); polylines.len()];
+    let is_interior = |line: &Polyline, p: (f64, f64)| {
+        let (start, end) = (line.points[0], line.points[line.points.len() - 1]);
+        distance(p, start) > tolerance && distance(p, end) > tolerance
+    };
+    for a in 0..polylines.len() {
+        for b in 0..polylines.len() {
+            if a == b {
+                continue;
+            }
+            let (line_a, line_b) = (&polylines[a], &polylines[b]);
+            for (i, sa) in line_a.points.windows(2).enumerate() {
+                // Crossings are found once, from the lower-numbered line.
+                if a < b {
+                    for (j, sb) in line_b.points.windows(2).enumerate() {
+                        let Some((t, u)) = intersection(sa[0], sa[1], sb[0], sb[1]) else {
+                            continue;
+                        };
+                        let p = lerp(sa[0], sa[1], t);
+                        if is_interior(line_a, p) {
+                            splits[a].push((i, t, p));
+                        }
+                        if is_interior(line_b, p) {
+                            splits[b].push((j, u, p));
+                        }
+                    }
+                }
+                // Ends of line b landing on this segment of line a.
+                for end in [line_b.points[0], line_b.points[line_b.points.len() - 1]] {
+                    let t = project(sa[0], sa[1], end);
+                    let p = lerp(sa[0], sa[1], t);
+                    if distance(p, end) <= tolerance && is_interior(line_a, p) {
+                        splits[a].push((i, t, p));
+                    }
+                }
+            }
+        }
+    }
+    for line in &mut splits {
+        line.sort_by(|x, y| x.0.cmp(&y.0).then(x.1.total_cmp(&y.1)));
+        line.dedup_by(|x, y| distance(x.2, y.2) <= tolerance);
+    }
+    splits
+}
+
+/// Cuts `points` at the sorted `splits`, dropping consecutive positions closer
+/// than `tolerance`.
+fn pieces(points: &[(f64, f64)], splits: &[SplitPoint], tolerance: f64) -> Vec<Vec<(f64, f64)>> {
+    let push = |piece: &mut Vec<(f64, f64)>, p: (f64, f64)| {
+        if piece
+            .last()
+            .is_none_or(|last| distance(*last, p) > tolerance)
+        {
+            piece.push(p);
+        }
+    };
+    let mut pieces = Vec::new();
+    let mut piece = vec![points[0]];
+    let mut splits = splits.iter().peekable();
+    for (segment, end) in points.iter().skip(1).enumerate() {
+        while let Some((_, _, p)) = splits.next_if(|s| s.0 == segment) {
+            push(&mut piece, *p);
+            pieces.push(std::mem::replace(&mut piece, vec![*p]));
+        }
+        push(&mut piece, *end);
+    }
+    pieces.push(piece);
+    // A split at a vertex can leave a single-position piece behind.
+    pieces.retain(|piece| piece.len() >= 2);
+    pieces
+}
+
+/// Parameters `(t, u)` along segments `p1-p2` and `q1-q2` of their crossing point.
+fn intersection(
+    p1: (f64, f64),
+    p2: (f64, f64),
+    q1: (f64, f64),
+    q2: (f64, f64),
+) -> std::option::Option<(f64, f64)> {
+    let r = (p2.0 - p1.0, p2.1 - p1.1);
+    let s = (q2.0 - q1.0, q2.1 - q1.1);
+    let denominator = r.0 * s.1 - r.1 * s.0;
+    if denominator.abs() < f64::EPSILON {
+        return None;
+    }
+    let d = (q1.0 - p1.0, q1.1 - p1.1);
+    let t = (d.0 * s.1 - d.1 * s.0) / denominator;
+    let u = (d.0 * r.1 - d.1 * r.0) / denominator;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then_some((t, u))
+}
+
+/// Parameter of the point of segment `a-b` closest to `p`.
+pub(crate) fn project(a: (f64, f64), b: (f64, f64), p: (f64, f64)) -> f64 {
+    let ab = (b.0 - a.0, b.1 - a.1);
+    let length2 = ab.0 * ab.0 + ab.1 * ab.1;
+    if length2 == 0.0 {
+        return 0.0;
+    }
+    (((p.0 - a.0) * ab.0 + (p.1 - a.1) * ab.1) / length2).clamp(0.0, 1.0)
+}
+
+pub(crate) fn lerp(a: (f64, f64), b: (f64, f64), t: f64) -> (f64, f64) {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+pub(crate) fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use crate::types::link::LinkProperty;
+    use rstest::rstest;
+
+    fn line(id: &str, points: &[(f64, f64)]) -> Polyline {
+        Polyline {
+            id: id.to_string(),
+            points: points.to_vec(),
+            diameter: 8.0,
+            material: Some("Ductile Iron".to_string()),
+            install_year: Some(1987),
+        }
+    }
+
+    fn crossing() -> Vec<Polyline> {
+        vec![
+            line("A", &[(0.0, 0.0), (10.0, 0.0)]),
+            line("B", &[(5.0, -5.0), (5.0, 5.0)]),
+        ]
+    }
+
+    #[rstest]
+    fn test_build_from_polylines_snaps_ends(ph_close: EPANET) {
+        let lines = vec![
+            line("A", &[(0.0, 0.0), (5.0, 5.0), (10.0, 0.0)]),
+            line("B", &[(10.05, 0.0), (20.0, 0.0)]),
+        ];
+        let options = PolylineModelOptions {
+            material_roughness: vec![("ductile iron".to_string(), 130.0)],
+            elevations: ElevationSource::Points(vec![(0.0, 0.0, 10.0), (20.0, 0.0, 30.0)]),
+            ..PolylineModelOptions::default()
+        };
+        let model = ph_close.build_from_polylines(&lines, &options).unwrap();
+        assert_eq!(model.nodes, vec!["J1", "J2", "J3"]);
+        assert_eq!(model.links, vec!["A", "B"]);
+
+        let a = ph_close.get_link_index("A").unwrap();
+        let b = ph_close.get_link_index("B").unwrap();
+        assert_eq!(
+            ph_close.get_link_nodes(a).unwrap().1,
+            ph_close.get_link_nodes(b).unwrap().0
+        );
+        assert!(approx_eq(
+            ph_close.get_link_value(a, LinkProperty::Length).unwrap(),
+            2.0 * 50f64.sqrt(),
+            1e-9
+        ));
+        assert_eq!(ph_close.get_vertex_count(a).unwrap(), 1);
+        assert_eq!(
+            ph_close.get_link_value(a, LinkProperty::Roughness).unwrap(),
+            130.0
+        );
+        assert_eq!(
+            ph_close.get_tag(ObjectType::Link, a).unwrap(),
+            "Ductile_Iron"
+        );
+        assert_eq!(
+            ph_close.get_comment(ObjectType::Link, a).unwrap(),
+            "installed 1987"
+        );
+
+        let j3 = ph_close.get_node_index("J3").unwrap();
+        assert_eq!(
+            ph_close
+                .get_node_value(j3, NodeProperty::Elevation)
+                .unwrap(),
+            30.0
+        );
+    }
+
+    #[rstest]
+    fn test_build_from_polylines_splits_intersections(ph_close: EPANET) {
+        let options = PolylineModelOptions {
+            split_at_intersections: true,
+            ..PolylineModelOptions::default()
+        };
+        let model = ph_close
+            .build_from_polylines(&crossing(), &options)
+            .unwrap();
+        assert_eq!(model.splits, 2);
+        assert_eq!(model.nodes.len(), 5);
+        assert_eq!(model.links, vec!["A-1", "A-2", "B-1", "B-2"]);
+        let centre = ph_close
+            .get_link_nodes(ph_close.get_link_index("A-1").unwrap())
+            .unwrap()
+            .1;
+        assert_eq!(ph_close.get_coordinates(centre).unwrap(), (5.0, 0.0));
+        assert_eq!(
+            ph_close
+                .get_link_nodes(ph_close.get_link_index("B-2").unwrap())
+                .unwrap()
+                .0,
+            centre
+        );
+    }
+
+    #[rstest]
+    fn test_build_from_polylines_without_splitting(ph_close: EPANET) {
+        let model = ph_close
+            .build_from_polylines(&crossing(), &PolylineModelOptions::default())
+            .unwrap();
+        assert_eq!(model.splits, 0);
+        assert_eq!(model.nodes.len(), 4);
+        assert_eq!(model.links, vec!["A", "B"]);
+    }
+
+    #[rstest]
+    fn test_build_from_polylines_t_junction_and_existing_node(ph_close: EPANET) {
+        let reservoir = ph_close.add_node("R", NodeType::Reservoir).unwrap();
+        ph_close.set_coordinates(reservoir, 0.0, 0.0).unwrap();
+        let lines = vec![
+            line("MAIN", &[(0.0, 0.0), (10.0, 0.0)]),
+            line("SERVICE", &[(4.0, 6.0), (4.0, 0.05)]),
+        ];
+        let options = PolylineModelOptions {
+            split_at_intersections: true,
+            elevations: ElevationSource::Grid(ElevationGrid {
+                origin: (0.0, 0.0),
+                cell_size: 10.0,
+                columns: 2,
+                values: vec![0.0, 10.0, 20.0, 30.0],
+            }),
+            ..PolylineModelOptions::default()
+        };
+        let model = ph_close.build_from_polylines(&lines, &options).unwrap();
+        assert_eq!(model.links, vec!["MAIN-1", "MAIN-2", "SERVICE"]);
+        assert_eq!(model.nodes.len(), 3);
+        let main = ph_close.get_link_index("MAIN-1").unwrap();
+        assert_eq!(
+            ph_close.get_link_nodes(main).unwrap().0,
+            ph_close.get_node_index("R").unwrap()
+        );
+        let tee = ph_close.get_link_nodes(main).unwrap().1;
+        let service = ph_close.get_link_index("SERVICE").unwrap();
+        assert_eq!(ph_close.get_link_nodes(service).unwrap().1, tee);
+        // Bilinear: 4 along x (4), 0 along y.
+        assert!(approx_eq(
+            ph_close
+                .get_node_value(tee, NodeProperty::Elevation)
+                .unwrap(),
+            4.0,
+            1e-9
+        ));
+    }
+
+    #[rstest]
+    fn test_polyline_from_wkt() {
+        let line = Polyline::from_wkt("P1", "LINESTRING Z (0 0 1, 10 0 2, 10 5 3)", 6.0).unwrap();
+        assert_eq!(line.points, vec![(0.0, 0.0), (10.0, 0.0), (10.0, 5.0)]);
+        assert_eq!(
+            Polyline::from_wkt("P1", "POINT (0 0)", 6.0)
+                .unwrap_err()
+                .code,
+            250
+        );
+        assert_eq!(
+            Polyline::from_wkt("P1", "LINESTRING (0 0)", 6.0)
+                .unwrap_err()
+                .code,
+            250
+        );
+    }
+
+    #[cfg(feature = "geojson")]
+    #[rstest]
+    fn test_polyline_from_geojson() {
+        let text = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "geometry": {"type": "MultiLineString",
+                "coordinates": [[[0, 0], [1, 0]], [[1, 0], [1, 1]]]},
+             "properties": {"id": 7, "diameter": "150", "material": "PVC", "install_year": 2004}},
+            {"type": "Feature", "geometry": {"type": "Point", "coordinates": [0, 0]}, "properties": {}}
+        ]}"#;
+        let lines = Polyline::from_geojson(text, &PolylineFields::default()).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].id, "7-2");
+        assert_eq!(lines[1].diameter, 150.0);
+        assert_eq!(lines[1].material.as_deref(), Some("PVC"));
+        assert_eq!(lines[1].install_year, Some(2004));
+    }
+}
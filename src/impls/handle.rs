@@ -0,0 +1,162 @@
+//! Element handle API methods for EPANET.
+//!
+//! This module issues [`NodeHandle`]s and [`LinkHandle`]s and keeps the handle
+//! registry in step with deletions and renames.
+
+use crate::epanet_error::*;
+use crate::types::handle::*;
+use crate::types::index::{LinkIndex, NodeIndex};
+use crate::EPANET;
+
+/// ## Element Handle APIs
+impl EPANET {
+    /// Returns a stable handle to the node with the given ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 203 if there is no such node.
+    pub fn get_node_handle(&self, id: &str) -> Result<NodeHandle> {
+        self.get_node_index(id)?;
+        Ok(NodeHandle {
+            project: self.project_id,
+            slot: self.handles.borrow_mut().nodes.slot(id),
+        })
+    }
+
+    /// Returns a stable handle to the node at `index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 203 if there is no such node.
+    pub fn get_node_handle_by_index(&self, index: impl Into<NodeIndex>) -> Result<NodeHandle> {
+        self.get_node_handle(&self.get_node_id(index)?)
+    }
+
+    /// Returns a stable handle to the link with the given ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 204 if there is no such link.
+    pub fn get_link_handle(&self, id: &str) -> Result<LinkHandle> {
+        self.get_link_index(id)?;
+        Ok(LinkHandle {
+            project: self.project_id,
+            slot: self.handles.borrow_mut().links.slot(id),
+        })
+    }
+
+    /// Returns a stable handle to the link at `index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 204 if there is no such link.
+    pub fn get_link_handle_by_index(&self, index: impl Into<LinkIndex>) -> Result<LinkHandle> {
+        self.get_link_handle(&self.get_link_id(index)?)
+    }
+
+    // Helper methods - Internal API
+
+    /// Current ID behind a handle issued by `project`, or error `code`.
+    pub(crate) fn handle_id(
+        &self,
+        project: u64,
+        id: impl FnOnce(&HandleRegistry) -> Option<&str>,
+        code: i32,
+    ) -> Result<String> {
+        if project != self.project_id {
+            return Err(EPANETError::from(code).with_context("handle belongs to another project"));
+        }
+        id(&self.handles.borrow())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                EPANETError::from(code).with_context("handle refers to a deleted element")
+            })
+    }
+
+    /// Invalidates the handles of deleted node `id` and of the links deleted with it.
+    pub(crate) fn forget_node(&self, id: &str) {
+        let mut handles = self.handles.borrow_mut();
+        handles.nodes.remove(id);
+        if !handles.links.is_empty() {
+            handles
+                .links
+                .retain(|link| self.get_link_index(link).is_ok());
+        }
+    }
+
+    /// Invalidates the handles of deleted link `id`.
+    pub(crate) fn forget_link(&self, id: &str) {
+        self.handles.borrow_mut().links.remove(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use crate::types::node::NodeType;
+    use crate::types::ActionCodeType::{Conditional, Unconditional};
+    use rstest::rstest;
+
+    #[rstest]
+    fn test_node_handle_survives_reindexing(ph: EPANET) {
+        let tank = ph.get_node_handle("2").unwrap();
+        assert_eq!(tank, ph.get_node_handle_by_index(11).unwrap());
+        // Adding a junction shifts the tank and reservoir indices.
+        ph.add_node("NEW", NodeType::Junction).unwrap();
+        assert_eq!(tank.index(&ph).unwrap(), 12);
+        ph.delete_node(ph.get_node_index("NEW").unwrap(), Unconditional)
+            .unwrap();
+        ph.delete_node(ph.get_node_index("32").unwrap(), Unconditional)
+            .unwrap();
+        assert_eq!(tank.index(&ph).unwrap(), 10);
+        assert_eq!(tank.get(&ph).unwrap().id, "2");
+
+        ph.set_node_id(tank.index(&ph).unwrap(), "T2").unwrap();
+        assert_eq!(tank.id(&ph).unwrap(), "T2");
+        assert_eq!(tank, ph.get_node_handle("T2").unwrap());
+    }
+
+    #[rstest]
+    fn test_handle_use_after_delete(ph: EPANET, #[from(ph)] other: EPANET) {
+        let node = ph.get_node_handle("23").unwrap();
+        let link = ph.get_link_handle("113").unwrap();
+        let pump = ph.get_link_handle("9").unwrap();
+        assert!(ph
+            .delete_link(link.index(&ph).unwrap(), Conditional)
+            .is_ok());
+        assert!(!link.is_valid(&ph));
+        assert_eq!(link.index(&ph).unwrap_err().code, 204);
+
+        // Deleting a node also deletes its links; a new node with the old ID is a
+        // different element.
+        let pipe = ph.get_link_handle("22").unwrap();
+        ph.delete_node(node.index(&ph).unwrap(), Unconditional)
+            .unwrap();
+        ph.add_node("23", NodeType::Junction).unwrap();
+        assert_eq!(node.index(&ph).unwrap_err().code, 203);
+        assert!(!pipe.is_valid(&ph));
+        assert!(pump.is_valid(&ph));
+
+        // Handles are tied to their project.
+        assert_eq!(pump.index(&other).unwrap_err().code, 204);
+    }
+
+    #[rstest]
+    fn test_handle_outlives_project() {
+        let (node, link) = {
+            let old = ph();
+            (
+                old.get_node_handle("10").unwrap(),
+                old.get_link_handle("10").unwrap(),
+            )
+        };
+        // A new project may reuse the address of the dropped one; the old
+        // handles must still not resolve in it.
+        let new = ph();
+        new.get_node_handle("10").unwrap();
+        new.get_link_handle("10").unwrap();
+        assert_eq!(node.index(&new).unwrap_err().code, 203);
+        assert_eq!(link.index(&new).unwrap_err().code, 204);
+    }
+}
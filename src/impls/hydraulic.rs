@@ -6,6 +6,7 @@ use crate::bindings as ffi;
 use crate::epanet_error::*;
 use crate::EPANET;
 use crate::types::analysis::InitHydOption;
+use crate::types::options::TimeParameter;
 use std::os::raw::c_long;
 
 /// ## Hydraulic Analysis APIs
@@ -288,6 +289,71 @@ impl EPANET {
     pub fn close_h(&self) -> Result<()> {
         check_error(unsafe { ffi::EN_closeH(self.ph) })
     }
+
+    // Helper methods - Internal API
+
+    /// Runs a complete extended period hydraulic simulation, calling `observe`
+    /// with the elapsed time (in seconds) after each hydraulic time step has been
+    /// solved and before the solver advances to the next one.
+    ///
+    /// Solver warnings (codes 1-99) do not abort the run; the number of warnings
+    /// encountered is returned instead. The hydraulic solver is always closed
+    /// before returning.
+    pub(crate) fn run_hydraulics_with<F>(&self, mut observe: F) -> Result<u32>
+    where
+        F: FnMut(i64) -> Result<()>,
+    {
+        self.open_h()?;
+        let mut run = || -> Result<u32> {
+            self.init_h(InitHydOption::NoSave)?;
+            let mut warnings = 0;
+            loop {
+                let mut out_current_time: c_long = 0;
+                let code = unsafe { ffi::EN_runH(self.ph, &mut out_current_time) };
+                check_error_allow_warnings(code)?;
+                warnings += (code > 0) as u32;
+
+                observe(out_current_time as i64)?;
+
+                let mut out_next_time: c_long = 0;
+                let code = unsafe { ffi::EN_nextH(self.ph, &mut out_next_time) };
+                check_error_allow_warnings(code)?;
+                warnings += (code > 0) as u32;
+                if out_next_time == 0 {
+                    break;
+                }
+            }
+            Ok(warnings)
+        };
+        let result = run();
+        let closed = self.close_h();
+        let warnings = result?;
+        closed?;
+        Ok(warnings)
+    }
+
+    /// Solves a single steady-state snapshot with time patterns evaluated at
+    /// `pattern_time` seconds and tanks at their initial levels, then calls `observe`.
+    ///
+    /// The simulation duration and pattern start time are restored before
+    /// returning. Returns the number of solver warnings, as
+    /// [`run_hydraulics_with`](Self::run_hydraulics_with) does.
+    pub(crate) fn solve_snapshot_with<F>(&self, pattern_time: i32, mut observe: F) -> Result<u32>
+    where
+        F: FnMut() -> Result<()>,
+    {
+        let duration = self.get_time_parameter(TimeParameter::Duration)?;
+        let pattern_start = self.get_time_parameter(TimeParameter::PatternStart)?;
+        let mut run = || -> Result<u32> {
+            self.set_time_parameter(TimeParameter::Duration, 0)?;
+            self.set_time_parameter(TimeParameter::PatternStart, pattern_time)?;
+            self.run_hydraulics_with(|_| observe())
+        };
+        let result = run();
+        self.set_time_parameter(TimeParameter::PatternStart, pattern_start)?;
+        self.set_time_parameter(TimeParameter::Duration, duration)?;
+        result
+    }
 }
 
 #[cfg(test)]
@@ -313,12 +379,12 @@ mod tests {
 
         loop {
             let run_result = ph.run_h();
-            assert!(matches!(run_result, Ok(_)));
+            assert!(run_result.is_ok());
 
             let step_result = ph.next_h();
-            assert!(matches!(step_result, Ok(_)));
+            assert!(step_result.is_ok());
 
-            if step_result.unwrap() <= 0 {
+            if step_result.unwrap() == 0 {
                 break;
             }
         }
@@ -362,4 +428,22 @@ mod tests {
         // Clean up the created file after the test
         fs::remove_file(hyd_file).expect("Failed to remove the hydraulics file");
     }
+
+    #[rstest]
+    fn test_run_hydraulics_with(ph: EPANET) {
+        let mut times = Vec::new();
+        let warnings = ph
+            .run_hydraulics_with(|t| {
+                times.push(t);
+                Ok(())
+            })
+            .expect("Failed to run hydraulics");
+        assert_eq!(warnings, 0);
+        assert_eq!(times.first(), Some(&0));
+        assert_eq!(times.last(), Some(&(24 * 3600)));
+        assert!(times.windows(2).all(|w| w[0] < w[1]));
+
+        // The solver is closed afterwards, so a new analysis can be started.
+        assert_eq!(ph.solve_h(), Ok(()));
+    }
 }
@@ -0,0 +1,199 @@
+//! Lazy iteration API methods for EPANET.
+//!
+//! This module creates [`NodeIter`]s and [`LinkIter`]s and the filtered and
+//! ID-only iterators built on them. Nothing is read from the engine until an
+//! element is inspected.
+
+use crate::epanet_error::*;
+use crate::types::index::{LinkId, NodeId};
+use crate::types::iter::*;
+use crate::types::link::{LinkProperty, LinkType};
+use crate::types::node::{NodeProperty, NodeType};
+use crate::types::CountType;
+use crate::EPANET;
+
+/// ## Lazy Iteration APIs
+impl EPANET {
+    /// Iterates over all nodes in index order without reading any of their
+    /// properties; each [`NodeRef`] only carries the node index.
+    pub fn iter_nodes(&self) -> Result<NodeIter<'_>> {
+        let count = self.get_count(CountType::NodeCount)?;
+        Ok(NodeIter {
+            project: self,
+            indices: 1..count + 1,
+        })
+    }
+
+    /// Iterates over all links in index order without reading any of their
+    /// properties; each [`LinkRef`] only carries the link index.
+    pub fn iter_links(&self) -> Result<LinkIter<'_>> {
+        let count = self.get_count(CountType::LinkCount)?;
+        Ok(LinkIter {
+            project: self,
+            indices: 1..count + 1,
+        })
+    }
+
+    /// Iterates over the nodes of one type, reading only the node type. A node
+    /// whose type cannot be read is yielded as its error.
+    pub fn iter_nodes_of_type(
+        &self,
+        node_type: NodeType,
+    ) -> Result<impl Iterator<Item = Result<NodeRef<'_>>> + '_> {
+        Ok(self
+            .iter_nodes()?
+            .filter_map(move |node| matching(node, node.node_type().map(|t| t == node_type))))
+    }
+
+    /// Iterates over the links of one type, reading only the link type. A link
+    /// whose type cannot be read is yielded as its error.
+    pub fn iter_links_of_type(
+        &self,
+        link_type: LinkType,
+    ) -> Result<impl Iterator<Item = Result<LinkRef<'_>>> + '_> {
+        Ok(self
+            .iter_links()?
+            .filter_map(move |link| matching(link, link.link_type().map(|t| t == link_type))))
+    }
+
+    /// Iterates over the nodes whose tag equals `tag`. A node whose tag cannot
+    /// be read is yielded as its error.
+    pub fn iter_nodes_with_tag(
+        &self,
+        tag: &str,
+    ) -> Result<impl Iterator<Item = Result<NodeRef<'_>>> + '_> {
+        let tag = tag.to_string();
+        Ok(self
+            .iter_nodes()?
+            .filter_map(move |node| matching(node, node.tag().map(|t| t == tag))))
+    }
+
+    /// Iterates over the links whose tag equals `tag`. A link whose tag cannot
+    /// be read is yielded as its error.
+    pub fn iter_links_with_tag(
+        &self,
+        tag: &str,
+    ) -> Result<impl Iterator<Item = Result<LinkRef<'_>>> + '_> {
+        let tag = tag.to_string();
+        Ok(self
+            .iter_links()?
+            .filter_map(move |link| matching(link, link.tag().map(|t| t == tag))))
+    }
+
+    /// Iterates over the nodes for which `predicate` holds on the value of
+    /// `property`, e.g. `iter_nodes_where(NodeProperty::Pressure, |p| p < 20.0)`.
+    ///
+    /// A node whose value cannot be read is yielded as its error.
+    pub fn iter_nodes_where<'a>(
+        &'a self,
+        property: NodeProperty,
+        predicate: impl Fn(f64) -> bool + 'a,
+    ) -> Result<impl Iterator<Item = Result<NodeRef<'a>>> + 'a> {
+        Ok(self
+            .iter_nodes()?
+            .filter_map(move |node| matching(node, node.value(property).map(&predicate))))
+    }
+
+    /// Iterates over the links for which `predicate` holds on the value of
+    /// `property`, e.g. `iter_links_where(LinkProperty::Diameter, |d| d >= 12.0)`.
+    ///
+    /// A link whose value cannot be read is yielded as its error.
+    pub fn iter_links_where<'a>(
+        &'a self,
+        property: LinkProperty,
+        predicate: impl Fn(f64) -> bool + 'a,
+    ) -> Result<impl Iterator<Item = Result<LinkRef<'a>>> + 'a> {
+        Ok(self
+            .iter_links()?
+            .filter_map(move |link| matching(link, link.value(property).map(&predicate))))
+    }
+
+    /// Iterates over the node IDs in index order.
+    pub fn iter_node_ids(&self) -> Result<impl Iterator<Item = Result<NodeId>> + '_> {
+        Ok(self.iter_nodes()?.map(|node| node.id()))
+    }
+
+    /// Iterates over the link IDs in index order.
+    pub fn iter_link_ids(&self) -> Result<impl Iterator<Item = Result<LinkId>> + '_> {
+        Ok(self.iter_links()?.map(|link| link.id()))
+    }
+}
+
+/// `Some(Ok(item))` if the test on `item` passed, `None` if it failed, and
+/// the error if it could not be made, for the filtered iterators.
+fn matching<T>(item: T, test: Result<bool>) -> Option<Result<T>> {
+    test.map(|passed| passed.then_some(item)).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use crate::types::index::{LinkIndex, NodeIndex};
+    use crate::types::ObjectType;
+    use rstest::rstest;
+
+    #[rstest]
+    fn test_iter_nodes_and_links(ph: EPANET) {
+        let nodes = ph.iter_nodes().unwrap();
+        assert_eq!(
+            nodes.len(),
+            ph.get_count(CountType::NodeCount).unwrap() as usize
+        );
+        let last = nodes.last().unwrap();
+        assert_eq!(last.index(), NodeIndex(11));
+        assert_eq!(last.id().unwrap(), "2");
+        assert_eq!(last.node_type().unwrap(), NodeType::Tank);
+        assert_eq!(last.get().unwrap().id, "2");
+
+        let ids = ph
+            .iter_link_ids()
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            ids.len(),
+            ph.get_count(CountType::LinkCount).unwrap() as usize
+        );
+        assert_eq!(ids[0], "10");
+        let pump = ph
+            .iter_links()
+            .unwrap()
+            .rev()
+            .find(|l| l.id().unwrap() == "9")
+            .unwrap();
+        assert_eq!(pump.index(), ph.get_link_index("9").unwrap());
+        assert_eq!(pump.nodes().unwrap().0, ph.get_node_index("9").unwrap());
+    }
+
+    #[rstest]
+    fn test_iter_filters(ph: EPANET) {
+        let pumps: Vec<LinkIndex> = ph
+            .iter_links_of_type(LinkType::Pump)
+            .unwrap()
+            .map(|l| l.unwrap().index())
+            .collect();
+        assert_eq!(pumps, vec![ph.get_link_index("9").unwrap()]);
+        assert_eq!(
+            ph.iter_nodes_of_type(NodeType::Reservoir).unwrap().count(),
+            1
+        );
+
+        let large: Vec<String> = ph
+            .iter_links_where(LinkProperty::Diameter, |d| d >= 18.0)
+            .unwrap()
+            .map(|l| l.unwrap().id().unwrap().into())
+            .collect();
+        assert_eq!(large, vec!["10", "110"]);
+
+        let node = ph.get_node_index("22").unwrap();
+        ph.set_tag(ObjectType::Node, node, "Zone_A").unwrap();
+        let tagged: Vec<NodeIndex> = ph
+            .iter_nodes_with_tag("Zone_A")
+            .unwrap()
+            .map(|n| n.unwrap().index())
+            .collect();
+        assert_eq!(tagged, vec![node]);
+        assert_eq!(ph.iter_links_with_tag("Zone_A").unwrap().count(), 0);
+    }
+}
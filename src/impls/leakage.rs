@@ -0,0 +1,232 @@
+//! Pipe leakage API methods for EPANET.
+//!
+//! This module assigns leak parameters to pipes by tag and summarizes the
+//! leakage of a hydraulic run per pipe, per node and for the whole system.
+
+use crate::epanet_error::*;
+use crate::types::index::LinkIndex;
+use crate::types::leakage::*;
+use crate::types::link::{LinkProperty, LinkType};
+use crate::types::node::NodeProperty;
+use crate::types::options::AnalysisStatistic;
+use crate::types::{CountType, ObjectType};
+use crate::EPANET;
+use std::collections::HashMap;
+
+/// ## Leakage APIs
+impl EPANET {
+    /// Sets the leak area and expansion of every pipe whose tag matches a
+    /// class, leaving other pipes unchanged. When several classes share a
+    /// tag, the first one applies.
+    ///
+    /// Returns the number of pipes assigned to each class, in class order.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 202 if a class has a negative or non-finite parameter, in
+    /// which case no pipe is changed. If the engine rejects a value, the pipes
+    /// already set are restored before the error is returned.
+    pub fn set_leak_parameters_by_tag(&self, classes: &[LeakClass]) -> Result<Vec<usize>> {
+        if let Some(class) = classes.iter().find(|c| {
+            !(c.leak_area.is_finite() && c.leak_expansion.is_finite())
+                || c.leak_area < 0.0
+                || c.leak_expansion < 0.0
+        }) {
+            return Err(EPANETError::from(202).with_context(format!(
+                "invalid leak parameters for tag '{}': area {}, expansion {}",
+                class.tag, class.leak_area, class.leak_expansion
+            )));
+        }
+        let mut by_tag = HashMap::new();
+        for (position, class) in classes.iter().enumerate().rev() {
+            by_tag.insert(class.tag.as_str(), position);
+        }
+
+        let mut counts = vec![0; classes.len()];
+        let (mut areas, mut expansions) = (Vec::new(), Vec::new());
+        for index in self.leak_candidates()? {
+            let tag = self.get_tag(ObjectType::Link, index)?;
+            if let Some(&position) = by_tag.get(tag.as_str()) {
+                counts[position] += 1;
+                areas.push((index, classes[position].leak_area));
+                expansions.push((index, classes[position].leak_expansion));
+            }
+        }
+
+        let previous_areas = areas
+            .iter()
+            .map(|(index, _)| Ok((*index, self.get_link_value(*index, LinkProperty::LeakArea)?)))
+            .collect::<Result<Vec<_>>>()?;
+        self.set_link_values_sparse(LinkProperty::LeakArea, areas)?;
+        if let Err(err) = self.set_link_values_sparse(LinkProperty::LeakExpan, expansions) {
+            self.set_link_values_sparse(LinkProperty::LeakArea, previous_areas)?;
+            return Err(err);
+        }
+        Ok(counts)
+    }
+
+    /// Runs a hydraulic analysis and reports the leakage at every step, and
+    /// the volume lost from each leaking pipe, at each node and in total.
+    ///
+    /// Hydraulic warnings (e.g. negative pressures) do not stop the run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if the solver fails.
+    pub fn leakage_report(&self) -> Result<LeakageReport> {
+        let seconds_per_unit = self.get_flow_units()?.seconds_per_unit();
+        let mut pipes = Vec::new();
+        for index in self.leak_candidates()? {
+            if self.get_link_value(index, LinkProperty::LeakArea)? > 0.0
+                || self.get_link_value(index, LinkProperty::LeakExpan)? > 0.0
+            {
+                pipes.push(PipeLeakage {
+                    link: index,
+                    id: self.get_link_id(index)?,
+                    volume: 0.0,
+                    peak: 0.0,
+                });
+            }
+        }
+
+        let mut steps = Vec::new();
+        let mut pipe_flows = Vec::new();
+        let mut node_flows = Vec::new();
+        self.run_hydraulics_with(|t| {
+            let nodes = self.get_node_values(NodeProperty::LeakageFlow)?;
+            steps.push(LeakageStep {
+                time: t,
+                leakage: nodes.iter().sum(),
+                cumulative_percent_loss: self.get_statistic(AnalysisStatistic::LeakageLoss)?,
+            });
+            pipe_flows.push(
+                pipes
+                    .iter()
+                    .map(|pipe| self.get_link_value(pipe.link, LinkProperty::LinkLeakage))
+                    .collect::<Result<Vec<_>>>()?,
+            );
+            node_flows.push(nodes);
+            Ok(())
+        })?;
+
+        let node_count = self.get_count(CountType::NodeCount)? as usize;
+        let (mut node_volumes, mut node_peaks) = (vec![0.0; node_count], vec![0.0; node_count]);
+        for (i, (pipe_step, node_step)) in pipe_flows.iter().zip(&node_flows).enumerate() {
+            let dt = steps.get(i + 1).map_or(0, |next| next.time - steps[i].time) as f64;
+            for (pipe, flow) in pipes.iter_mut().zip(pipe_step) {
+                pipe.volume += flow * dt / seconds_per_unit;
+                pipe.peak = pipe.peak.max(*flow);
+            }
+            for (node, flow) in node_step.iter().enumerate() {
+                node_volumes[node] += flow * dt / seconds_per_unit;
+                node_peaks[node] = f64::max(node_peaks[node], *flow);
+            }
+        }
+        let mut nodes = Vec::new();
+        for (offset, (volume, peak)) in node_volumes.into_iter().zip(node_peaks).enumerate() {
+            if peak > 0.0 {
+                let index = offset as i32 + 1;
+                nodes.push(NodeLeakage {
+                    node: index.into(),
+                    id: self.get_node_id(index)?,
+                    volume,
+                    peak,
+                });
+            }
+        }
+        Ok(LeakageReport {
+            total_volume: pipes.iter().map(|p| p.volume).sum(),
+            percent_loss: steps.last().map_or(0.0, |s| s.cumulative_percent_loss),
+            steps,
+            pipes,
+            nodes,
+        })
+    }
+
+    // Helper methods - Internal API
+
+    /// Indices of the links that can leak: pipes and check-valve pipes.
+    fn leak_candidates(&self) -> Result<Vec<LinkIndex>> {
+        self.iter_links()?
+            .filter_map(|link| match link.link_type() {
+                Ok(LinkType::Pipe | LinkType::CvPipe) => Some(Ok(link.index())),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use rstest::rstest;
+
+    fn classes() -> Vec<LeakClass> {
+        vec![
+            LeakClass {
+                tag: "CI_1960".into(),
+                leak_area: 1.0,
+                leak_expansion: 0.1,
+            },
+            LeakClass {
+                tag: "PVC_1990".into(),
+                leak_area: 0.0,
+                leak_expansion: 0.0,
+            },
+        ]
+    }
+
+    #[rstest]
+    fn test_set_leak_parameters_by_tag(ph: EPANET) {
+        for id in ["10", "11", "9"] {
+            let index = ph.get_link_index(id).unwrap();
+            ph.set_tag(ObjectType::Link, index, "CI_1960").unwrap();
+        }
+        let index = ph.get_link_index("12").unwrap();
+        ph.set_tag(ObjectType::Link, index, "PVC_1990").unwrap();
+
+        // The pump carries the tag but cannot leak
+        assert_eq!(
+            ph.set_leak_parameters_by_tag(&classes()).unwrap(),
+            vec![2, 1]
+        );
+        let pipe = ph.get_link("11").unwrap();
+        let data = pipe.as_pipe().unwrap();
+        assert_eq!((data.leak_area, data.leak_expansion), (1.0, 0.1));
+        assert_eq!(ph.get_link("21").unwrap().as_pipe().unwrap().leak_area, 0.0);
+
+        let mut invalid = classes();
+        invalid[1].leak_area = -1.0;
+        let err = ph.set_leak_parameters_by_tag(&invalid).unwrap_err();
+        assert_eq!(err.code, 202);
+    }
+
+    #[rstest]
+    fn test_leakage_report(ph: EPANET) {
+        let report = ph.leakage_report().unwrap();
+        assert!(report.pipes.is_empty() && report.nodes.is_empty());
+        assert_eq!(report.total_volume, 0.0);
+
+        let mut pipe = ph.get_link("110").unwrap();
+        pipe.as_pipe_mut().unwrap().leak_area = 5.0;
+        pipe.update().unwrap();
+        let report = ph.leakage_report().unwrap();
+        assert_eq!(report.pipes.len(), 1);
+        assert_eq!(report.pipes[0].id, "110");
+        assert!(report.pipes[0].volume > 0.0);
+        assert_eq!(report.total_volume, report.pipes[0].volume);
+
+        // Pipe 110 joins tank 2 to junction 12; all of its leakage goes to
+        // the junction
+        let ids: Vec<&str> = report.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["12"]);
+        let node_volume: f64 = report.nodes.iter().map(|n| n.volume).sum();
+        // Node and pipe leakage agree to within the solver tolerance
+        assert!((node_volume - report.total_volume).abs() < 1e-3 * report.total_volume);
+        assert!(report.steps.iter().all(|s| s.leakage > 0.0));
+        assert_eq!(report.steps[0].cumulative_percent_loss, 0.0);
+        assert!(report.percent_loss > 0.0 && report.percent_loss < 100.0);
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::bindings as ffi;
 use crate::epanet_error::*;
+use crate::types::index::{CurveIndex, LinkIndex, NodeIndex, PatternIndex};
 use crate::types::link::*;
 use crate::types::MAX_ID_SIZE;
 use crate::types::{ActionCodeType, CountType::LinkCount};
@@ -22,7 +23,7 @@ impl EPANET {
         link_type: LinkType,
         from_node: &str,
         to_node: &str,
-    ) -> Result<i32> {
+    ) -> Result<LinkIndex> {
         let c_id = CString::new(id)?;
         let c_from = CString::new(from_node)?;
         let c_to = CString::new(to_node)?;
@@ -45,7 +46,7 @@ impl EPANET {
             ),
         )?;
 
-        Ok(out_index)
+        Ok(LinkIndex(out_index))
     }
 
     /// Retrieves a Link by its ID.
@@ -55,12 +56,13 @@ impl EPANET {
     }
 
     /// Retrieves a Link by its index.
-    pub fn get_link_by_index(&self, index: i32) -> Result<Link<'_>> {
+    pub fn get_link_by_index(&self, index: impl Into<LinkIndex>) -> Result<Link<'_>> {
+        let index = index.into();
         let id = self.get_link_id(index)?;
         let link_type = self.get_link_type(index)?;
         let (from_node, to_node) = self.get_link_nodes(index)?;
         let status = LinkStatusType::from_i32(
-            self.get_link_value(index, LinkProperty::Status)? as i32
+            self.get_link_value(index, LinkProperty::InitStatus)? as i32
         ).unwrap_or(LinkStatusType::Open);
 
         let kind = match link_type {
@@ -69,17 +71,21 @@ impl EPANET {
                 diameter: self.get_link_value(index, LinkProperty::Diameter)?,
                 roughness: self.get_link_value(index, LinkProperty::Roughness)?,
                 minor_loss: self.get_link_value(index, LinkProperty::MinorLoss)?,
+                leak_area: self.get_link_value(index, LinkProperty::LeakArea)?,
+                leak_expansion: self.get_link_value(index, LinkProperty::LeakExpan)?,
             }),
             LinkType::CvPipe => LinkKind::CvPipe(PipeData {
                 length: self.get_link_value(index, LinkProperty::Length)?,
                 diameter: self.get_link_value(index, LinkProperty::Diameter)?,
                 roughness: self.get_link_value(index, LinkProperty::Roughness)?,
                 minor_loss: self.get_link_value(index, LinkProperty::MinorLoss)?,
+                leak_area: self.get_link_value(index, LinkProperty::LeakArea)?,
+                leak_expansion: self.get_link_value(index, LinkProperty::LeakExpan)?,
             }),
             LinkType::Pump => {
                 let pump_type = self.get_pump_type(index)?;
                 let head_curve_idx = self.get_head_curve_index(index).ok();
-                let head_curve_index = if head_curve_idx == Some(0) {
+                let head_curve_index = if head_curve_idx == Some(CurveIndex(0)) {
                     None
                 } else {
                     head_curve_idx
@@ -90,7 +96,7 @@ impl EPANET {
                 let efficiency_curve_index = if efficiency_curve_idx == 0 {
                     None
                 } else {
-                    Some(efficiency_curve_idx)
+                    Some(CurveIndex(efficiency_curve_idx))
                 };
 
                 let energy_pattern_idx =
@@ -98,7 +104,7 @@ impl EPANET {
                 let energy_pattern_index = if energy_pattern_idx == 0 {
                     None
                 } else {
-                    Some(energy_pattern_idx)
+                    Some(PatternIndex(energy_pattern_idx))
                 };
 
                 LinkKind::Pump(PumpData {
@@ -120,10 +126,10 @@ impl EPANET {
             | LinkType::Pcv => {
                 let curve_idx = if link_type == LinkType::Gpv {
                     let idx = self.get_link_value(index, LinkProperty::GPVCurve)? as i32;
-                    if idx == 0 { None } else { Some(idx) }
+                    if idx == 0 { None } else { Some(CurveIndex(idx)) }
                 } else if link_type == LinkType::Pcv {
                     let idx = self.get_link_value(index, LinkProperty::PCVCurve)? as i32;
-                    if idx == 0 { None } else { Some(idx) }
+                    if idx == 0 { None } else { Some(CurveIndex(idx)) }
                 } else {
                     None
                 };
@@ -147,20 +153,27 @@ impl EPANET {
         })
     }
 
-    pub fn delete_link(&self, index: i32, action_code_type: ActionCodeType) -> Result<()> {
-        check_error(unsafe { ffi::EN_deletelink(self.ph, index, action_code_type as i32) })
+    pub fn delete_link(&self, index: impl Into<LinkIndex>, action_code_type: ActionCodeType) -> Result<()> {
+        let index = index.into();
+        let id = self.get_link_id(index).ok();
+        check_error(unsafe { ffi::EN_deletelink(self.ph, index.0, action_code_type as i32) })?;
+        if let Some(id) = id {
+            self.forget_link(&id);
+        }
+        Ok(())
     }
 
-    pub fn get_link_index(&self, id: &str) -> Result<i32> {
+    pub fn get_link_index(&self, id: &str) -> Result<LinkIndex> {
         let c_id = CString::new(id).unwrap();
         let mut out_index = 0;
         check_error(unsafe { ffi::EN_getlinkindex(self.ph, c_id.as_ptr(), &mut out_index) })?;
-        Ok(out_index)
+        Ok(LinkIndex(out_index))
     }
 
-    pub fn get_link_id(&self, index: i32) -> Result<String> {
+    pub fn get_link_id(&self, index: impl Into<LinkIndex>) -> Result<String> {
+        let index = index.into();
         let mut out_id: Vec<c_char> = vec![0; MAX_ID_SIZE as usize + 1usize];
-        check_error(unsafe { ffi::EN_getlinkid(self.ph, index, out_id.as_mut_ptr()) })?;
+        check_error(unsafe { ffi::EN_getlinkid(self.ph, index.0, out_id.as_mut_ptr()) })?;
         let id = unsafe { std::ffi::CStr::from_ptr(out_id.as_ptr()) }
             .to_string_lossy()
             .trim_end()
@@ -168,24 +181,31 @@ impl EPANET {
         Ok(id)
     }
 
-    pub fn set_link_id(&self, index: i32, id: &str) -> Result<()> {
+    pub fn set_link_id(&self, index: impl Into<LinkIndex>, id: &str) -> Result<()> {
+        let index = index.into();
         let c_id = CString::new(id).unwrap();
-        check_error(unsafe { ffi::EN_setlinkid(self.ph, index, c_id.as_ptr()) })
+        let old_id = self.get_link_id(index).ok();
+        check_error(unsafe { ffi::EN_setlinkid(self.ph, index.0, c_id.as_ptr()) })?;
+        if let Some(old_id) = old_id {
+            self.handles.borrow_mut().links.rename(&old_id, id);
+        }
+        Ok(())
     }
 
-    pub fn get_link_type(&self, index: i32) -> Result<LinkType> {
+    pub fn get_link_type(&self, index: impl Into<LinkIndex>) -> Result<LinkType> {
+        let index = index.into();
         let mut out_type = 0;
-        check_error(unsafe { ffi::EN_getlinktype(self.ph, index, &mut out_type) })?;
+        check_error(unsafe { ffi::EN_getlinktype(self.ph, index.0, &mut out_type) })?;
         Ok(LinkType::from_i32(out_type).unwrap())
     }
 
     pub fn set_link_type(
         &self,
-        index: i32,
+        index: impl Into<LinkIndex>,
         link_type: LinkType,
         action_code: ActionCodeType,
-    ) -> Result<i32> {
-        let mut in_out_index = index;
+    ) -> Result<LinkIndex> {
+        let mut in_out_index = index.into().0;
         let result = unsafe {
             ffi::EN_setlinktype(
                 self.ph,
@@ -195,25 +215,30 @@ impl EPANET {
             )
         };
         check_error(result)?;
-        Ok(in_out_index)
+        Ok(LinkIndex(in_out_index))
     }
 
-    pub fn get_link_nodes(&self, index: i32) -> Result<(i32, i32)> {
+    pub fn get_link_nodes(&self, index: impl Into<LinkIndex>) -> Result<(NodeIndex, NodeIndex)> {
+        let index = index.into();
         let (mut out_node1, mut out_node2) = (0, 0);
         check_error(unsafe {
-            ffi::EN_getlinknodes(self.ph, index, &mut out_node1, &mut out_node2)
+            ffi::EN_getlinknodes(self.ph, index.0, &mut out_node1, &mut out_node2)
         })?;
-        Ok((out_node1, out_node2))
+        Ok((NodeIndex(out_node1), NodeIndex(out_node2)))
     }
 
-    pub fn set_link_nodes(&self, index: i32, node1: i32, node2: i32) -> Result<()> {
-        check_error(unsafe { ffi::EN_setlinknodes(self.ph, index, node1, node2) })
+    pub fn set_link_nodes(&self, index: impl Into<LinkIndex>, node1: impl Into<NodeIndex>, node2: impl Into<NodeIndex>) -> Result<()> {
+        let index = index.into();
+        let node1 = node1.into();
+        let node2 = node2.into();
+        check_error(unsafe { ffi::EN_setlinknodes(self.ph, index.0, node1.0, node2.0) })
     }
 
-    pub fn get_link_value(&self, index: i32, property: LinkProperty) -> Result<f64> {
+    pub fn get_link_value(&self, index: impl Into<LinkIndex>, property: LinkProperty) -> Result<f64> {
+        let index = index.into();
         let mut out_value = 0.0;
         check_error(unsafe {
-            ffi::EN_getlinkvalue(self.ph, index, property as i32, &mut out_value)
+            ffi::EN_getlinkvalue(self.ph, index.0, property as i32, &mut out_value)
         })?;
         Ok(out_value)
     }
@@ -225,61 +250,112 @@ impl EPANET {
         Ok(values)
     }
 
-    pub fn set_link_value(&self, index: i32, property: LinkProperty, value: f64) -> Result<()> {
-        check_error(unsafe { ffi::EN_setlinkvalue(self.ph, index, property as i32, value) })
+    pub fn set_link_value(&self, index: impl Into<LinkIndex>, property: LinkProperty, value: f64) -> Result<()> {
+        let index = index.into();
+        check_error(unsafe { ffi::EN_setlinkvalue(self.ph, index.0, property as i32, value) })
+    }
+
+    /// Sets a property for all links, one value per link in index order, as
+    /// returned by [`get_link_values`](Self::get_link_values).
+    ///
+    /// If setting one link fails, the links already set are restored to their
+    /// previous values before the error, which names the failing link, is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 250 if `values` does not hold one value per link, or the
+    /// error of the first link that cannot be set.
+    pub fn set_link_values(&self, property: LinkProperty, values: &[f64]) -> Result<()> {
+        let link_count = self.get_count(LinkCount)?;
+        if values.len() != link_count as usize {
+            return Err(EPANETError::from(250).with_context(format!(
+                "{} values given to set {:?} for {} links",
+                values.len(),
+                property,
+                link_count
+            )));
+        }
+        self.set_link_values_sparse(property, (1..).zip(values.iter().copied()))
+    }
+
+    /// Sets a property for the links given as `(index, value)` pairs, leaving
+    /// all other links unchanged.
+    ///
+    /// If setting one link fails, the links already set are restored to their
+    /// previous values before the error, which names the failing link, is returned.
+    pub fn set_link_values_sparse<I: Into<LinkIndex>>(
+        &self,
+        property: LinkProperty,
+        values: impl IntoIterator<Item = (I, f64)>,
+    ) -> Result<()> {
+        self.set_each_or_restore(
+            values.into_iter().map(|(index, value)| (index.into().0, value)),
+            |index| self.get_link_value(index, property),
+            |index, value| self.set_link_value(index, property, value),
+            |index| format!("link {} (index {index})", self.get_link_id(index).unwrap_or_default()),
+        )
     }
 
     pub fn set_pipe_data(
         &self,
-        index: i32,
+        index: impl Into<LinkIndex>,
         length: f64,
         diameter: f64,
         roughness: f64,
         minor_loss: f64,
     ) -> Result<()> {
-        check_error(unsafe { ffi::EN_setpipedata(self.ph, index, length, diameter, roughness, minor_loss) })
+        let index = index.into();
+        check_error(unsafe { ffi::EN_setpipedata(self.ph, index.0, length, diameter, roughness, minor_loss) })
     }
 
-    pub fn get_pump_type(&self, index: i32) -> Result<PumpType> {
+    pub fn get_pump_type(&self, index: impl Into<LinkIndex>) -> Result<PumpType> {
+        let index = index.into();
         let mut out_type = 0;
-        check_error(unsafe { ffi::EN_getpumptype(self.ph, index, &mut out_type) })?;
+        check_error(unsafe { ffi::EN_getpumptype(self.ph, index.0, &mut out_type) })?;
         Ok(PumpType::from_i32(out_type).unwrap())
     }
 
-    pub fn get_head_curve_index(&self, link_index: i32) -> Result<i32> {
+    pub fn get_head_curve_index(&self, link_index: impl Into<LinkIndex>) -> Result<CurveIndex> {
+        let link_index = link_index.into();
         let mut out_index = 0;
-        check_error(unsafe { ffi::EN_getheadcurveindex(self.ph, link_index, &mut out_index) })?;
-        Ok(out_index)
+        check_error(unsafe { ffi::EN_getheadcurveindex(self.ph, link_index.0, &mut out_index) })?;
+        Ok(CurveIndex(out_index))
     }
 
-    pub fn set_head_curve_index(&self, link_index: i32, curve_index: i32) -> Result<()> {
-        check_error(unsafe { ffi::EN_setheadcurveindex(self.ph, link_index, curve_index) })
+    pub fn set_head_curve_index(&self, link_index: impl Into<LinkIndex>, curve_index: impl Into<CurveIndex>) -> Result<()> {
+        let link_index = link_index.into();
+        let curve_index = curve_index.into();
+        check_error(unsafe { ffi::EN_setheadcurveindex(self.ph, link_index.0, curve_index.0) })
     }
 
-    pub fn get_vertex_count(&self, link_index: i32) -> Result<i32> {
+    pub fn get_vertex_count(&self, link_index: impl Into<LinkIndex>) -> Result<i32> {
+        let link_index = link_index.into();
         let mut out_count = 0;
-        check_error(unsafe { ffi::EN_getvertexcount(self.ph, link_index, &mut out_count) })?;
+        check_error(unsafe { ffi::EN_getvertexcount(self.ph, link_index.0, &mut out_count) })?;
         Ok(out_count)
     }
 
-    pub fn get_vertex(&self, link_index: i32, vertex_index: i32) -> Result<(f64, f64)> {
+    pub fn get_vertex(&self, link_index: impl Into<LinkIndex>, vertex_index: i32) -> Result<(f64, f64)> {
+        let link_index = link_index.into();
         let (mut out_x, mut out_y) = (0.0, 0.0);
         check_error(unsafe {
-            ffi::EN_getvertex(self.ph, link_index, vertex_index, &mut out_x, &mut out_y)
+            ffi::EN_getvertex(self.ph, link_index.0, vertex_index, &mut out_x, &mut out_y)
         })?;
         Ok((out_x, out_y))
     }
 
-    pub fn set_vertex(&self, link_index: i32, vertex_index: i32, x: f64, y: f64) -> Result<()> {
-        check_error(unsafe { ffi::EN_setvertex(self.ph, link_index, vertex_index, x, y) })
+    pub fn set_vertex(&self, link_index: impl Into<LinkIndex>, vertex_index: i32, x: f64, y: f64) -> Result<()> {
+        let link_index = link_index.into();
+        check_error(unsafe { ffi::EN_setvertex(self.ph, link_index.0, vertex_index, x, y) })
     }
 
-    pub fn set_vertices(&self, link_index: i32, vertices: Vec<(f64, f64)>) -> Result<()> {
+    pub fn set_vertices(&self, link_index: impl Into<LinkIndex>, vertices: Vec<(f64, f64)>) -> Result<()> {
+        let link_index = link_index.into();
         let (mut xs, mut ys): (Vec<f64>, Vec<f64>) = vertices.iter().cloned().unzip();
         check_error(unsafe {
             ffi::EN_setvertices(
                 self.ph,
-                link_index,
+                link_index.0,
                 xs.as_mut_ptr(),
                 ys.as_mut_ptr(),
                 vertices.len() as i32,
@@ -318,7 +394,7 @@ mod tests {
     #[rstest]
     fn test_link_index_and_id(ph: EPANET) {
         let index = ph.get_link_index("10").unwrap();
-        assert!(index > 0);
+        assert!(index.0 > 0);
         assert_eq!(ph.get_link_id(index).unwrap(), "10");
         assert!(ph.get_link_index("nonexistent").is_err());
     }
@@ -389,7 +465,7 @@ mod tests {
         assert!(pump_type == PumpType::Custom || pump_type == PumpType::PowerFunc || pump_type == PumpType::ConstHp || pump_type == PumpType::NoCurve);
 
         let curve_index = ph.get_head_curve_index(index).unwrap();
-        assert!(curve_index > 0);
+        assert!(curve_index.0 > 0);
 
         ph.set_head_curve_index(index, curve_index).unwrap();
         assert_eq!(ph.get_head_curve_index(index).unwrap(), curve_index);
@@ -415,4 +491,20 @@ mod tests {
         assert!(approx_eq(x2, 10.0, 0.001));
         assert!(approx_eq(y2, 20.0, 0.001));
     }
+
+    #[rstest]
+    fn test_set_link_values(ph: EPANET) {
+        let count = ph.get_count(LinkCount).unwrap() as usize;
+        ph.set_link_values(LinkProperty::Roughness, &vec![120.0; count]).unwrap();
+        for pipe in ph.pipes().unwrap() {
+            assert_eq!(pipe.as_pipe().unwrap().roughness, 120.0);
+        }
+        assert_eq!(ph.set_link_values(LinkProperty::Roughness, &[]).unwrap_err().code, 250);
+
+        // A non-positive diameter fails; the first pipe keeps its diameter
+        let pipe = ph.get_link_index("11").unwrap();
+        let result = ph.set_link_values_sparse(LinkProperty::Diameter, [(1, 20.0), (pipe.0, -5.0)]);
+        assert!(result.unwrap_err().to_string().contains("link 11 (index 2)"));
+        assert_eq!(ph.get_link_value(1, LinkProperty::Diameter).unwrap(), 18.0);
+    }
 }
@@ -0,0 +1,622 @@
+//! Model merge API methods for EPANET.
+//!
+//! This module imports the curves, patterns, nodes, links, controls and rules of
+//! a second project into the current one, using the `add_*` and `set_*`
+//! wrappers, and remaps the references between them.
+
+use crate::epanet_error::*;
+use crate::types::index::{CurveIndex, LinkIndex, NodeIndex, PatternIndex};
+use crate::types::link::{Link, LinkKind, LinkProperty, LinkStatusType, LinkType};
+use crate::types::merge::*;
+use crate::types::node::{Node, NodeKind, NodeProperty};
+use crate::types::rule::{ActionClause, Premise, PremiseObject, Rule};
+use crate::types::CountType;
+use crate::EPANET;
+use std::collections::HashMap;
+
+/// ## Model Merge APIs
+impl EPANET {
+    /// Imports `other` into this project: its curves, patterns, nodes and links,
+    /// then (optionally) its simple controls and rules.
+    ///
+    /// Every incoming ID gets [`prefix`](MergeOptions::prefix); an ID that is
+    /// still taken is resolved by the [`ConflictRule`] for its element type.
+    /// Incoming nodes sharing an ID with an existing node are boundary nodes by
+    /// default, so that a proposed sub-network connects to the existing network
+    /// where it shares node IDs. References between incoming elements (link end
+    /// nodes, demand and energy patterns, pump and volume curves, controls and
+    /// rules) are remapped to the merged IDs.
+    ///
+    /// The element data copied is the design data: node elevations, demand
+    /// categories, emitters, tank geometry, initial quality and coordinates, and
+    /// link dimensions, roughness, pump curves and energy data, valve settings,
+    /// initial status and vertices.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 213 if the projects use different flow units or head loss
+    /// formulas, error 215 for an ID conflict under [`ConflictRule::Fail`] or a
+    /// type change under [`ConflictRule::Replace`], or any [`EPANETError`] from
+    /// the engine. Elements merged before an error remain in the project.
+    pub fn merge(&self, other: &EPANET, options: &MergeOptions) -> Result<MergeReport> {
+        if self.get_flow_units()? != other.get_flow_units()? {
+            return Err(EPANETError::from(213).with_context("models use different flow units"));
+        }
+        if self.head_loss_type()? != other.head_loss_type()? {
+            return Err(
+                EPANETError::from(213).with_context("models use different head loss formulas")
+            );
+        }
+        let mut report = MergeReport::default();
+        let mut ids = MergedIds::default();
+
+        for curve in other.curves()? {
+            let exists = |id: &str| self.get_curve_index(id).is_ok();
+            let (id, outcome) = resolve(options, "curve", &curve.id, options.curves, exists)?;
+            if matches!(outcome, MergeOutcome::Added | MergeOutcome::Renamed) {
+                self.add_curve(&id)?;
+            }
+            if outcome != MergeOutcome::Kept {
+                let index = self.get_curve_index(&id)?;
+                self.set_curve(index, &curve.points)?;
+                self.set_curve_type(index, curve.curve_type)?;
+            }
+            ids.curves.insert(curve.id.clone(), id.clone());
+            report.curves.push(merged(curve.id, id, outcome));
+        }
+
+        for pattern in other.patterns()? {
+            let exists = |id: &str| self.get_pattern_index(id).is_ok();
+            let (id, outcome) = resolve(options, "pattern", &pattern.id, options.patterns, exists)?;
+            if matches!(outcome, MergeOutcome::Added | MergeOutcome::Renamed) {
+                self.add_pattern(&id)?;
+            }
+            if outcome != MergeOutcome::Kept {
+                self.set_pattern(self.get_pattern_index(&id)?, &pattern.multipliers)?;
+            }
+            ids.patterns.insert(pattern.id.clone(), id.clone());
+            report.patterns.push(merged(pattern.id, id, outcome));
+        }
+
+        for node in other.nodes()? {
+            let exists = |id: &str| self.get_node_index(id).is_ok();
+            let (id, outcome) = if options.nodes == ConflictRule::Keep && exists(&node.id) {
+                (node.id.clone(), MergeOutcome::Kept)
+            } else {
+                resolve(options, "node", &node.id, options.nodes, exists)?
+            };
+            let index = match outcome {
+                MergeOutcome::Added | MergeOutcome::Renamed => {
+                    Some(self.add_node(&id, node.node_type())?)
+                }
+                MergeOutcome::Replaced => {
+                    let index = self.get_node_index(&id)?;
+                    let existing = self.get_node_type(index)?;
+                    if existing != node.node_type() {
+                        return Err(EPANETError::from(215).with_context(format!(
+                            "cannot replace {existing:?} {id} with a {:?}",
+                            node.node_type()
+                        )));
+                    }
+                    Some(index)
+                }
+                MergeOutcome::Kept => None,
+            };
+            if let Some(index) = index {
+                self.merge_node(other, &node, index, &ids)?;
+            }
+            ids.nodes.insert(node.id.clone(), id.clone());
+            report.nodes.push(merged(node.id.clone(), id, outcome));
+        }
+
+        for link in other.links()? {
+            let exists = |id: &str| self.get_link_index(id).is_ok();
+            let (id, outcome) = resolve(options, "link", &link.id, options.links, exists)?;
+            let from = ids.node(&other.get_node_id(link.from_node)?);
+            let to = ids.node(&other.get_node_id(link.to_node)?);
+            let link_type = link.link_type();
+            let index = match outcome {
+                MergeOutcome::Added | MergeOutcome::Renamed => {
+                    Some(self.add_link(&id, link_type, &from, &to)?)
+                }
+                MergeOutcome::Replaced => {
+                    let index = self.get_link_index(&id)?;
+                    let existing = self.get_link_type(index)?;
+                    if existing != link_type {
+                        return Err(EPANETError::from(215).with_context(format!(
+                            "cannot replace {existing:?} {id} with a {link_type:?}"
+                        )));
+                    }
+                    self.set_link_nodes(
+                        index,
+                        self.get_node_index(&from)?,
+                        self.get_node_index(&to)?,
+                    )?;
+                    Some(index)
+                }
+                MergeOutcome::Kept => None,
+            };
+            if let Some(index) = index {
+                self.merge_link(other, &link, index, &ids)?;
+            }
+            ids.links.insert(link.id.clone(), id.clone());
+            report.links.push(merged(link.id, id, outcome));
+        }
+
+        if options.copy_controls {
+            for control in other.controls()? {
+                let link =
+                    self.get_link_index(&ids.link(&other.get_link_id(control.link_index)?))?;
+                let node = match control.node_index {
+                    NodeIndex(0) => NodeIndex(0),
+                    index => self.get_node_index(&ids.node(&other.get_node_id(index)?))?,
+                };
+                let added = self.add_control(
+                    control.control_type,
+                    link,
+                    control.setting,
+                    node,
+                    control.level,
+                    control.enabled,
+                )?;
+                report.controls.push(added.index());
+            }
+        }
+
+        if options.copy_rules {
+            for rule in other.rules()? {
+                let existing: Vec<String> = (1..=self.get_count(CountType::RuleCount)?)
+                    .map(|index| self.get_rule_id(index))
+                    .collect::<Result<_>>()?;
+                let exists = |id: &str| existing.iter().any(|e| e == id);
+                let (id, outcome) = resolve(options, "rule", &rule.rule_id, options.rules, exists)?;
+                if outcome == MergeOutcome::Replaced {
+                    let position = existing.iter().position(|e| *e == id).unwrap_or_default();
+                    self.delete_rule(position as i32 + 1)?;
+                }
+                if outcome != MergeOutcome::Kept {
+                    self.merge_rule(other, &rule, &id, &ids)?;
+                }
+                report.rules.push(merged(rule.rule_id.clone(), id, outcome));
+            }
+        }
+        Ok(report)
+    }
+
+    // Helper methods - Internal API
+
+    /// Copies the data of `node` in `other` to the node at `index`.
+    fn merge_node(
+        &self,
+        other: &EPANET,
+        node: &Node,
+        index: NodeIndex,
+        ids: &MergedIds,
+    ) -> Result<()> {
+        let source = node.index();
+        match &node.kind {
+            NodeKind::Junction(junction) => {
+                for category in (2..=self.get_demand_count(index)?).rev() {
+                    self.delete_demand(index, category)?;
+                }
+                let pattern = |category| -> Result<String> {
+                    match other.get_demand_pattern(source, category)? {
+                        PatternIndex(0) => Ok(String::new()),
+                        pattern => Ok(ids.pattern(&other.get_pattern_id(pattern)?)),
+                    }
+                };
+                for category in 1..=other.get_demand_count(source)? {
+                    let base = other.get_base_demand(source, category)?;
+                    let name = other.get_demand_name(source, category)?;
+                    if category == 1 {
+                        self.set_junction_data(index, junction.elevation, base, &pattern(1)?)?;
+                        self.set_demand_name(index, 1, &name)?;
+                    } else {
+                        self.add_demand(index, base, &pattern(category)?, &name)?;
+                    }
+                }
+                self.set_node_value(index, NodeProperty::Emitter, junction.emitter)?;
+            }
+            NodeKind::Tank(tank) => {
+                let curve = match tank.volume_curve.as_str() {
+                    "" => String::new(),
+                    curve => ids.curve(curve),
+                };
+                self.set_tank_data(
+                    index,
+                    tank.elevation,
+                    tank.init_level,
+                    tank.min_level,
+                    tank.max_level,
+                    tank.diameter,
+                    tank.min_volume,
+                    &curve,
+                )?;
+            }
+            NodeKind::Reservoir(reservoir) => {
+                self.set_node_value(index, NodeProperty::Elevation, reservoir.elevation)?;
+                let pattern = other.get_node_value(source, NodeProperty::Pattern)? as i32;
+                let pattern = self.merged_pattern_index(other, PatternIndex(pattern), ids)?;
+                self.set_node_value(index, NodeProperty::Pattern, pattern.0 as f64)?;
+            }
+        }
+        let quality = other.get_node_value(source, NodeProperty::InitQual)?;
+        self.set_node_value(index, NodeProperty::InitQual, quality)?;
+        if let Ok((x, y)) = other.get_coordinates(source) {
+            self.set_coordinates(index, x, y)?;
+        }
+        Ok(())
+    }
+
+    /// Copies the data of `link` in `other` to the link at `index`.
+    fn merge_link(
+        &self,
+        other: &EPANET,
+        link: &Link,
+        index: LinkIndex,
+        ids: &MergedIds,
+    ) -> Result<()> {
+        let source = link.index();
+        let copy = |property| {
+            self.set_link_value(index, property, other.get_link_value(source, property)?)
+        };
+        match &link.kind {
+            LinkKind::Pipe(pipe) | LinkKind::CvPipe(pipe) => {
+                self.set_pipe_data(
+                    index,
+                    pipe.length,
+                    pipe.diameter,
+                    pipe.roughness,
+                    pipe.minor_loss,
+                )?;
+                copy(LinkProperty::KBulk)?;
+                copy(LinkProperty::KWall)?;
+                copy(LinkProperty::InitStatus)?;
+                self.set_link_value(index, LinkProperty::LeakArea, pipe.leak_area)?;
+                self.set_link_value(index, LinkProperty::LeakExpan, pipe.leak_expansion)?;
+            }
+            LinkKind::Pump(pump) => {
+                match pump.head_curve_index {
+                    Some(curve) if curve.0 > 0 => self
+                        .set_head_curve_index(index, self.merged_curve_index(other, curve, ids)?)?,
+                    _ if pump.power > 0.0 => {
+                        self.set_link_value(index, LinkProperty::PumpPower, pump.power)?
+                    }
+                    _ => {}
+                }
+                if let Some(curve) = pump.efficiency_curve_index.filter(|c| c.0 > 0) {
+                    let curve = self.merged_curve_index(other, curve, ids)?;
+                    self.set_link_value(index, LinkProperty::PumpECurve, curve.0 as f64)?;
+                }
+                for property in [LinkProperty::PumpEPat, LinkProperty::LinkPattern] {
+                    let pattern = other.get_link_value(source, property)? as i32;
+                    let pattern = self.merged_pattern_index(other, PatternIndex(pattern), ids)?;
+                    self.set_link_value(index, property, pattern.0 as f64)?;
+                }
+                copy(LinkProperty::PumpECost)?;
+                copy(LinkProperty::InitSetting)?;
+                copy(LinkProperty::InitStatus)?;
+            }
+            LinkKind::Valve(valve) => {
+                self.set_link_value(index, LinkProperty::Diameter, valve.diameter)?;
+                copy(LinkProperty::MinorLoss)?;
+                let curve_property = match link.link_type() {
+                    LinkType::Gpv => Some(LinkProperty::GPVCurve),
+                    LinkType::Pcv => Some(LinkProperty::PCVCurve),
+                    _ => None,
+                };
+                if let (Some(property), Some(curve)) =
+                    (curve_property, valve.curve_index.filter(|c| c.0 > 0))
+                {
+                    let curve = self.merged_curve_index(other, curve, ids)?;
+                    self.set_link_value(index, property, curve.0 as f64)?;
+                }
+                self.set_link_value(index, LinkProperty::InitSetting, valve.setting)?;
+                // Valves without a fixed status are active; only a closed valve is
+                // distinguishable through the initial status.
+                if link.status == LinkStatusType::Closed {
+                    self.set_link_value(index, LinkProperty::InitStatus, 0.0)?;
+                }
+            }
+        }
+        let vertices = link.vertices()?;
+        if !vertices.is_empty() {
+            self.set_vertices(index, vertices)?;
+        }
+        Ok(())
+    }
+
+    /// Adds `rule` of `other` as rule `id`, remapped to the merged elements.
+    ///
+    /// Rules can only be added from text, so a placeholder with the same number of
+    /// clauses is added first and its clauses are then overwritten.
+    fn merge_rule(&self, other: &EPANET, rule: &Rule, id: &str, ids: &MergedIds) -> Result<()> {
+        let premises = rule
+            .premises
+            .iter()
+            .map(|premise| self.merged_premise(other, premise, ids))
+            .collect::<Result<Vec<_>>>()?;
+        let remap = |actions: &[ActionClause]| {
+            actions
+                .iter()
+                .map(|action| {
+                    let id = ids.link(&other.get_link_id(action.link_index)?);
+                    let mut action = action.clone();
+                    action.link_index = self.get_link_index(&id)?;
+                    Ok(action)
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        let (then_actions, else_actions) = (remap(&rule.then_actions)?, remap(&rule.else_actions)?);
+        let placeholder = |keyword: &str, count: usize| -> Result<String> {
+            let link = self.get_link_id(then_actions[0].link_index)?;
+            Ok((0..count)
+                .map(|i| {
+                    let keyword = if i == 0 { keyword } else { "AND" };
+                    format!("\n{keyword} LINK {link} STATUS = OPEN")
+                })
+                .collect())
+        };
+        let conditions: String = (0..premises.len())
+            .map(|i| format!("\n{} SYSTEM TIME = 0", if i == 0 { "IF" } else { "AND" }))
+            .collect();
+        self.add_rule(&format!(
+            "RULE {id}{conditions}{}{}",
+            placeholder("THEN", then_actions.len())?,
+            placeholder("ELSE", else_actions.len())?
+        ))?;
+        let mut added = self.get_rule(self.get_count(CountType::RuleCount)?)?;
+        added.premises = premises;
+        added.then_actions = then_actions;
+        added.else_actions = else_actions;
+        added.priority = rule.priority;
+        added.enabled = rule.enabled;
+        self.update_rule(&added)
+    }
+
+    /// `premise` of a rule in `other` with its node or link remapped.
+    fn merged_premise(
+        &self,
+        other: &EPANET,
+        premise: &Premise,
+        ids: &MergedIds,
+    ) -> Result<Premise> {
+        let mut premise = premise.clone();
+        premise.object = match premise.object {
+            PremiseObject::Node(index) => PremiseObject::Node(
+                self.get_node_index(&ids.node(&other.get_node_id(index)?))?,
+            ),
+            PremiseObject::Link(index) => PremiseObject::Link(
+                self.get_link_index(&ids.link(&other.get_link_id(index)?))?,
+            ),
+            PremiseObject::System => PremiseObject::System,
+        };
+        Ok(premise)
+    }
+
+    /// Index in this project of the merged pattern `index` of `other`, or 0 for none.
+    fn merged_pattern_index(
+        &self,
+        other: &EPANET,
+        index: PatternIndex,
+        ids: &MergedIds,
+    ) -> Result<PatternIndex> {
+        match index {
+            PatternIndex(0) => Ok(index),
+            index => self.get_pattern_index(&ids.pattern(&other.get_pattern_id(index)?)),
+        }
+    }
+
+    /// Index in this project of the merged curve `index` of `other`.
+    fn merged_curve_index(
+        &self,
+        other: &EPANET,
+        index: CurveIndex,
+        ids: &MergedIds,
+    ) -> Result<CurveIndex> {
+        self.get_curve_index(&ids.curve(&other.get_curve_id(index)?))
+    }
+}
+
+/// Project IDs of the merged elements, keyed by their IDs in the incoming model.
+#[derive(Default)]
+struct MergedIds {
+    nodes: HashMap<String, String>,
+    links: HashMap<String, String>,
+    patterns: HashMap<String, String>,
+    curves: HashMap<String, String>,
+}
+
+impl MergedIds {
+    fn lookup(map: &HashMap<String, String>, id: &str) -> String {
+        map.get(id).cloned().unwrap_or_else(|| id.to_string())
+    }
+
+    fn node(&self, id: &str) -> String {
+        Self::lookup(&self.nodes, id)
+    }
+
+    fn link(&self, id: &str) -> String {
+        Self::lookup(&self.links, id)
+    }
+
+    fn pattern(&self, id: &str) -> String {
+        Self::lookup(&self.patterns, id)
+    }
+
+    fn curve(&self, id: &str) -> String {
+        Self::lookup(&self.curves, id)
+    }
+}
+
+/// Prefixes `source_id` and applies `rule` if the result is taken.
+fn resolve(
+    options: &MergeOptions,
+    kind: &str,
+    source_id: &str,
+    rule: ConflictRule,
+    exists: impl Fn(&str) -> bool,
+) -> Result<(String, MergeOutcome)> {
+    let id = format!("{}{source_id}", options.prefix);
+    if !exists(&id) {
+        return Ok((id, MergeOutcome::Added));
+    }
+    match rule {
+        ConflictRule::Keep => Ok((id, MergeOutcome::Kept)),
+        ConflictRule::Replace => Ok((id, MergeOutcome::Replaced)),
+        ConflictRule::Rename => {
+            let free = (2..)
+                .map(|n| format!("{id}_{n}"))
+                .find(|candidate| !exists(candidate))
+                .unwrap_or_default();
+            Ok((free, MergeOutcome::Renamed))
+        }
+        ConflictRule::Fail => {
+            Err(EPANETError::from(215).with_context(format!("{kind} {id} already exists")))
+        }
+    }
+}
+
+fn merged(source_id: String, id: String, outcome: MergeOutcome) -> MergedElement {
+    MergedElement {
+        source_id,
+        id,
+        outcome,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use crate::types::control::ControlType;
+    use crate::types::node::NodeType;
+    use crate::types::options::{FlowUnits, HeadLossType};
+    use rstest::rstest;
+
+    /// A GPM proposal extending net1 from node 22, with a pipe ID net1 already uses.
+    fn proposal() -> EPANET {
+        let ph = EPANET::new(
+            &temp_rpt_path(),
+            "",
+            FlowUnits::Gpm,
+            HeadLossType::HazenWilliams,
+        )
+        .unwrap();
+        let boundary = ph.add_node("22", NodeType::Junction).unwrap();
+        ph.set_junction_data(boundary, 695.0, 0.0, "").unwrap();
+        let new = ph.add_node("N1", NodeType::Junction).unwrap();
+        ph.add_pattern("1").unwrap();
+        ph.set_junction_data(new, 680.0, 50.0, "1").unwrap();
+        ph.set_coordinates(new, 60.0, 40.0).unwrap();
+        let pipe = ph.add_link("P1", LinkType::Pipe, "22", "N1").unwrap();
+        ph.set_pipe_data(pipe, 1000.0, 8.0, 120.0, 0.0).unwrap();
+        let pipe = ph.add_link("10", LinkType::Pipe, "N1", "22").unwrap();
+        ph.set_pipe_data(pipe, 1200.0, 6.0, 110.0, 0.0).unwrap();
+        ph.add_control(ControlType::Timer, 1, 0.0, 0, 3600.0, true)
+            .unwrap();
+        ph
+    }
+
+    #[rstest]
+    fn test_merge_boundary_nodes(ph: EPANET) {
+        let report = ph.merge(&proposal(), &MergeOptions::default()).unwrap();
+
+        assert_eq!(report.boundary_nodes().collect::<Vec<_>>(), vec!["22"]);
+        assert_eq!(
+            report.nodes[1],
+            merged("N1".into(), "N1".into(), MergeOutcome::Added)
+        );
+        assert_eq!(
+            report.links[1],
+            merged("10".into(), "10_2".into(), MergeOutcome::Renamed)
+        );
+        assert_eq!(report.patterns[0].outcome, MergeOutcome::Kept);
+
+        let pipe = ph
+            .get_link_by_index(ph.get_link_index("P1").unwrap())
+            .unwrap();
+        let ends = (
+            ph.get_node_id(pipe.from_node).unwrap(),
+            ph.get_node_id(pipe.to_node).unwrap(),
+        );
+        assert_eq!(ends, ("22".to_string(), "N1".to_string()));
+        let renamed = ph.get_link_index("10_2").unwrap();
+        assert_eq!(
+            ph.get_link_value(renamed, LinkProperty::Diameter).unwrap(),
+            6.0
+        );
+        let n1 = ph.get_node_index("N1").unwrap();
+        assert_eq!(ph.get_coordinates(n1).unwrap(), (60.0, 40.0));
+        assert_eq!(
+            ph.get_demand_pattern(n1, 1).unwrap(),
+            ph.get_pattern_index("1").unwrap()
+        );
+        // The boundary node keeps its own data.
+        let node22 = ph.get_node_index("22").unwrap();
+        assert_eq!(ph.get_base_demand(node22, 1).unwrap(), 200.0);
+
+        assert_eq!(report.controls, vec![3]);
+        let control = ph.get_control(3).unwrap();
+        assert_eq!(control.link_index, ph.get_link_index("P1").unwrap());
+        assert!(ph.solve_h().is_ok());
+    }
+
+    #[rstest]
+    fn test_merge_with_prefix(ph: EPANET, #[from(ph)] other: EPANET) {
+        other
+            .add_rule("RULE R1\nIF NODE 2 LEVEL > 140\nTHEN LINK 9 STATUS = CLOSED\nPRIORITY 2")
+            .unwrap();
+        let options = MergeOptions {
+            prefix: "B-".into(),
+            nodes: ConflictRule::Rename,
+            ..MergeOptions::default()
+        };
+        let report = ph.merge(&other, &options).unwrap();
+
+        assert_eq!(ph.get_count(CountType::NodeCount).unwrap(), 22);
+        assert_eq!(ph.get_count(CountType::LinkCount).unwrap(), 26);
+        assert!(report
+            .nodes
+            .iter()
+            .all(|n| n.outcome == MergeOutcome::Added && n.id == format!("B-{}", n.source_id)));
+        assert_eq!(report.boundary_nodes().count(), 0);
+
+        let pump = ph.get_link_index("B-9").unwrap();
+        let curve = ph.get_head_curve_index(pump).unwrap();
+        assert_eq!(ph.get_curve_id(curve).unwrap(), "B-1");
+        let control = ph.get_control(report.controls[0]).unwrap();
+        assert_eq!(ph.get_link_id(control.link_index).unwrap(), "B-9");
+        assert_eq!(ph.get_node_id(control.node_index).unwrap(), "B-2");
+
+        assert_eq!(
+            report.rules,
+            vec![merged("R1".into(), "B-R1".into(), MergeOutcome::Added)]
+        );
+        let rule = ph.get_rule(1).unwrap();
+        assert_eq!(rule.rule_id, "B-R1");
+        assert_eq!(
+            ph.get_node_id(rule.premises[0].object.index()).unwrap(),
+            "B-2"
+        );
+        assert_eq!(rule.premises[0].value, 140.0);
+        assert_eq!(
+            ph.get_link_id(rule.then_actions[0].link_index).unwrap(),
+            "B-9"
+        );
+        assert_eq!(rule.priority, 2.0);
+    }
+
+    #[rstest]
+    fn test_merge_conflicts(ph: EPANET, ph_close: EPANET) {
+        let options = MergeOptions {
+            links: ConflictRule::Fail,
+            ..MergeOptions::default()
+        };
+        let error = ph.merge(&proposal(), &options).unwrap_err();
+        assert_eq!(error.code, 215);
+
+        let error = ph.merge(&ph_close, &MergeOptions::default()).unwrap_err();
+        assert_eq!(error.code, 213);
+    }
+}
@@ -18,19 +18,64 @@
 //! | [`pattern`] | Time pattern CRUD |
 //! | [`report`] | Report generation, report file output, statistics, error lookup |
 //! | [`rule`] | Rule-based control CRUD |
+//! | [`schedule`] | Pump schedule evaluation, optimization, and write-back as patterns or controls |
+//! | [`design`] | Loading condition checks, pipe sizing optimization against a diameter catalogue |
+//! | [`sensitivity`] | Morris and Sobol global sensitivity analysis of simulation outputs |
+//! | [`skeleton`] | Network skeletonization: branch trimming, series and parallel pipe merging |
+//! | [`flushing`] | Unidirectional flushing sequence simulation |
+//! | [`export`] | Streaming CSV / NDJSON result writers, run-to-file export |
+//! | `arrow` | Recorded results and network attributes as Arrow record batches (`arrow` feature) |
+//! | `geojson` | GeoJSON export of network geometry and attributes, import of nodes and links (`geojson` feature) |
+//! | [`gis`] | Model building from pipe polylines: end snapping, intersection splitting, elevations |
+//! | [`render`] | Standalone SVG network maps coloured by results, with legends and element symbols |
+//! | [`validation`] | Model lint engine: connectivity, element data plausibility, empty patterns, dangling references |
+//! | [`diff`] | Element-by-element model comparison with rename detection and float tolerance |
+//! | [`merge`] | Importing a second model with ID prefixing, conflict rules, boundary nodes and remapped controls |
+//! | [`handle`] | Stable node and link handles that follow deletions and renames |
+//! | [`iter`] | Lazy node and link iterators, filtered by type, tag or property value, and ID-only iteration |
+//! | [`arrays`] | Struct-of-arrays network snapshot with a CSR node-link incidence matrix |
+//! | `ndarray` | Recorded results as `time × element` matrices, property values as arrays (`ndarray` feature) |
+//! | [`pda`] | Switching to pressure driven analysis, per-step and run summaries of demand delivery |
+//! | [`leakage`] | Leak parameters assigned by pipe tag, per-pipe, per-node and system leakage reports |
+//! | [`emitter`] | Emitter discharge law, leak localization from excess minimum night flow |
+//! | [`allocation`] | Customer billing records allocated to the nearest junction or pipe as named demand categories |
 //! | [`collections`] | Bulk fetch: nodes, links, pipes, pumps, valves, patterns, curves, controls, rules |
 
+pub mod allocation;
+pub mod arrays;
+#[cfg(feature = "arrow")]
+pub mod arrow;
 pub mod collections;
 pub mod control;
 pub mod curve;
 pub mod demand;
+pub mod design;
+pub mod diff;
+pub mod emitter;
+pub mod export;
+pub mod flushing;
+#[cfg(feature = "geojson")]
+pub mod geojson;
+pub mod gis;
+pub mod handle;
 pub mod hydraulic;
+pub mod iter;
+pub mod leakage;
 pub mod link;
+pub mod merge;
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
 pub mod node;
 pub mod options;
 pub mod pattern;
+pub mod pda;
 pub mod project;
 pub mod quality;
+pub mod render;
 pub mod report;
 pub mod rule;
+pub mod schedule;
+pub mod sensitivity;
+pub mod skeleton;
 pub mod test_utils;
+pub mod validation;
@@ -0,0 +1,129 @@
+//! ndarray API methods for EPANET.
+//!
+//! This module contains methods, available with the `ndarray` feature, for
+//! recording simulation results as `time × element` matrices and reading node
+//! and link properties as one-dimensional arrays.
+
+use crate::epanet_error::*;
+use crate::types::export::{is_report_time, ExportOptions};
+use crate::types::link::LinkProperty;
+use crate::types::ndarray::*;
+use crate::types::node::NodeProperty;
+use crate::EPANET;
+use ndarray::{Array1, Array2};
+
+/// ## ndarray APIs
+impl EPANET {
+    /// Runs an extended period simulation and records the properties and elements
+    /// selected by `options` at each step, as one `time × element` matrix per
+    /// property.
+    ///
+    /// Steps are filtered by [`ExportOptions::report_times_only`] exactly as
+    /// [`export_results`](EPANET::export_results) does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if an ID does not exist or the solver fails.
+    pub fn record_result_arrays(&self, options: &ExportOptions) -> Result<ResultArrays> {
+        let (nodes, links) = self.export_elements(options)?;
+        let (report_start, report_step) = self.report_times()?;
+        let mut times = Vec::new();
+        let mut node_values = vec![Vec::new(); options.node_properties.len()];
+        let mut link_values = vec![Vec::new(); options.link_properties.len()];
+        self.run_export_simulation(self.exports_quality(options)?, |project, t| {
+            if options.report_times_only && !is_report_time(t, report_start, report_step) {
+                return Ok(());
+            }
+            times.push(t);
+            for (values, property) in node_values.iter_mut().zip(&options.node_properties) {
+                for (index, _) in &nodes {
+                    values.push(project.get_node_value(*index, *property)?);
+                }
+            }
+            for (values, property) in link_values.iter_mut().zip(&options.link_properties) {
+                for (index, _) in &links {
+                    values.push(project.get_link_value(*index, *property)?);
+                }
+            }
+            Ok(())
+        })?;
+
+        let steps = times.len();
+        let matrix = |values: Vec<f64>, columns: usize| {
+            Array2::from_shape_vec((steps, columns), values)
+                .expect("one value per step and element")
+        };
+        Ok(ResultArrays {
+            times: Array1::from(times),
+            node_properties: options.node_properties.clone(),
+            link_properties: options.link_properties.clone(),
+            node_values: node_values
+                .into_iter()
+                .map(|values| matrix(values, nodes.len()))
+                .collect(),
+            link_values: link_values
+                .into_iter()
+                .map(|values| matrix(values, links.len()))
+                .collect(),
+            node_ids: nodes.into_iter().map(|(_, id)| id).collect(),
+            link_ids: links.into_iter().map(|(_, id)| id).collect(),
+        })
+    }
+
+    /// Current value of a property for every node, in index order, as an
+    /// [`Array1`]. See [`get_node_values`](EPANET::get_node_values).
+    pub fn get_node_values_array(&self, property: NodeProperty) -> Result<Array1<f64>> {
+        self.get_node_values(property).map(Array1::from)
+    }
+
+    /// Current value of a property for every link, in index order, as an
+    /// [`Array1`]. See [`get_link_values`](EPANET::get_link_values).
+    pub fn get_link_values_array(&self, property: LinkProperty) -> Result<Array1<f64>> {
+        self.get_link_values(property).map(Array1::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use ndarray::Axis;
+    use rstest::rstest;
+
+    #[rstest]
+    fn test_record_result_arrays(ph: EPANET) {
+        let options = ExportOptions {
+            node_properties: vec![NodeProperty::Pressure, NodeProperty::Demand],
+            link_properties: vec![LinkProperty::Flow],
+            nodes: Some(vec!["10".into(), "22".into(), "2".into()]),
+            ..ExportOptions::default()
+        };
+        let results = ph.record_result_arrays(&options).unwrap();
+        assert_eq!(results.step_count(), 25);
+        assert_eq!(results.times[1] - results.times[0], 3600);
+
+        let pressure = results.node(NodeProperty::Pressure).unwrap();
+        assert_eq!(pressure.dim(), (25, 3));
+        assert!(pressure.column(1).iter().all(|p| *p > 0.0));
+        let flows = results.link(LinkProperty::Flow).unwrap();
+        assert_eq!(flows.dim(), (25, 13));
+        assert!(results.link(LinkProperty::Velocity).is_none());
+
+        // Junction 10 has no demand, junction 22 does
+        let demand = results.node(NodeProperty::Demand).unwrap();
+        let mean = demand.mean_axis(Axis(0)).unwrap();
+        assert!(mean[1] > 0.0 && mean[0] == 0.0);
+    }
+
+    #[rstest]
+    fn test_values_arrays(ph: EPANET) {
+        let elevations = ph.get_node_values_array(NodeProperty::Elevation).unwrap();
+        assert_eq!(
+            elevations.to_vec(),
+            ph.get_node_values(NodeProperty::Elevation).unwrap()
+        );
+        let lengths = ph.get_link_values_array(LinkProperty::Length).unwrap();
+        assert_eq!(lengths.len(), 13);
+        assert_eq!(lengths[ph.get_link_index("110").unwrap().offset()], 200.0);
+    }
+}
@@ -5,6 +5,7 @@
 use crate::bindings as ffi;
 use crate::epanet_error::*;
 use crate::types::node::{JunctionData, Node, NodeKind, NodeProperty, NodeType, ReservoirData, TankData};
+use crate::types::index::NodeIndex;
 use crate::types::MAX_MSG_SIZE;
 use crate::types::{ActionCodeType, CountType::NodeCount};
 use crate::EPANET;
@@ -15,7 +16,7 @@ use std::mem::MaybeUninit;
 /// ## Node APIs
 impl EPANET {
     /// Thin wrapper around the raw `EN_addnode` FFI call returning the node index.
-    pub fn add_node(&self, id: &str, node_type: NodeType) -> Result<i32> {
+    pub fn add_node(&self, id: &str, node_type: NodeType) -> Result<NodeIndex> {
         let _id = CString::new(id)?;
         let mut out_index = MaybeUninit::uninit();
         let code = unsafe {
@@ -30,7 +31,7 @@ impl EPANET {
             code,
             format!("Failed to add node of type {:?} with id {}", node_type, id),
         )?;
-        Ok(unsafe { out_index.assume_init() })
+        Ok(NodeIndex(unsafe { out_index.assume_init() }))
     }
 
     /// Retrieves a [`Node`] by its identifier.
@@ -39,7 +40,8 @@ impl EPANET {
         self.get_node_by_index(index)
     }
 
-    pub fn get_node_by_index(&self, index: i32) -> Result<Node<'_>> {
+    pub fn get_node_by_index(&self, index: impl Into<NodeIndex>) -> Result<Node<'_>> {
+        let index = index.into();
         let id = self.get_node_id(index)?;
         let node_type = self.get_node_type(index)?;
 
@@ -56,6 +58,7 @@ impl EPANET {
                     elevation: self.get_node_value(index, NodeProperty::Elevation)?,
                     demand: self.get_node_value(index, NodeProperty::BaseDemand)?,
                     demand_pattern,
+                    emitter: self.get_node_value(index, NodeProperty::Emitter)?,
                 })
             }
             NodeType::Tank => {
@@ -130,15 +133,21 @@ impl EPANET {
     /// # See Also
     /// - `EN_deletenode` (EPANET C API)
     /// - [`ActionCodeType`] for possible adjustment actions when deleting a node.
-    pub fn delete_node(&self, id: i32, action_code: ActionCodeType) -> Result<()> {
-        let code = unsafe { ffi::EN_deletenode(self.ph, id, action_code as i32) };
+    pub fn delete_node(&self, id: impl Into<NodeIndex>, action_code: ActionCodeType) -> Result<()> {
+        let id = id.into();
+        let node_id = self.get_node_id(id).ok();
+        let code = unsafe { ffi::EN_deletenode(self.ph, id.0, action_code as i32) };
         check_error_with_context(
             code,
             format!(
                 "Failed to delete node with id {} with action code {:?}",
                 id, action_code
             ),
-        )
+        )?;
+        if let Some(node_id) = node_id {
+            self.forget_node(&node_id);
+        }
+        Ok(())
     }
 
     /// Retrieves the index of a node in the EPANET model given its ID.
@@ -175,12 +184,12 @@ impl EPANET {
     ///
     /// # See Also
     /// - EN_getnodeindex (EPANET C API)
-    pub fn get_node_index(&self, id: &str) -> Result<i32> {
+    pub fn get_node_index(&self, id: &str) -> Result<NodeIndex> {
         let _id = CString::new(id)?;
         let mut out_index = MaybeUninit::uninit();
         let code = unsafe { ffi::EN_getnodeindex(self.ph, _id.as_ptr(), out_index.as_mut_ptr()) };
         check_error_with_context(code, format!("Failed to get index for node with id {}", id))?;
-        Ok(unsafe { out_index.assume_init() })
+        Ok(NodeIndex(unsafe { out_index.assume_init() }))
     }
 
     /// Retrieves the ID of a specific node in the EPANET model.
@@ -221,9 +230,10 @@ impl EPANET {
     /// # See Also
     /// - EN_getnodeid (EPANET C API)
     /// - [`MAX_MSG_SIZE`] for the size limit used for node IDs.
-    pub fn get_node_id(&self, index: i32) -> Result<String> {
+    pub fn get_node_id(&self, index: impl Into<NodeIndex>) -> Result<String> {
+        let index = index.into();
         let mut out_id: Vec<c_char> = vec![0; MAX_MSG_SIZE as usize + 1usize];
-        let code = unsafe { ffi::EN_getnodeid(self.ph, index, out_id.as_mut_ptr()) };
+        let code = unsafe { ffi::EN_getnodeid(self.ph, index.0, out_id.as_mut_ptr()) };
         check_error_with_context(
             code,
             format!("Failed to get node id for node at index {}", index),
@@ -271,16 +281,22 @@ impl EPANET {
     ///
     /// # See Also
     /// - EN_setnodeid (EPANET C API)
-    pub fn set_node_id(&self, index: i32, node_id: &str) -> Result<()> {
+    pub fn set_node_id(&self, index: impl Into<NodeIndex>, node_id: &str) -> Result<()> {
+        let index = index.into();
         let _id = CString::new(node_id)?;
-        let code = unsafe { ffi::EN_setnodeid(self.ph, index, _id.as_ptr()) };
+        let old_id = self.get_node_id(index).ok();
+        let code = unsafe { ffi::EN_setnodeid(self.ph, index.0, _id.as_ptr()) };
         check_error_with_context(
             code,
             format!(
                 "Failed to set the id of {} for node at index {}",
                 node_id, index
             ),
-        )
+        )?;
+        if let Some(old_id) = old_id {
+            self.handles.borrow_mut().nodes.rename(&old_id, node_id);
+        }
+        Ok(())
     }
 
     /// Retrieves the type of a specific node in the EPANET model.
@@ -318,9 +334,10 @@ impl EPANET {
     /// # See Also
     /// - EN_getnodetype (EPANET C API)
     /// - [`NodeType`] for the list of possible node types returned by this function.
-    pub fn get_node_type(&self, index: i32) -> Result<NodeType> {
+    pub fn get_node_type(&self, index: impl Into<NodeIndex>) -> Result<NodeType> {
+        let index = index.into();
         let mut node_type: MaybeUninit<c_int> = MaybeUninit::uninit();
-        let code = unsafe { ffi::EN_getnodetype(self.ph, index, node_type.as_mut_ptr()) };
+        let code = unsafe { ffi::EN_getnodetype(self.ph, index.0, node_type.as_mut_ptr()) };
         check_error_with_context(
             code,
             format!("Failed to get node type for node at index {}", index),
@@ -343,11 +360,12 @@ impl EPANET {
     /// Retrieves the value of a specific property for a single node.
     ///
     /// This thin wrapper delegates to the raw `EN_getnodevalue` FFI function.
-    pub fn get_node_value(&self, index: i32, node_property: NodeProperty) -> Result<f64> {
+    pub fn get_node_value(&self, index: impl Into<NodeIndex>, node_property: NodeProperty) -> Result<f64> {
+        let index = index.into();
         let mut value = MaybeUninit::<f64>::uninit();
         check_error_with_context(
             unsafe {
-                ffi::EN_getnodevalue(self.ph, index, node_property as i32, value.as_mut_ptr())
+                ffi::EN_getnodevalue(self.ph, index.0, node_property as i32, value.as_mut_ptr())
             },
             format!(
                 "Failed to get {:?} for node at index {}",
@@ -362,11 +380,12 @@ impl EPANET {
     /// This thin wrapper delegates to the raw `EN_setnodevalue` FFI function.
     pub fn set_node_value(
         &self,
-        index: i32,
+        index: impl Into<NodeIndex>,
         node_property: NodeProperty,
         value: f64,
     ) -> Result<()> {
-        let code = unsafe { ffi::EN_setnodevalue(self.ph, index, node_property as i32, value) };
+        let index = index.into();
+        let code = unsafe { ffi::EN_setnodevalue(self.ph, index.0, node_property as i32, value) };
         check_error_with_context(
             code,
             format!(
@@ -376,22 +395,64 @@ impl EPANET {
         )
     }
 
+    /// Sets a property for all nodes, one value per node in index order, as
+    /// returned by [`get_node_values`](Self::get_node_values).
+    ///
+    /// If setting one node fails, the nodes already set are restored to their
+    /// previous values before the error, which names the failing node, is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 250 if `values` does not hold one value per node, or the
+    /// error of the first node that cannot be set.
+    pub fn set_node_values(&self, node_property: NodeProperty, values: &[f64]) -> Result<()> {
+        let node_count = self.get_count(NodeCount)?;
+        if values.len() != node_count as usize {
+            return Err(EPANETError::from(250).with_context(format!(
+                "{} values given to set {:?} for {} nodes",
+                values.len(),
+                node_property,
+                node_count
+            )));
+        }
+        self.set_node_values_sparse(node_property, (1..).zip(values.iter().copied()))
+    }
+
+    /// Sets a property for the nodes given as `(index, value)` pairs, leaving
+    /// all other nodes unchanged.
+    ///
+    /// If setting one node fails, the nodes already set are restored to their
+    /// previous values before the error, which names the failing node, is returned.
+    pub fn set_node_values_sparse<I: Into<NodeIndex>>(
+        &self,
+        node_property: NodeProperty,
+        values: impl IntoIterator<Item = (I, f64)>,
+    ) -> Result<()> {
+        self.set_each_or_restore(
+            values.into_iter().map(|(index, value)| (index.into().0, value)),
+            |index| self.get_node_value(index, node_property),
+            |index, value| self.set_node_value(index, node_property, value),
+            |index| format!("node {} (index {index})", self.get_node_id(index).unwrap_or_default()),
+        )
+    }
+
     pub fn set_junction_data(
         &self,
-        index: i32,
+        index: impl Into<NodeIndex>,
         elevation: f64,
         demand: f64,
         demand_pattern: &str,
     ) -> Result<()> {
+        let index = index.into();
         let _demand_pat = CString::new(demand_pattern)?;
         check_error(unsafe {
-            ffi::EN_setjuncdata(self.ph, index, elevation, demand, _demand_pat.as_ptr())
+            ffi::EN_setjuncdata(self.ph, index.0, elevation, demand, _demand_pat.as_ptr())
         })
     }
 
     pub fn set_tank_data(
         &self,
-        index: i32,
+        index: impl Into<NodeIndex>,
         elevation: f64,
         init_level: f64,
         min_level: f64,
@@ -400,11 +461,12 @@ impl EPANET {
         min_volume: f64,
         volume_curve: &str,
     ) -> Result<()> {
+        let index = index.into();
         let _volume_curve = CString::new(volume_curve)?;
         check_error(unsafe {
             ffi::EN_settankdata(
                 self.ph,
-                index,
+                index.0,
                 elevation,
                 init_level,
                 min_level,
@@ -416,21 +478,54 @@ impl EPANET {
         })
     }
 
-    pub fn get_coordinates(&self, index: i32) -> Result<(f64, f64)> {
+    pub fn get_coordinates(&self, index: impl Into<NodeIndex>) -> Result<(f64, f64)> {
+        let index = index.into();
         let (mut x, mut y) = (0f64, 0f64);
-        check_error(unsafe { ffi::EN_getcoord(self.ph, index, &mut x, &mut y) })?;
+        check_error(unsafe { ffi::EN_getcoord(self.ph, index.0, &mut x, &mut y) })?;
         Ok((x, y))
     }
 
-    pub fn set_coordinates(&self, index: i32, x: f64, y: f64) -> Result<()> {
-        check_error(unsafe { ffi::EN_setcoord(self.ph, index, x, y) })
+    pub fn set_coordinates(&self, index: impl Into<NodeIndex>, x: f64, y: f64) -> Result<()> {
+        let index = index.into();
+        check_error(unsafe { ffi::EN_setcoord(self.ph, index.0, x, y) })
     }
 
-    pub fn get_number_of_demands(&self, index: i32) -> Result<i32> {
+    pub fn get_number_of_demands(&self, index: impl Into<NodeIndex>) -> Result<i32> {
+        let index = index.into();
         let mut num_demands: i32 = 0;
-        check_error(unsafe { ffi::EN_getnumdemands(self.ph, index, &mut num_demands) })?;
+        check_error(unsafe { ffi::EN_getnumdemands(self.ph, index.0, &mut num_demands) })?;
         Ok(num_demands)
     }
+
+    // Helper methods - Internal API
+
+    /// Applies `set` to each `(index, value)` in turn. If an element fails, the
+    /// elements already set are put back to the values read with `get` before
+    /// they were changed, and the error is returned naming the element.
+    pub(crate) fn set_each_or_restore(
+        &self,
+        values: impl IntoIterator<Item = (i32, f64)>,
+        get: impl Fn(i32) -> Result<f64>,
+        set: impl Fn(i32, f64) -> Result<()>,
+        element: impl Fn(i32) -> String,
+    ) -> Result<()> {
+        let mut previous = Vec::new();
+        for (index, value) in values {
+            match get(index).and_then(|old| set(index, value).map(|_| old)) {
+                Ok(old) => previous.push((index, old)),
+                Err(error) => {
+                    for (index, old) in previous.into_iter().rev() {
+                        let _ = set(index, old);
+                    }
+                    return Err(error.with_context(format!(
+                        "failed to set {}; earlier elements restored",
+                        element(index)
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -556,4 +651,29 @@ mod tests {
             1e-3
         ));
     }
+
+    #[rstest]
+    fn test_set_node_values(ph: EPANET) {
+        let raised: Vec<f64> = ph.get_node_values(Elevation).unwrap().iter().map(|e| e + 1.0).collect();
+        ph.set_node_values(Elevation, &raised).unwrap();
+        assert_eq!(ph.get_node_values(Elevation).unwrap(), raised);
+
+        let result = ph.set_node_values(Elevation, &raised[1..]);
+        assert_eq!(result.unwrap_err().code, 250);
+    }
+
+    #[rstest]
+    fn test_set_node_values_sparse_restores(ph: EPANET) {
+        ph.set_node_values_sparse(Emitter, [(2, 0.5), (5, 0.25)]).unwrap();
+        assert_eq!(ph.get_node_value(5, Emitter).unwrap(), 0.25);
+
+        // A negative emitter coefficient fails on the third node
+        let error = ph
+            .set_node_values_sparse(Emitter, [(1, 0.1), (2, 0.2), (3, -1.0)])
+            .unwrap_err();
+        assert_eq!(error.code, 209);
+        assert!(error.to_string().contains("node 12 (index 3)"), "{error}");
+        assert_eq!(ph.get_node_value(1, Emitter).unwrap(), 0.0);
+        assert_eq!(ph.get_node_value(2, Emitter).unwrap(), 0.5);
+    }
 }
@@ -4,6 +4,7 @@
 
 use crate::bindings as ffi;
 use crate::epanet_error::*;
+use crate::types::index::NodeIndex;
 use crate::types::options::{FlowUnits, Option, QualityAnalysisInfo, QualityType, TimeParameter};
 use crate::types::MAX_ID_SIZE;
 use crate::EPANET;
@@ -82,7 +83,7 @@ impl EPANET {
             quality_type,
             chem_name,
             chem_units,
-            trace_node_index,
+            trace_node_index: NodeIndex(trace_node_index),
         })
     }
 
@@ -5,6 +5,7 @@
 
 use crate::bindings as ffi;
 use crate::epanet_error::*;
+use crate::types::index::PatternIndex;
 use crate::types::pattern::Pattern;
 use crate::types::MAX_ID_SIZE;
 use crate::EPANET;
@@ -73,7 +74,8 @@ impl EPANET {
     /// # Errors
     ///
     /// Returns an [`EPANETError`] if the index is invalid.
-    pub fn get_pattern_by_index(&self, index: i32) -> Result<Pattern<'_>> {
+    pub fn get_pattern_by_index(&self, index: impl Into<PatternIndex>) -> Result<Pattern<'_>> {
+        let index = index.into();
         let id = self.get_pattern_id(index)?;
         let length = self.get_pattern_length(index)?;
 
@@ -101,22 +103,24 @@ impl EPANET {
     }
 
     /// Deletes a time pattern from the project by its 1-based index.
-    pub fn delete_pattern(&self, index: i32) -> Result<()> {
-        check_error(unsafe { ffi::EN_deletepattern(self.ph, index) })
+    pub fn delete_pattern(&self, index: impl Into<PatternIndex>) -> Result<()> {
+        let index = index.into();
+        check_error(unsafe { ffi::EN_deletepattern(self.ph, index.0) })
     }
 
     /// Returns the 1-based index of a pattern given its ID.
-    pub fn get_pattern_index(&self, id: &str) -> Result<i32> {
+    pub fn get_pattern_index(&self, id: &str) -> Result<PatternIndex> {
         let mut index: i32 = 0;
         let c_id = std::ffi::CString::new(id).unwrap();
         check_error(unsafe { ffi::EN_getpatternindex(self.ph, c_id.as_ptr(), &mut index) })?;
-        Ok(index)
+        Ok(PatternIndex(index))
     }
 
     /// Returns the ID of a pattern given its 1-based index.
-    pub fn get_pattern_id(&self, index: i32) -> Result<String> {
+    pub fn get_pattern_id(&self, index: impl Into<PatternIndex>) -> Result<String> {
+        let index = index.into();
         let mut out_id: Vec<std::ffi::c_char> = vec![0; MAX_ID_SIZE as usize + 1];
-        check_error(unsafe { ffi::EN_getpatternid(self.ph, index, out_id.as_mut_ptr()) })?;
+        check_error(unsafe { ffi::EN_getpatternid(self.ph, index.0, out_id.as_mut_ptr()) })?;
         let id = unsafe { std::ffi::CStr::from_ptr(out_id.as_ptr()) }
             .to_str()
             .unwrap_or("")
@@ -126,41 +130,47 @@ impl EPANET {
     }
 
     /// Sets the ID of a pattern given its 1-based index.
-    pub fn set_pattern_id(&self, index: i32, id: &str) -> Result<()> {
+    pub fn set_pattern_id(&self, index: impl Into<PatternIndex>, id: &str) -> Result<()> {
+        let index = index.into();
         let c_id = std::ffi::CString::new(id).unwrap();
-        check_error(unsafe { ffi::EN_setpatternid(self.ph, index, c_id.as_ptr()) })
+        check_error(unsafe { ffi::EN_setpatternid(self.ph, index.0, c_id.as_ptr()) })
     }
 
     /// Returns the number of time periods in a pattern.
-    pub fn get_pattern_length(&self, index: i32) -> Result<i32> {
+    pub fn get_pattern_length(&self, index: impl Into<PatternIndex>) -> Result<i32> {
+        let index = index.into();
         let mut out_length = 0;
-        check_error(unsafe { ffi::EN_getpatternlen(self.ph, index, &mut out_length) })?;
+        check_error(unsafe { ffi::EN_getpatternlen(self.ph, index.0, &mut out_length) })?;
         Ok(out_length)
     }
 
     /// Returns the multiplier for a specific time period (1-based) of a pattern.
-    pub fn get_pattern_value(&self, index: i32, period: i32) -> Result<f64> {
+    pub fn get_pattern_value(&self, index: impl Into<PatternIndex>, period: i32) -> Result<f64> {
+        let index = index.into();
         let mut out_value = 0.0;
-        check_error(unsafe { ffi::EN_getpatternvalue(self.ph, index, period, &mut out_value) })?;
+        check_error(unsafe { ffi::EN_getpatternvalue(self.ph, index.0, period, &mut out_value) })?;
         Ok(out_value)
     }
 
     /// Sets the multiplier for a specific time period (1-based) of a pattern.
-    pub fn set_pattern_value(&self, index: i32, period: i32, value: f64) -> Result<()> {
-        check_error(unsafe { ffi::EN_setpatternvalue(self.ph, index, period, value) })
+    pub fn set_pattern_value(&self, index: impl Into<PatternIndex>, period: i32, value: f64) -> Result<()> {
+        let index = index.into();
+        check_error(unsafe { ffi::EN_setpatternvalue(self.ph, index.0, period, value) })
     }
 
     /// Returns the average of all multipliers in a pattern.
-    pub fn get_average_pattern_value(&self, index: i32) -> Result<f64> {
+    pub fn get_average_pattern_value(&self, index: impl Into<PatternIndex>) -> Result<f64> {
+        let index = index.into();
         let mut out_value = 0.0;
-        check_error(unsafe { ffi::EN_getaveragepatternvalue(self.ph, index, &mut out_value) })?;
+        check_error(unsafe { ffi::EN_getaveragepatternvalue(self.ph, index.0, &mut out_value) })?;
         Ok(out_value)
     }
 
     /// Sets all multipliers for a pattern at once, replacing any existing values.
-    pub fn set_pattern(&self, index: i32, values: &[f64]) -> Result<()> {
+    pub fn set_pattern(&self, index: impl Into<PatternIndex>, values: &[f64]) -> Result<()> {
+        let index = index.into();
         let c_values = values.as_ptr() as *mut f64;
-        check_error(unsafe { ffi::EN_setpattern(self.ph, index, c_values, values.len() as i32) })
+        check_error(unsafe { ffi::EN_setpattern(self.ph, index.0, c_values, values.len() as i32) })
     }
 
     /// Loads a pattern from a file.
@@ -193,7 +203,7 @@ mod tests {
 
         assert_eq!(pattern.id, "TestPat");
         assert_eq!(pattern.multipliers, multipliers);
-        assert!(pattern.index() > 0);
+        assert!(pattern.index().0 > 0);
     }
 
     #[rstest]
@@ -292,4 +302,20 @@ mod tests {
         assert_eq!(pattern.multipliers, vec![1.5]);
         assert!(approx_eq(pattern.average().unwrap(), 1.5, 1e-6));
     }
+
+    #[rstest]
+    fn test_pattern_refresh(ph: EPANET) {
+        let mut pattern = ph.get_pattern_by_index(1).unwrap();
+        assert!(!pattern.is_stale().unwrap());
+
+        ph.set_pattern_value(pattern.index(), 1, 9.0).unwrap();
+        assert_eq!(pattern.diff_from_engine().unwrap(), vec!["multipliers"]);
+
+        pattern.refresh().unwrap();
+        assert_eq!(pattern.multipliers[0], 9.0);
+        assert!(!pattern.is_stale().unwrap());
+
+        ph.delete_pattern(pattern.index()).unwrap();
+        assert_eq!(pattern.refresh().unwrap_err().code, 205);
+    }
 }
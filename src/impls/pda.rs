@@ -0,0 +1,292 @@
+//! Pressure driven analysis API methods for EPANET.
+//!
+//! This module switches a project to pressure driven analysis and summarizes
+//! how much of the requested demand the network delivers, per time step and
+//! over a hydraulic run.
+
+use crate::epanet_error::*;
+use crate::types::demand::{DemandModel, DemandModelInfo};
+use crate::types::index::NodeIndex;
+use crate::types::node::{NodeProperty, NodeType};
+use crate::types::options::{AnalysisStatistic, TimeParameter};
+use crate::types::pda::*;
+use crate::EPANET;
+
+/// Relative shortfall below which a junction counts as fully served.
+const DEFICIT_TOLERANCE: f64 = 1e-6;
+
+/// ## Pressure Driven Analysis APIs
+impl EPANET {
+    /// Switches the project to pressure driven analysis.
+    ///
+    /// Junctions receive no demand below `pressure_min`, their full demand at or
+    /// above `pressure_required`, and a fraction
+    /// `((p - pressure_min) / (pressure_required - pressure_min))^(1 / pressure_exponent)`
+    /// in between.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 202 unless `0 <= pressure_min < pressure_required` and
+    /// `pressure_exponent > 0`, all finite.
+    pub fn set_pressure_driven(
+        &self,
+        pressure_min: f64,
+        pressure_required: f64,
+        pressure_exponent: f64,
+    ) -> Result<()> {
+        let valid = [pressure_min, pressure_required, pressure_exponent]
+            .iter()
+            .all(|v| v.is_finite())
+            && pressure_min >= 0.0
+            && pressure_required > pressure_min
+            && pressure_exponent > 0.0;
+        if !valid {
+            return Err(EPANETError::from(202).with_context(format!(
+                "invalid PDA parameters: minimum pressure {pressure_min}, required pressure \
+                 {pressure_required}, exponent {pressure_exponent}; expected \
+                 0 <= minimum < required and exponent > 0"
+            )));
+        }
+        self.set_demand_model(DemandModelInfo {
+            demand_type: DemandModel::Pda,
+            pressure_min,
+            pressure_required,
+            pressure_exponent,
+        })
+    }
+
+    /// Summarizes demand delivery for the current hydraulic solution, e.g.
+    /// after each `run_h` of a custom simulation loop.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if no hydraulic solution is available.
+    pub fn pda_step_summary(&self) -> Result<PdaStepSummary> {
+        let junctions = self.pda_junctions()?;
+        let time = self.get_time_parameter(TimeParameter::HTime)? as i64;
+        Ok(self.pda_step(time, &junctions)?.0)
+    }
+
+    /// Runs a hydraulic analysis and summarizes demand delivery at every step
+    /// and over the run.
+    ///
+    /// The demand model is used as configured; under demand driven analysis
+    /// every junction is fully served. Hydraulic warnings (e.g. negative
+    /// pressures) do not stop the run. A single-period run (zero duration)
+    /// requests no volume, but still reports its deficient junctions and their
+    /// service levels.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if the solver fails.
+    pub fn pda_summary(&self) -> Result<PdaSummary> {
+        let junctions = self.pda_junctions()?;
+        let seconds_per_unit = self.get_flow_units()?.seconds_per_unit();
+        let mut steps = Vec::new();
+        let mut flows = Vec::new();
+        self.run_hydraulics_with(|t| {
+            let (step, full, delivered) = self.pda_step(t, &junctions)?;
+            steps.push(step);
+            flows.push((full, delivered));
+            Ok(())
+        })?;
+
+        let mut nodes: Vec<NodeService> = junctions
+            .iter()
+            .map(|(node, id)| NodeService {
+                node: *node,
+                id: id.clone(),
+                requested_volume: 0.0,
+                delivered_volume: 0.0,
+                service_level: 0.0,
+                deficient_time: 0,
+                deficient_steps: 0,
+            })
+            .collect();
+        // A zero-duration run (a single snapshot) weights every step equally
+        let duration = steps.last().map_or(0, |last| last.time - steps[0].time);
+        let mut demand_time = vec![0; junctions.len()];
+        for (i, (full, delivered)) in flows.iter().enumerate() {
+            let dt = steps.get(i + 1).map_or(0, |next| next.time - steps[i].time);
+            let weight = if duration > 0 { dt } else { 1 };
+            for (j, node) in nodes.iter_mut().enumerate() {
+                node.requested_volume += full[j] * dt as f64 / seconds_per_unit;
+                node.delivered_volume += delivered[j] * dt as f64 / seconds_per_unit;
+                if full[j] > 0.0 {
+                    demand_time[j] += weight;
+                    node.service_level += 100.0 * delivered[j] / full[j] * weight as f64;
+                    if is_deficient(full[j], delivered[j]) {
+                        node.deficient_time += dt;
+                        node.deficient_steps += 1;
+                    }
+                }
+            }
+        }
+        for (node, time) in nodes.iter_mut().zip(demand_time) {
+            node.service_level = if time > 0 {
+                node.service_level / time as f64
+            } else {
+                100.0
+            };
+        }
+        Ok(PdaSummary {
+            requested_volume: nodes.iter().map(|n| n.requested_volume).sum(),
+            delivered_volume: nodes.iter().map(|n| n.delivered_volume).sum(),
+            steps,
+            nodes,
+        })
+    }
+
+    // Helper methods - Internal API
+
+    /// Index and ID of every junction.
+    fn pda_junctions(&self) -> Result<Vec<(NodeIndex, String)>> {
+        self.iter_nodes_of_type(NodeType::Junction)?
+            .map(|node| {
+                let node = node?;
+                Ok((node.index(), node.id()?.into()))
+            })
+            .collect()
+    }
+
+    /// Summary of the current solution, with the full and delivered demand of
+    /// each of `junctions`.
+    fn pda_step(
+        &self,
+        time: i64,
+        junctions: &[(NodeIndex, String)],
+    ) -> Result<(PdaStepSummary, Vec<f64>, Vec<f64>)> {
+        let all_full = self.get_node_values(NodeProperty::FullDemand)?;
+        let all_delivered = self.get_node_values(NodeProperty::DemandFlow)?;
+        let full: Vec<f64> = junctions
+            .iter()
+            .map(|(n, _)| all_full[n.offset()])
+            .collect();
+        let delivered: Vec<f64> = junctions
+            .iter()
+            .map(|(n, _)| all_delivered[n.offset()])
+            .collect();
+        let deficient_nodes = junctions
+            .iter()
+            .zip(full.iter().zip(&delivered))
+            .filter(|(_, (f, d))| is_deficient(**f, **d))
+            .map(|((node, id), (f, d))| DeficientNode {
+                node: *node,
+                id: id.clone(),
+                full_demand: *f,
+                delivered: *d,
+                percent_satisfied: 100.0 * d / f,
+            })
+            .collect();
+        let step = PdaStepSummary {
+            time,
+            deficient_nodes,
+            full_demand: full.iter().sum(),
+            delivered: delivered.iter().sum(),
+            engine_deficient_nodes: self.get_statistic(AnalysisStatistic::DeficientNodes)? as i32,
+            engine_demand_reduction: self.get_statistic(AnalysisStatistic::DemandReduction)?,
+        };
+        Ok((step, full, delivered))
+    }
+}
+
+/// `true` when a junction receives noticeably less than its positive demand.
+fn is_deficient(full: f64, delivered: f64) -> bool {
+    full > 0.0 && delivered < full * (1.0 - DEFICIT_TOLERANCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use rstest::rstest;
+
+    #[rstest]
+    fn test_set_pressure_driven(ph: EPANET) {
+        ph.set_pressure_driven(5.0, 40.0, 0.5).unwrap();
+        let model = ph.get_demand_model().unwrap();
+        assert_eq!(model.demand_type, DemandModel::Pda);
+        assert_eq!(
+            (
+                model.pressure_min,
+                model.pressure_required,
+                model.pressure_exponent
+            ),
+            (5.0, 40.0, 0.5)
+        );
+
+        for (min, required, exponent) in [(40.0, 40.0, 0.5), (-1.0, 40.0, 0.5), (5.0, 40.0, 0.0)] {
+            let err = ph.set_pressure_driven(min, required, exponent).unwrap_err();
+            assert_eq!(err.code, 202);
+        }
+        assert_eq!(ph.get_demand_model().unwrap().pressure_min, 5.0);
+    }
+
+    #[rstest]
+    fn test_pda_summary(ph: EPANET) {
+        // Demand driven: everyone is served
+        let summary = ph.pda_summary().unwrap();
+        // Hourly steps plus tank and control events over 24 hours
+        assert_eq!(summary.steps[0].time, 0);
+        assert_eq!(summary.steps.last().unwrap().time, 24 * 3600);
+        assert!(summary.steps.iter().all(|s| s.deficient_nodes.is_empty()));
+        assert!(summary.requested_volume > 0.0);
+        assert!(summary.unmet_volume().abs() < 1e-6 * summary.requested_volume);
+        assert!(summary
+            .nodes
+            .iter()
+            .all(|n| (n.service_level - 100.0).abs() < 1e-6));
+
+        // net1 pressures stay well below 200 psi, so every junction with a
+        // demand falls short
+        ph.set_pressure_driven(0.0, 200.0, 0.5).unwrap();
+        let summary = ph.pda_summary().unwrap();
+        assert_eq!(summary.nodes.len(), 9);
+        let first = &summary.steps[0];
+        assert_eq!(first.deficient_nodes.len(), 8);
+        assert_eq!(first.engine_deficient_nodes, 8);
+        assert!(first.engine_demand_reduction > 0.0);
+        let node = first.deficient_nodes.iter().find(|n| n.id == "11").unwrap();
+        assert!(node.percent_satisfied > 0.0 && node.percent_satisfied < 100.0);
+        assert!((first.unmet_demand() - (first.full_demand - first.delivered)).abs() < 1e-9);
+
+        assert!(summary.unmet_volume() > 0.0);
+        assert!(summary.percent_delivered() < 100.0);
+        assert_eq!(summary.deficient_nodes().count(), 8);
+        let junction_10 = summary.nodes.iter().find(|n| n.id == "10").unwrap();
+        assert_eq!(
+            (junction_10.requested_volume, junction_10.service_level),
+            (0.0, 100.0)
+        );
+        let total: f64 = summary.nodes.iter().map(|n| n.unmet_volume()).sum();
+        assert!((total - summary.unmet_volume()).abs() < 1e-6 * summary.requested_volume);
+
+        ph.open_h().unwrap();
+        ph.init_h(crate::types::analysis::InitHydOption::NoSave)
+            .unwrap();
+        ph.run_h().unwrap();
+        let step = ph.pda_step_summary().unwrap();
+        assert_eq!(step, summary.steps[0]);
+        ph.close_h().unwrap();
+    }
+
+    #[rstest]
+    fn test_pda_summary_single_period(ph: EPANET) {
+        ph.set_time_parameter(TimeParameter::Duration, 0).unwrap();
+        ph.set_pressure_driven(0.0, 200.0, 0.5).unwrap();
+        let summary = ph.pda_summary().unwrap();
+        assert_eq!(summary.steps.len(), 1);
+        let step = &summary.steps[0];
+        assert_eq!(step.deficient_nodes.len(), 8);
+        assert_eq!(summary.deficient_nodes().count(), 8);
+
+        let shortfall = &step.deficient_nodes[0];
+        let node = summary
+            .nodes
+            .iter()
+            .find(|n| n.node == shortfall.node)
+            .unwrap();
+        assert_eq!((node.deficient_steps, node.deficient_time), (1, 0));
+        assert!((node.service_level - shortfall.percent_satisfied).abs() < 1e-9);
+    }
+}
@@ -12,7 +12,8 @@ use std::mem::MaybeUninit;
 
 /// ## Project APIs
 impl EPANET {
-    pub fn get_comment(&self, object_type: ObjectType, index: i32) -> Result<String> {
+    pub fn get_comment(&self, object_type: ObjectType, index: impl Into<i32>) -> Result<String> {
+        let index = index.into();
         let mut out_comment: Vec<c_char> = vec![0; MAX_MSG_SIZE as usize + 1usize];
         check_error(unsafe {
             ffi::EN_getcomment(self.ph, object_type as i32, index, out_comment.as_mut_ptr())
@@ -25,7 +26,8 @@ impl EPANET {
         Ok(comment)
     }
 
-    pub fn set_comment(&self, object_type: ObjectType, index: i32, comment: &str) -> Result<()> {
+    pub fn set_comment(&self, object_type: ObjectType, index: impl Into<i32>, comment: &str) -> Result<()> {
+        let index = index.into();
         let _comment = CString::new(comment)?;
         check_error(unsafe { ffi::EN_setcomment(self.ph, object_type as i32, index, _comment.as_ptr()) })
     }
@@ -73,7 +75,8 @@ impl EPANET {
         Ok(title)
     }
 
-    pub fn get_tag(&self, object_type: ObjectType, index: i32) -> Result<String> {
+    pub fn get_tag(&self, object_type: ObjectType, index: impl Into<i32>) -> Result<String> {
+        let index = index.into();
         let mut out_tag: Vec<c_char> = vec![0; MAX_MSG_SIZE as usize + 1usize];
         check_error(unsafe { ffi::EN_gettag(self.ph, object_type as i32, index, out_tag.as_mut_ptr()) })?;
         let tag = unsafe { CStr::from_ptr(out_tag.as_ptr()) }
@@ -84,7 +87,8 @@ impl EPANET {
         Ok(tag)
     }
 
-    pub fn set_tag(&self, object_type: ObjectType, index: i32, tag: &str) -> Result<()> {
+    pub fn set_tag(&self, object_type: ObjectType, index: impl Into<i32>, tag: &str) -> Result<()> {
+        let index = index.into();
         let _tag = CString::new(tag)?;
         check_error(unsafe { ffi::EN_settag(self.ph, object_type as i32, index, _tag.as_ptr()) })
     }
@@ -266,7 +266,7 @@ mod tests {
             let t = t_result.unwrap();
             let t_step = t_step_result.unwrap();
             println!("Time: {}s, TStep: {}s", t, t_step);
-            if t_step <= 0 || t >= t_stop {
+            if t_step == 0 || t >= t_stop {
                 break;
             }
         }
@@ -352,7 +352,7 @@ mod tests {
                 tstep_q
             );
 
-            if tstep_h.unwrap() <= 0 {
+            if tstep_h.unwrap() == 0 {
                 break;
             }
         }
@@ -0,0 +1,480 @@
+//! Network map rendering API methods for EPANET.
+//!
+//! This module contains a renderer that writes the network as a standalone SVG
+//! document, with nodes and links optionally coloured by result values.
+
+use crate::epanet_error::*;
+use crate::types::export::{property_name, save_error};
+use crate::types::link::LinkKind;
+use crate::types::node::NodeKind;
+use crate::types::render::*;
+use crate::EPANET;
+use std::fmt::Debug;
+use std::io::Write;
+
+const NODE_COLOR: &str = "#333333";
+const LINK_COLOR: &str = "#777777";
+
+/// ## Rendering APIs
+impl EPANET {
+    /// Draws the network as a standalone SVG document written to `out` and returns
+    /// the sink.
+    ///
+    /// The map is scaled to fit [`SvgOptions::width`] by [`SvgOptions::height`]
+    /// with north up. Junctions are drawn as circles, tanks as squares and
+    /// reservoirs as triangles; pumps are marked with a circle and valves with a
+    /// bow tie at the middle of the link. Every element carries a `class` (e.g.
+    /// `junction`, `pipe`, `pump`) and a `<title>` tooltip with its ID and value.
+    /// Elements without coordinates are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 254 if no node has coordinates, 250 if a legend is malformed
+    /// or a [`Coloring::Values`] list does not have one value per element, or error
+    /// 308 if the sink fails.
+    pub fn render_svg<W: Write>(&self, mut out: W, options: &SvgOptions) -> Result<W> {
+        let nodes = self.nodes()?;
+        let links = self.links()?;
+        let positions: Vec<_> = nodes
+            .iter()
+            .map(|node| self.get_coordinates(node.index()).ok())
+            .collect();
+        let mut paths = Vec::with_capacity(links.len());
+        for link in &links {
+            let ends = (
+                positions[link.from_node.offset()],
+                positions[link.to_node.offset()],
+            );
+            paths.push(match ends {
+                (Some(start), Some(end)) => {
+                    let mut path = vec![start];
+                    path.extend(link.vertices()?);
+                    path.push(end);
+                    Some(path)
+                }
+                _ => None,
+            });
+        }
+
+        let points = positions
+            .iter()
+            .flatten()
+            .chain(paths.iter().flatten().flatten());
+        let (mut min, mut max) = (
+            (f64::INFINITY, f64::INFINITY),
+            (f64::NEG_INFINITY, f64::NEG_INFINITY),
+        );
+        for &(x, y) in points {
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+        if !min.0.is_finite() {
+            return Err(EPANETError::from(254).with_context("no node has coordinates"));
+        }
+        let (width, height) = (options.width, options.height);
+        let available = (width - 2.0 * options.margin, height - 2.0 * options.margin);
+        let span = (max.0 - min.0, max.1 - min.1);
+        let scale = match (span.0 > 0.0, span.1 > 0.0) {
+            (true, true) => (available.0 / span.0).min(available.1 / span.1),
+            (true, false) => available.0 / span.0,
+            (false, true) => available.1 / span.1,
+            (false, false) => 1.0,
+        };
+        let offset = (
+            options.margin + (available.0 - span.0 * scale) / 2.0,
+            options.margin + (available.1 - span.1 * scale) / 2.0,
+        );
+        let project = |(x, y): (f64, f64)| {
+            (
+                offset.0 + (x - min.0) * scale,
+                height - offset.1 - (y - min.1) * scale,
+            )
+        };
+
+        let node_values =
+            coloring_values(&options.node_coloring, nodes.len(), |index, property| {
+                self.get_node_value(index, property)
+            })?;
+        let link_values =
+            coloring_values(&options.link_coloring, links.len(), |index, property| {
+                self.get_link_value(index, property)
+            })?;
+        let node_legend = node_values
+            .as_ref()
+            .map(|(title, values)| resolve_legend(&options.node_legend, values, title))
+            .transpose()?;
+        let link_legend = link_values
+            .as_ref()
+            .map(|(title, values)| resolve_legend(&options.link_legend, values, title))
+            .transpose()?;
+        let color = |legend: &Option<Legend>,
+                     values: &Option<(String, Vec<f64>)>,
+                     i: usize,
+                     neutral: &'static str| {
+            match (legend, values) {
+                (Some(legend), Some((_, values))) => {
+                    xml(legend.color(values[i]).unwrap_or(neutral))
+                }
+                _ => neutral.to_string(),
+            }
+        };
+        let tooltip =
+            |kind: String, id: &str, values: &Option<(String, Vec<f64>)>, i: usize| match values {
+                Some((_, values)) => format!("{kind} {}: {}", xml(id), number(values[i])),
+                None => format!("{kind} {}", xml(id)),
+            };
+
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#).map_err(save_error)?;
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif">"#
+        ).map_err(save_error)?;
+        writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#).map_err(save_error)?;
+        if let Some(title) = &options.title {
+            writeln!(
+                out,
+                r#"<text x="{:.1}" y="{:.1}" font-size="16">{}</text>"#,
+                options.margin / 2.0,
+                options.margin / 2.0 + 8.0,
+                xml(title)
+            )
+            .map_err(save_error)?;
+        }
+
+        let size = options.node_size;
+        writeln!(
+            out,
+            r#"<g id="links" fill="none" stroke-linecap="round" stroke-linejoin="round">"#
+        )
+        .map_err(save_error)?;
+        for (i, (link, path)) in links.iter().zip(&paths).enumerate() {
+            let Some(path) = path else { continue };
+            let stroke = color(&link_legend, &link_values, i, LINK_COLOR);
+            let kind = self.get_link_type(link.index())?;
+            let class = match link.kind {
+                LinkKind::Pipe(_) | LinkKind::CvPipe(_) => "pipe",
+                LinkKind::Pump(_) => "pump",
+                LinkKind::Valve(_) => "valve",
+            };
+            let points: Vec<String> = path
+                .iter()
+                .map(|p| {
+                    let (x, y) = project(*p);
+                    format!("{x:.1},{y:.1}")
+                })
+                .collect();
+            writeln!(
+                out,
+                r#"<polyline class="{class}" points="{}" stroke="{stroke}" stroke-width="{}"><title>{}</title></polyline>"#,
+                points.join(" "),
+                options.link_width,
+                tooltip(format!("{kind:?}"), &link.id, &link_values, i)
+            ).map_err(save_error)?;
+
+            if class == "pipe" {
+                continue;
+            }
+            let (mid, direction) = midpoint(&path.iter().map(|p| project(*p)).collect::<Vec<_>>());
+            let normal = (-direction.1, direction.0);
+            let at = |along: f64, across: f64| {
+                format!(
+                    "{:.1},{:.1}",
+                    mid.0 + direction.0 * along + normal.0 * across,
+                    mid.1 + direction.1 * along + normal.1 * across
+                )
+            };
+            let d = 2.0 * size;
+            if class == "pump" {
+                writeln!(
+                    out,
+                    r#"<g class="pump-symbol"><circle cx="{:.1}" cy="{:.1}" r="{d:.1}" fill="white" stroke="{stroke}" stroke-width="1.5"/><polygon points="{} {} {}" fill="{stroke}"/></g>"#,
+                    mid.0,
+                    mid.1,
+                    at(-d / 2.0, d / 2.0),
+                    at(-d / 2.0, -d / 2.0),
+                    at(d / 2.0, 0.0)
+                ).map_err(save_error)?;
+            } else {
+                writeln!(
+                    out,
+                    r#"<polygon class="valve-symbol" points="{} {} {} {}" fill="{stroke}" stroke="{stroke}"/>"#,
+                    at(-d, d),
+                    at(-d, -d),
+                    at(d, d),
+                    at(d, -d)
+                ).map_err(save_error)?;
+            }
+        }
+        writeln!(out, "</g>").map_err(save_error)?;
+
+        writeln!(out, r#"<g id="nodes">"#).map_err(save_error)?;
+        for (i, (node, position)) in nodes.iter().zip(&positions).enumerate() {
+            let Some(position) = position else { continue };
+            let (x, y) = project(*position);
+            let fill = color(&node_legend, &node_values, i, NODE_COLOR);
+            let title = tooltip(format!("{:?}", node.node_type()), &node.id, &node_values, i);
+            match node.kind {
+                NodeKind::Junction(_) => writeln!(
+                    out,
+                    r#"<circle class="junction" cx="{x:.1}" cy="{y:.1}" r="{size:.1}" fill="{fill}"><title>{title}</title></circle>"#
+                ).map_err(save_error)?,
+                NodeKind::Tank(_) => {
+                    let half = 2.0 * size;
+                    writeln!(
+                        out,
+                        r#"<rect class="tank" x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{fill}" stroke="black"><title>{title}</title></rect>"#,
+                        x - half,
+                        y - half,
+                        2.0 * half,
+                        2.0 * half
+                    ).map_err(save_error)?
+                }
+                NodeKind::Reservoir(_) => {
+                    let r = 2.5 * size;
+                    writeln!(
+                        out,
+                        r#"<polygon class="reservoir" points="{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}" fill="{fill}" stroke="black"><title>{title}</title></polygon>"#,
+                        x,
+                        y - r,
+                        x - r,
+                        y + r * 0.8,
+                        x + r,
+                        y + r * 0.8
+                    ).map_err(save_error)?
+                }
+            }
+        }
+        writeln!(out, "</g>").map_err(save_error)?;
+
+        if options.show_legend {
+            let mut top = options.margin / 2.0;
+            for (id, legend) in [("node-legend", &node_legend), ("link-legend", &link_legend)] {
+                let Some(legend) = legend else { continue };
+                top = write_legend(&mut out, id, legend, width - 150.0, top)? + 10.0;
+            }
+        }
+        writeln!(out, "</svg>").map_err(save_error)?;
+        out.flush().map_err(save_error)?;
+        Ok(out)
+    }
+}
+
+/// Legend title and one value per element, or `None` when not coloured.
+fn coloring_values<P: Copy + Debug>(
+    coloring: &Coloring<P>,
+    count: usize,
+    value: impl Fn(i32, P) -> Result<f64>,
+) -> Result<Option<(String, Vec<f64>)>> {
+    match coloring {
+        Coloring::None => Ok(None),
+        Coloring::Property(property) => {
+            let values = (1..=count as i32)
+                .map(|index| value(index, *property))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Some((property_name(property), values)))
+        }
+        Coloring::Values(values) if values.len() == count => {
+            Ok(Some(("value".to_string(), values.clone())))
+        }
+        Coloring::Values(values) => Err(EPANETError::from(250).with_context(format!(
+            "{} colouring values for {count} elements",
+            values.len()
+        ))),
+    }
+}
+
+/// The configured legend, or equal intervals over the finite `values`, titled.
+fn resolve_legend(legend: &Option<Legend>, values: &[f64], title: &str) -> Result<Legend> {
+    let mut legend = match legend {
+        Some(legend) => legend.clone(),
+        None => {
+            let finite = values.iter().copied().filter(|v| v.is_finite());
+            let (min, max) = finite.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+                (lo.min(v), hi.max(v))
+            });
+            if min.is_finite() {
+                Legend::equal_intervals(min, max, &Legend::DEFAULT_COLORS)
+            } else {
+                Legend::equal_intervals(0.0, 0.0, &Legend::DEFAULT_COLORS[..1])
+            }
+        }
+    };
+    legend.validate()?;
+    legend.title.get_or_insert_with(|| title.to_string());
+    Ok(legend)
+}
+
+/// Writes a legend box with its top-left corner at `(x, top)` and returns its
+/// bottom.
+fn write_legend<W: Write>(out: &mut W, id: &str, legend: &Legend, x: f64, top: f64) -> Result<f64> {
+    let row = 16.0;
+    writeln!(out, r#"<g id="{id}" font-size="11">"#).map_err(save_error)?;
+    writeln!(
+        out,
+        r#"<text x="{x:.1}" y="{:.1}" font-weight="bold">{}</text>"#,
+        top + 11.0,
+        xml(legend.title.as_deref().unwrap_or_default())
+    )
+    .map_err(save_error)?;
+    let breaks = &legend.breaks;
+    for (i, color) in legend.colors.iter().enumerate() {
+        let label = match (i, breaks.len()) {
+            (_, 0) => "all".to_string(),
+            (0, _) => format!("&lt; {}", number(breaks[0])),
+            (i, n) if i == n => format!("&gt;= {}", number(breaks[n - 1])),
+            (i, _) => format!("{} - {}", number(breaks[i - 1]), number(breaks[i])),
+        };
+        let y = top + row * (i + 1) as f64;
+        writeln!(
+            out,
+            r#"<rect x="{x:.1}" y="{:.1}" width="12" height="12" fill="{}"/><text x="{:.1}" y="{:.1}">{label}</text>"#,
+            y + 2.0,
+            xml(color),
+            x + 18.0,
+            y + 12.0
+        ).map_err(save_error)?;
+    }
+    writeln!(out, "</g>").map_err(save_error)?;
+    Ok(top + row * (legend.colors.len() + 1) as f64)
+}
+
+/// Point halfway along `path` and the unit direction of the segment it lies on.
+fn midpoint(path: &[(f64, f64)]) -> ((f64, f64), (f64, f64)) {
+    let lengths: Vec<f64> = path
+        .windows(2)
+        .map(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1))
+        .collect();
+    let mut remaining = lengths.iter().sum::<f64>() / 2.0;
+    for (w, length) in path.windows(2).zip(&lengths) {
+        if remaining <= *length && *length > 0.0 {
+            let t = remaining / length;
+            let direction = ((w[1].0 - w[0].0) / length, (w[1].1 - w[0].1) / length);
+            return (
+                (
+                    w[0].0 + (w[1].0 - w[0].0) * t,
+                    w[0].1 + (w[1].1 - w[0].1) * t,
+                ),
+                direction,
+            );
+        }
+        remaining -= length;
+    }
+    (path[0], (1.0, 0.0))
+}
+
+fn number(value: f64) -> String {
+    format!("{value:.2}")
+}
+
+fn xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use crate::types::link::LinkProperty;
+    use crate::types::node::NodeProperty;
+    use rstest::rstest;
+
+    fn render(ph: &EPANET, options: &SvgOptions) -> String {
+        String::from_utf8(ph.render_svg(Vec::new(), options).unwrap()).unwrap()
+    }
+
+    #[rstest]
+    fn test_render_svg_symbols(ph: EPANET) {
+        let svg = render(&ph, &SvgOptions::default());
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches(r#"class="junction""#).count(), 9);
+        assert_eq!(svg.matches(r#"class="tank""#).count(), 1);
+        assert_eq!(svg.matches(r#"class="reservoir""#).count(), 1);
+        assert_eq!(svg.matches(r#"class="pipe""#).count(), 12);
+        assert_eq!(svg.matches(r#"class="pump-symbol""#).count(), 1);
+        assert!(svg.contains("<title>Junction 10</title>"));
+        assert!(!svg.contains("node-legend"));
+    }
+
+    #[rstest]
+    fn test_render_svg_coloring(ph: EPANET) {
+        ph.solve_h().unwrap();
+        let options = SvgOptions {
+            title: Some("Net1 & pressures".to_string()),
+            node_coloring: Coloring::Property(NodeProperty::Pressure),
+            link_coloring: Coloring::Property(LinkProperty::Velocity),
+            link_legend: Some(Legend {
+                breaks: vec![0.5, 1.5],
+                colors: vec!["blue".into(), "green".into(), "red".into()],
+                title: None,
+            }),
+            ..SvgOptions::default()
+        };
+        let svg = render(&ph, &options);
+        assert!(svg.contains("Net1 &amp; pressures"));
+        assert!(svg.contains(r#"<g id="node-legend""#));
+        assert!(svg.contains(">pressure</text>"));
+        assert!(svg.contains(">velocity</text>"));
+        assert!(
+            svg.contains("&lt; 0.50") && svg.contains("0.50 - 1.50") && svg.contains("&gt;= 1.50")
+        );
+        assert!(svg.contains(r##"fill="#d7191c""##));
+        assert!(svg.contains(r#"stroke="red""#) || svg.contains(r#"stroke="green""#));
+    }
+
+    #[rstest]
+    fn test_render_svg_escapes_colors(ph: EPANET) {
+        let options = SvgOptions {
+            node_coloring: Coloring::Values(vec![0.0; 11]),
+            node_legend: Some(Legend {
+                breaks: Vec::new(),
+                colors: vec![r#"red" onload="x<"#.into()],
+                title: None,
+            }),
+            ..SvgOptions::default()
+        };
+        let svg = render(&ph, &options);
+        assert!(!svg.contains(r#"red" onload"#));
+        // Every node and the legend swatch
+        assert_eq!(
+            svg.matches(r#"fill="red&quot; onload=&quot;x&lt;""#)
+                .count(),
+            12
+        );
+    }
+
+    #[rstest]
+    fn test_render_svg_errors(ph: EPANET, ph_close: EPANET) {
+        let wrong_length = SvgOptions {
+            node_coloring: Coloring::Values(vec![1.0, 2.0]),
+            ..SvgOptions::default()
+        };
+        assert_eq!(
+            ph.render_svg(Vec::new(), &wrong_length).unwrap_err().code,
+            250
+        );
+        let bad_legend = SvgOptions {
+            node_coloring: Coloring::Values(vec![0.0; 11]),
+            node_legend: Some(Legend {
+                breaks: vec![2.0, 1.0],
+                colors: vec!["a".into(), "b".into(), "c".into()],
+                title: None,
+            }),
+            ..SvgOptions::default()
+        };
+        assert_eq!(
+            ph.render_svg(Vec::new(), &bad_legend).unwrap_err().code,
+            250
+        );
+        assert_eq!(
+            ph_close
+                .render_svg(Vec::new(), &SvgOptions::default())
+                .unwrap_err()
+                .code,
+            254
+        );
+    }
+}
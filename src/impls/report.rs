@@ -142,7 +142,8 @@ impl EPANET {
     ///
     /// # See Also
     /// - EN_getresultindex (EPANET C API)
-    pub fn get_result_index(&self, object_type: ObjectType, object_index: i32) -> Result<i32> {
+    pub fn get_result_index(&self, object_type: ObjectType, object_index: impl Into<i32>) -> Result<i32> {
+        let object_index = object_index.into();
         let mut index: i32 = -1;
         check_error(unsafe {
             ffi::EN_getresultindex(self.ph, object_type as i32, object_index, &mut index)
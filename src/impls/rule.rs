@@ -4,8 +4,9 @@
 
 use crate::bindings as ffi;
 use crate::epanet_error::*;
+use crate::types::index::{LinkIndex, RuleIndex};
 use crate::types::rule::*;
-use crate::types::MAX_ID_SIZE;
+use crate::types::{CountType, MAX_ID_SIZE};
 use crate::EPANET;
 use num_traits::FromPrimitive;
 use std::ffi::c_char;
@@ -17,11 +18,12 @@ impl EPANET {
         check_error(unsafe { ffi::EN_addrule(self.ph, c_rule.as_ptr() as *mut std::os::raw::c_char) })
     }
 
-    pub fn delete_rule(&self, index: i32) -> Result<()> {
-        check_error(unsafe { ffi::EN_deleterule(self.ph, index) })
+    pub fn delete_rule(&self, index: impl Into<RuleIndex>) -> Result<()> {
+        check_error(unsafe { ffi::EN_deleterule(self.ph, index.into().0) })
     }
 
-    pub fn get_rule(&self, index: i32) -> Result<Rule<'_>> {
+    pub fn get_rule(&self, index: impl Into<RuleIndex>) -> Result<Rule<'_>> {
+        let index = index.into();
         let rule_id = self.get_rule_id(index)?;
 
         let mut out_premise_count = 0;
@@ -31,7 +33,7 @@ impl EPANET {
         check_error(unsafe {
             ffi::EN_getrule(
                 self.ph,
-                index,
+                index.0,
                 &mut out_premise_count,
                 &mut out_then_action_count,
                 &mut out_else_action_count,
@@ -41,20 +43,20 @@ impl EPANET {
 
         let mut premises = Vec::new();
         for i in 1..=out_premise_count {
-            premises.push(self.get_premise(index, i)?);
+            premises.push(self.get_premise(index.0, i)?);
         }
 
         let mut then_actions = Vec::new();
         for i in 1..=out_then_action_count {
-            then_actions.push(self.get_then_action(index, i)?);
+            then_actions.push(self.get_then_action(index.0, i)?);
         }
 
         let mut else_actions = Vec::new();
         for i in 1..=out_else_action_count {
-            else_actions.push(self.get_else_action(index, i)?);
+            else_actions.push(self.get_else_action(index.0, i)?);
         }
 
-        let enabled = self.get_rule_enabled(index)?;
+        let enabled = self.get_rule_enabled(index.0)?;
 
         Ok(Rule {
             project: self,
@@ -68,15 +70,32 @@ impl EPANET {
         })
     }
 
-    fn get_rule_id(&self, rule_index: i32) -> Result<String> {
+    pub fn get_rule_id(&self, rule_index: impl Into<RuleIndex>) -> Result<String> {
         let mut out_rule_id: Vec<c_char> = vec![0; MAX_ID_SIZE as usize + 1usize];
-        check_error(unsafe { ffi::EN_getruleID(self.ph, rule_index, out_rule_id.as_mut_ptr()) })?;
+        check_error(unsafe {
+            ffi::EN_getruleID(self.ph, rule_index.into().0, out_rule_id.as_mut_ptr())
+        })?;
         let id = unsafe { std::ffi::CStr::from_ptr(out_rule_id.as_ptr()) }
             .to_string_lossy()
             .trim_end()
             .to_string();
         Ok(id)
     }
+
+    /// Returns the index of the rule with the given ID (label).
+    ///
+    /// # Errors
+    ///
+    /// Returns error 257 if there is no such rule.
+    pub fn get_rule_index(&self, id: &str) -> Result<RuleIndex> {
+        for index in 1..=self.get_count(CountType::RuleCount)? {
+            if self.get_rule_id(index)? == id {
+                return Ok(RuleIndex(index));
+            }
+        }
+        Err(EPANETError::from(257).with_context(format!("Rule {id} does not exist")))
+    }
+
     fn get_premise(&self, rule_index: i32, premise_index: i32) -> Result<Premise> {
         let mut out_logop = 0;
         let mut out_object = 0;
@@ -104,7 +123,7 @@ impl EPANET {
         let logical_operator =
             LogicalOperator::from_i32(out_logop).expect("Invalid logical operator");
         let rule_object = RuleObject::from_i32(out_object).expect("Invalid rule object");
-        let object_index = out_obj_index;
+        let object = PremiseObject::new(rule_object, out_obj_index);
         let variable = RuleVariable::from_i32(out_variable).expect("Invalid rule variable");
         let rule_operator = RuleOperator::from_i32(out_relop).expect("Invalid rule operator");
         let status: Option<RuleStatus> = RuleStatus::from_i32(out_status).or(None);
@@ -112,8 +131,7 @@ impl EPANET {
 
         Ok(Premise {
             logical_operator,
-            rule_object,
-            object_index,
+            object,
             variable,
             rule_operator,
             status,
@@ -136,7 +154,7 @@ impl EPANET {
             )
         })?;
         Ok(ActionClause {
-            link_index: out_link_index,
+            link_index: LinkIndex(out_link_index),
             status: RuleStatus::from_i32(out_status).expect("Invalid rule status"),
             setting: out_setting,
         })
@@ -157,7 +175,7 @@ impl EPANET {
             )
         })?;
         Ok(ActionClause {
-            link_index: out_link_index,
+            link_index: LinkIndex(out_link_index),
             status: RuleStatus::from_i32(out_status).expect("Invalid rule status"),
             setting: out_setting,
         })
@@ -279,7 +297,7 @@ impl EPANET {
         check_error(unsafe { ffi::EN_setrulepriority(self.ph, rule_index, priority) })
     }
 
-    fn set_rule_enabled(&self, rule_index: i32, enabled: bool) -> Result<()> {
+    pub(crate) fn set_rule_enabled(&self, rule_index: i32, enabled: bool) -> Result<()> {
         check_error(unsafe { ffi::EN_setruleenabled(self.ph, rule_index, enabled as i32) })
     }
 
@@ -287,11 +305,11 @@ impl EPANET {
     pub(crate) fn update_rule(&self, rule: &Rule) -> Result<()> {
         for (i, premise) in rule.premises.iter().enumerate() {
             self.set_premise(
-                rule.index,
+                rule.index.0,
                 (i + 1) as i32,
                 premise.logical_operator as i32,
-                premise.rule_object as i32,
-                premise.object_index,
+                premise.object.rule_object() as i32,
+                premise.object.index(),
                 premise.variable as i32,
                 premise.rule_operator as i32,
                 premise.status.map_or(0, |s| s as i32),
@@ -300,24 +318,24 @@ impl EPANET {
         }
         for (i, action) in rule.then_actions.iter().enumerate() {
             self.set_then_action(
-                rule.index,
+                rule.index.0,
                 (i + 1) as i32,
-                action.link_index,
+                action.link_index.0,
                 action.status as i32,
                 action.setting,
             )?;
         }
         for (i, action) in rule.else_actions.iter().enumerate() {
             self.set_else_action(
-                rule.index,
+                rule.index.0,
                 (i + 1) as i32,
-                action.link_index,
+                action.link_index.0,
                 action.status as i32,
                 action.setting,
             )?;
         }
-        self.set_rule_priority(rule.index, rule.priority)?;
-        self.set_rule_enabled(rule.index, rule.enabled)
+        self.set_rule_priority(rule.index.0, rule.priority)?;
+        self.set_rule_enabled(rule.index.0, rule.enabled)
     }
 }
 
@@ -386,7 +404,7 @@ mod tests {
 
         // Check that the index of pump9 has been reduced by 2
         let pump9_after = ph.get_link_index("9").unwrap();
-        assert_eq!(pump9_before - pump9_after, 2);
+        assert_eq!(pump9_before.0 - pump9_after.0, 2);
     }
 
     #[rstest]
@@ -450,4 +468,23 @@ mod tests {
 
         assert_eq!(ph.get_count(RuleCount).unwrap(), 1);
     }
+
+    #[rstest]
+    fn test_rule_refresh(ph: EPANET) {
+        ph.add_rule(R2).unwrap();
+        let mut rule = ph.get_rule(1).unwrap();
+        assert!(!rule.is_stale().unwrap());
+
+        // Edit through a second copy of the same rule
+        let mut other = ph.get_rule(1).unwrap();
+        other.priority = 5.0;
+        other.then_actions[0].status = RuleStatus::IsOpen;
+        other.update().unwrap();
+        assert_eq!(rule.diff_from_engine().unwrap(), vec!["then_actions", "priority"]);
+
+        rule.refresh().unwrap();
+        assert_eq!(rule.priority, 5.0);
+        assert_eq!(rule.then_actions[0].status, RuleStatus::IsOpen);
+        assert!(!rule.is_stale().unwrap());
+    }
 }
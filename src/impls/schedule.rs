@@ -0,0 +1,687 @@
+//! Pump scheduling API methods for EPANET.
+//!
+//! This module contains methods for evaluating pump operating schedules over an
+//! extended period simulation, searching for a minimum energy cost schedule that
+//! satisfies pressure and tank level constraints, and writing a schedule back to
+//! the project as speed patterns or timer controls.
+
+use crate::epanet_error::*;
+use crate::types::control::ControlType;
+use crate::types::index::{LinkIndex, NodeIndex, PatternIndex};
+use crate::types::link::{LinkProperty, LinkType};
+use crate::types::node::{NodeProperty, NodeType};
+use crate::types::options::{Option, TimeParameter};
+use crate::types::schedule::*;
+use crate::types::CountType;
+use crate::types::MAX_ID_SIZE;
+use crate::EPANET;
+
+/// ## Pump Scheduling APIs
+impl EPANET {
+    /// Simulates the project with the given pump schedule and reports its energy
+    /// cost and constraint violations.
+    ///
+    /// The schedule is applied through temporary speed patterns, and simple controls
+    /// and rules acting on the scheduled pumps are disabled for the run. The project
+    /// is restored to its previous state before returning.
+    ///
+    /// Energy is priced with each pump's energy price (or the global price when the
+    /// pump has none) multiplied by the pump's price pattern (or the global price
+    /// pattern), as in EPANET's own energy report.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if the schedule does not match the options
+    /// (period, number of periods, pump indices) or if the simulation fails.
+    pub fn evaluate_pump_schedule(
+        &self,
+        schedule: &PumpSchedule,
+        options: &PumpScheduleOptions,
+    ) -> Result<ScheduleEvaluation> {
+        let pumps: Vec<LinkIndex> = schedule.pumps.iter().map(|p| p.link_index).collect();
+        let options = PumpScheduleOptions {
+            pumps,
+            period: schedule.period,
+            ..options.clone()
+        };
+        let run = ScheduleRun::setup(self, &options)?;
+        let result = run.evaluate(self, schedule, &options);
+        run.restore(self)?;
+        result
+    }
+
+    /// Searches for a pump schedule that minimizes energy cost while keeping the
+    /// monitored pressures above [`PumpScheduleOptions::min_pressure`] and returning
+    /// every tank to its initial level.
+    ///
+    /// The search starts with every pump at its highest allowed setting and repeatedly
+    /// lowers or raises single pump-period settings, most expensive tariff periods
+    /// first, keeping any change that reduces the penalized cost
+    /// (see [`ScheduleEvaluation::objective`]). It stops when no single change improves
+    /// the schedule or when [`PumpScheduleOptions::max_evaluations`] is reached. The
+    /// result is a local optimum; check [`ScheduleEvaluation::feasible`] before use.
+    ///
+    /// The project is left unchanged. Use [`apply_pump_schedule`](Self::apply_pump_schedule)
+    /// to write the result back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if the options are invalid or a simulation fails.
+    pub fn optimize_pump_schedule(
+        &self,
+        options: &PumpScheduleOptions,
+    ) -> Result<PumpScheduleResult> {
+        let run = ScheduleRun::setup(self, options)?;
+        let result = run.optimize(self, options);
+        run.restore(self)?;
+        result
+    }
+
+    /// Writes a pump schedule back to the project.
+    ///
+    /// With [`ScheduleOutput::Patterns`] each pump is assigned a speed pattern named
+    /// `SCHED_<pump id>` (an existing pattern with that name is overwritten). With
+    /// [`ScheduleOutput::Controls`] a timer control is added at the start of the
+    /// simulation and at every period where the pump setting changes, and any speed
+    /// pattern on the pump is removed. In both cases existing simple controls and
+    /// rules acting on the scheduled pumps are disabled so they do not override
+    /// the schedule.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if a pump index is invalid, the period is not a
+    /// multiple of the pattern time step, or a pattern or control cannot be created.
+    pub fn apply_pump_schedule(
+        &self,
+        schedule: &PumpSchedule,
+        output: ScheduleOutput,
+    ) -> Result<()> {
+        let pumps: Vec<LinkIndex> = schedule.pumps.iter().map(|p| p.link_index).collect();
+        self.validate_schedule_pumps(&pumps)?;
+        let timing = ScheduleTiming::new(self, schedule.period)?;
+        timing.check(schedule)?;
+
+        for (control, _) in self.pump_controls(&pumps)? {
+            self.set_control_enabled(control, false)?;
+        }
+        for (rule, _) in self.pump_rules(&pumps)? {
+            self.set_rule_enabled(rule, false)?;
+        }
+
+        for timetable in &schedule.pumps {
+            match output {
+                ScheduleOutput::Patterns => {
+                    let link_id = self.get_link_id(timetable.link_index)?;
+                    let id: String = format!("SCHED_{link_id}")
+                        .chars()
+                        .take(MAX_ID_SIZE as usize)
+                        .collect();
+                    let pattern_index = match self.get_pattern_index(&id) {
+                        Ok(index) => index,
+                        Err(_) => {
+                            self.add_pattern(&id)?;
+                            self.get_pattern_index(&id)?
+                        }
+                    };
+                    self.set_pattern(pattern_index, &timing.expand(&timetable.settings))?;
+                    self.set_link_value(
+                        timetable.link_index,
+                        LinkProperty::LinkPattern,
+                        pattern_index.0 as f64,
+                    )?;
+                }
+                ScheduleOutput::Controls => {
+                    self.set_link_value(timetable.link_index, LinkProperty::LinkPattern, 0.0)?;
+                    let mut previous = None;
+                    for (k, &setting) in timetable.settings.iter().enumerate() {
+                        if previous != Some(setting) {
+                            self.add_control(
+                                ControlType::Timer,
+                                timetable.link_index,
+                                setting,
+                                0,
+                                (k as i64 * schedule.period as i64) as f64,
+                                true,
+                            )?;
+                            previous = Some(setting);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Helper methods - Internal API
+
+    fn validate_schedule_pumps(&self, pumps: &[LinkIndex]) -> Result<()> {
+        for &pump in pumps {
+            if self.get_link_type(pump)? != LinkType::Pump {
+                return Err(
+                    EPANETError::from(204).with_context(format!("link {pump} is not a pump"))
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `(control index, enabled)` for every simple control acting on one of `pumps`.
+    fn pump_controls(&self, pumps: &[LinkIndex]) -> Result<Vec<(i32, bool)>> {
+        let mut controls = Vec::new();
+        for index in 1..=self.get_count(CountType::ControlCount)? {
+            let control = self.get_control(index)?;
+            if pumps.contains(&control.link_index) {
+                controls.push((index, control.enabled));
+            }
+        }
+        Ok(controls)
+    }
+
+    /// Returns `(rule index, enabled)` for every rule with an action on one of `pumps`.
+    fn pump_rules(&self, pumps: &[LinkIndex]) -> Result<Vec<(i32, bool)>> {
+        let mut rules = Vec::new();
+        for index in 1..=self.get_count(CountType::RuleCount)? {
+            let rule = self.get_rule(index)?;
+            let acts_on_pump = rule
+                .then_actions
+                .iter()
+                .chain(rule.else_actions.iter())
+                .any(|a| pumps.contains(&a.link_index));
+            if acts_on_pump {
+                rules.push((index, rule.enabled));
+            }
+        }
+        Ok(rules)
+    }
+}
+
+/// Maps scheduling periods onto the project's pattern time steps.
+struct ScheduleTiming {
+    period: i64,
+    periods: usize,
+    pattern_step: i64,
+    pattern_start: i64,
+}
+
+impl ScheduleTiming {
+    fn new(project: &EPANET, period: i32) -> Result<Self> {
+        let pattern_step = project.get_time_parameter(TimeParameter::PatternStep)? as i64;
+        let pattern_start = project.get_time_parameter(TimeParameter::PatternStart)? as i64;
+        let duration = project.get_time_parameter(TimeParameter::Duration)? as i64;
+        let period = period as i64;
+        if period <= 0 || pattern_step <= 0 || period % pattern_step != 0 {
+            return Err(EPANETError::from(202).with_context(format!(
+                "schedule period {period} s is not a multiple of the pattern step {pattern_step} s"
+            )));
+        }
+        let periods = ((duration + period - 1) / period).max(1) as usize;
+        Ok(Self {
+            period,
+            periods,
+            pattern_step,
+            pattern_start,
+        })
+    }
+
+    fn check(&self, schedule: &PumpSchedule) -> Result<()> {
+        for timetable in &schedule.pumps {
+            if timetable.settings.len() != self.periods {
+                return Err(EPANETError::from(202).with_context(format!(
+                    "pump {} has {} settings but the schedule has {} periods",
+                    timetable.link_index,
+                    timetable.settings.len(),
+                    self.periods
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Period in effect at elapsed time `t`.
+    fn period_at(&self, t: i64) -> usize {
+        ((t / self.period) as usize).min(self.periods - 1)
+    }
+
+    /// Expands per-period settings into pattern multipliers, one per pattern step,
+    /// taking the pattern start time into account.
+    fn expand(&self, settings: &[f64]) -> Vec<f64> {
+        let horizon = self.period * self.periods as i64;
+        let len = horizon / self.pattern_step;
+        (0..len)
+            .map(|k| {
+                let t = (k * self.pattern_step - self.pattern_start).rem_euclid(horizon);
+                settings[self.period_at(t)]
+            })
+            .collect()
+    }
+}
+
+/// Energy price of a pump: base price and optional price pattern multipliers.
+struct Tariff {
+    price: f64,
+    multipliers: Vec<f64>,
+}
+
+impl Tariff {
+    fn at(&self, t: i64, timing: &ScheduleTiming) -> f64 {
+        if self.multipliers.is_empty() {
+            return self.price;
+        }
+        let p = ((t + timing.pattern_start) / timing.pattern_step) as usize;
+        self.price * self.multipliers[p % self.multipliers.len()]
+    }
+}
+
+/// Project state used while evaluating schedules, and what must be restored afterwards.
+struct ScheduleRun {
+    timing: ScheduleTiming,
+    pumps: Vec<LinkIndex>,
+    tariffs: Vec<Tariff>,
+    saved_patterns: Vec<f64>,
+    temp_patterns: Vec<PatternIndex>,
+    controls: Vec<(i32, bool)>,
+    rules: Vec<(i32, bool)>,
+    pressure_nodes: Vec<NodeIndex>,
+    /// `(node index, elevation, initial level)` for every tank.
+    tanks: Vec<(NodeIndex, f64, f64)>,
+}
+
+impl ScheduleRun {
+    fn setup(project: &EPANET, options: &PumpScheduleOptions) -> Result<Self> {
+        if options.settings.is_empty() || options.settings.iter().any(|&s| s < 0.0) {
+            return Err(EPANETError::from(202)
+                .with_context("pump schedule settings must be a non-empty list of values >= 0"));
+        }
+        let timing = ScheduleTiming::new(project, options.period)?;
+
+        let pumps = if options.pumps.is_empty() {
+            let mut pumps = Vec::new();
+            for index in 1..=project.get_count(CountType::LinkCount)? {
+                if project.get_link_type(index)? == LinkType::Pump {
+                    pumps.push(LinkIndex(index));
+                }
+            }
+            pumps
+        } else {
+            project.validate_schedule_pumps(&options.pumps)?;
+            options.pumps.clone()
+        };
+
+        let mut pressure_nodes = options.pressure_nodes.clone();
+        let mut tanks = Vec::new();
+        for index in 1..=project.get_count(CountType::NodeCount)? {
+            match project.get_node_type(index)? {
+                NodeType::Junction if options.pressure_nodes.is_empty() => {
+                    pressure_nodes.push(NodeIndex(index))
+                }
+                NodeType::Tank => tanks.push((
+                    NodeIndex(index),
+                    project.get_node_value(index, NodeProperty::Elevation)?,
+                    project.get_node_value(index, NodeProperty::TankLevel)?,
+                )),
+                _ => {}
+            }
+        }
+
+        let global_price = project.get_option(Option::GlobalPrice)?;
+        let global_pattern = project.get_option(Option::GlobalPattern)? as i32;
+        let mut tariffs = Vec::new();
+        let mut saved_patterns = Vec::new();
+        for &pump in &pumps {
+            let price = project.get_link_value(pump, LinkProperty::PumpECost)?;
+            let pattern = project.get_link_value(pump, LinkProperty::PumpEPat)? as i32;
+            let pattern = if pattern > 0 { pattern } else { global_pattern };
+            tariffs.push(Tariff {
+                price: if price > 0.0 { price } else { global_price },
+                multipliers: if pattern > 0 {
+                    project.get_pattern_by_index(pattern)?.multipliers
+                } else {
+                    Vec::new()
+                },
+            });
+            saved_patterns.push(project.get_link_value(pump, LinkProperty::LinkPattern)?);
+        }
+
+        let mut run = Self {
+            timing,
+            controls: project.pump_controls(&pumps)?,
+            rules: project.pump_rules(&pumps)?,
+            pumps,
+            tariffs,
+            saved_patterns,
+            temp_patterns: Vec::new(),
+            pressure_nodes,
+            tanks,
+        };
+        if let Err(e) = run.install(project) {
+            run.restore(project)?;
+            return Err(e);
+        }
+        Ok(run)
+    }
+
+    /// Creates a temporary speed pattern for every pump and disables conflicting controls.
+    fn install(&mut self, project: &EPANET) -> Result<()> {
+        for &(control, _) in &self.controls {
+            project.set_control_enabled(control, false)?;
+        }
+        for &(rule, _) in &self.rules {
+            project.set_rule_enabled(rule, false)?;
+        }
+        let mut suffix = 0;
+        for &pump in &self.pumps {
+            let id = loop {
+                suffix += 1;
+                let id = format!("__SCHEDULE_{suffix}");
+                if project.get_pattern_index(&id).is_err() {
+                    break id;
+                }
+            };
+            project.add_pattern(&id)?;
+            let index = project.get_pattern_index(&id)?;
+            self.temp_patterns.push(index);
+            project.set_link_value(pump, LinkProperty::LinkPattern, index.0 as f64)?;
+        }
+        Ok(())
+    }
+
+    fn restore(&self, project: &EPANET) -> Result<()> {
+        for (&pump, &pattern) in self.pumps.iter().zip(&self.saved_patterns) {
+            project.set_link_value(pump, LinkProperty::LinkPattern, pattern)?;
+        }
+        for &index in self.temp_patterns.iter().rev() {
+            project.delete_pattern(index)?;
+        }
+        for &(control, enabled) in &self.controls {
+            project.set_control_enabled(control, enabled)?;
+        }
+        for &(rule, enabled) in &self.rules {
+            project.set_rule_enabled(rule, enabled)?;
+        }
+        Ok(())
+    }
+
+    fn evaluate(
+        &self,
+        project: &EPANET,
+        schedule: &PumpSchedule,
+        options: &PumpScheduleOptions,
+    ) -> Result<ScheduleEvaluation> {
+        self.timing.check(schedule)?;
+        for (timetable, &pattern) in schedule.pumps.iter().zip(&self.temp_patterns) {
+            project.set_pattern(pattern, &self.timing.expand(&timetable.settings))?;
+        }
+
+        let mut energy_cost = 0.0;
+        let mut energy = 0.0;
+        let mut min_pressure = f64::INFINITY;
+        let mut pressure_deficit = 0.0;
+        let mut final_levels = vec![0.0; self.tanks.len()];
+        // (time, power in kW, cost per hour) of the previous hydraulic step
+        let mut previous: core::option::Option<(i64, f64, f64)> = None;
+
+        let warnings = project.run_hydraulics_with(|t| {
+            if let Some((t0, power, rate)) = previous {
+                let hours = (t - t0) as f64 / 3600.0;
+                energy += power * hours;
+                energy_cost += rate * hours;
+            }
+
+            let mut power = 0.0;
+            let mut rate = 0.0;
+            for (&pump, tariff) in self.pumps.iter().zip(&self.tariffs) {
+                let kw = project.get_link_value(pump, LinkProperty::Energy)?;
+                power += kw;
+                rate += kw * tariff.at(t, &self.timing);
+            }
+            previous = Some((t, power, rate));
+
+            for &node in &self.pressure_nodes {
+                let pressure = project.get_node_value(node, NodeProperty::Pressure)?;
+                min_pressure = min_pressure.min(pressure);
+                pressure_deficit += (options.min_pressure - pressure).max(0.0);
+            }
+            for (level, &(node, elevation, _)) in final_levels.iter_mut().zip(&self.tanks) {
+                *level = project.get_node_value(node, NodeProperty::Head)? - elevation;
+            }
+            Ok(())
+        })?;
+
+        let tank_level_violation: f64 = self
+            .tanks
+            .iter()
+            .zip(&final_levels)
+            .map(|(&(_, _, initial), &level)| {
+                ((level - initial).abs() - options.tank_level_tolerance).max(0.0)
+            })
+            .sum();
+
+        Ok(ScheduleEvaluation {
+            energy_cost,
+            energy,
+            min_pressure,
+            pressure_deficit,
+            tank_level_violation,
+            warnings,
+            feasible: pressure_deficit <= 0.0 && tank_level_violation <= 0.0 && warnings == 0,
+        })
+    }
+
+    fn optimize(
+        &self,
+        project: &EPANET,
+        options: &PumpScheduleOptions,
+    ) -> Result<PumpScheduleResult> {
+        let max_setting = options.settings.iter().cloned().fold(0.0, f64::max);
+        let mut schedule = PumpSchedule {
+            period: self.timing.period as i32,
+            pumps: self
+                .pumps
+                .iter()
+                .map(|&link_index| PumpTimetable {
+                    link_index,
+                    settings: vec![max_setting; self.timing.periods],
+                })
+                .collect(),
+        };
+        let mut best = self.evaluate(project, &schedule, options)?;
+        let mut evaluations = 1;
+
+        // Visit each pump's periods from the most to the least expensive tariff.
+        let mut moves = Vec::new();
+        for (p, tariff) in self.tariffs.iter().enumerate() {
+            for k in 0..self.timing.periods {
+                let start = k as i64 * self.timing.period;
+                let steps = self.timing.period / self.timing.pattern_step;
+                let price: f64 = (0..steps)
+                    .map(|s| tariff.at(start + s * self.timing.pattern_step, &self.timing))
+                    .sum();
+                moves.push((p, k, price));
+            }
+        }
+        moves.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        let mut improved = true;
+        while improved && evaluations < options.max_evaluations {
+            improved = false;
+            for &(p, k, _) in &moves {
+                let current = schedule.pumps[p].settings[k];
+                let mut candidates: Vec<f64> = options
+                    .settings
+                    .iter()
+                    .cloned()
+                    .filter(|&s| s != current)
+                    .collect();
+                // Try the cheapest settings first.
+                candidates.sort_by(f64::total_cmp);
+                for setting in candidates {
+                    if evaluations >= options.max_evaluations {
+                        break;
+                    }
+                    schedule.pumps[p].settings[k] = setting;
+                    let trial = self.evaluate(project, &schedule, options)?;
+                    evaluations += 1;
+                    if trial.objective(options.penalty) < best.objective(options.penalty) - 1e-9 {
+                        best = trial;
+                        improved = true;
+                        break;
+                    }
+                    schedule.pumps[p].settings[k] = current;
+                }
+            }
+        }
+
+        Ok(PumpScheduleResult {
+            schedule,
+            evaluation: best,
+            evaluations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use rstest::rstest;
+
+    /// Net1 with a day/night tariff: expensive between 06:00 and 18:00.
+    fn priced(ph: EPANET) -> EPANET {
+        ph.set_option(Option::GlobalPrice, 0.1).unwrap();
+        let tariff = [1.0, 1.0, 1.0, 3.0, 3.0, 3.0, 3.0, 3.0, 3.0, 1.0, 1.0, 1.0];
+        let pattern = ph.create_pattern("TARIFF", &tariff).unwrap();
+        ph.set_option(Option::GlobalPattern, pattern.index().0 as f64)
+            .unwrap();
+        ph
+    }
+
+    fn options(ph: &EPANET) -> PumpScheduleOptions {
+        PumpScheduleOptions {
+            pumps: vec![ph.get_link_index("9").unwrap()],
+            period: 7200,
+            min_pressure: 20.0,
+            tank_level_tolerance: 5.0,
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    fn test_evaluate_pump_schedule(ph: EPANET) {
+        let ph = priced(ph);
+        let options = options(&ph);
+        let pump = options.pumps[0];
+        let pattern_count = ph.get_count(CountType::PatternCount).unwrap();
+
+        let all_on = PumpSchedule {
+            period: 7200,
+            pumps: vec![PumpTimetable {
+                link_index: pump,
+                settings: vec![1.0; 12],
+            }],
+        };
+        let eval = ph.evaluate_pump_schedule(&all_on, &options).unwrap();
+        assert!(eval.energy > 0.0);
+        assert!(eval.energy_cost > eval.energy * 0.1);
+
+        let mut half = all_on.clone();
+        half.pumps[0].settings[6..].fill(0.0);
+        let cheaper = ph.evaluate_pump_schedule(&half, &options).unwrap();
+        assert!(cheaper.energy_cost < eval.energy_cost);
+
+        // The project is restored afterwards.
+        assert_eq!(
+            ph.get_count(CountType::PatternCount).unwrap(),
+            pattern_count
+        );
+        assert_eq!(
+            ph.get_link_value(pump, LinkProperty::LinkPattern).unwrap(),
+            0.0
+        );
+        assert!(ph.get_control(1).unwrap().enabled);
+        assert!(ph.get_control(2).unwrap().enabled);
+
+        let mut wrong = all_on.clone();
+        wrong.pumps[0].settings.pop();
+        assert!(ph.evaluate_pump_schedule(&wrong, &options).is_err());
+    }
+
+    #[rstest]
+    fn test_optimize_pump_schedule(ph: EPANET) {
+        let ph = priced(ph);
+        let options = options(&ph);
+
+        let result = ph.optimize_pump_schedule(&options).unwrap();
+        assert!(result.evaluation.feasible, "{:?}", result.evaluation);
+        assert!(result.evaluations <= options.max_evaluations);
+        assert_eq!(result.schedule.pumps[0].settings.len(), 12);
+
+        let all_on = PumpSchedule {
+            period: 7200,
+            pumps: vec![PumpTimetable {
+                link_index: options.pumps[0],
+                settings: vec![1.0; 12],
+            }],
+        };
+        let baseline = ph.evaluate_pump_schedule(&all_on, &options).unwrap();
+        assert!(result.evaluation.energy_cost < baseline.energy_cost);
+
+        // Re-evaluating the schedule reproduces the reported cost.
+        let again = ph
+            .evaluate_pump_schedule(&result.schedule, &options)
+            .unwrap();
+        assert!(approx_eq(
+            again.energy_cost,
+            result.evaluation.energy_cost,
+            1e-9
+        ));
+    }
+
+    #[rstest]
+    fn test_apply_pump_schedule(ph: EPANET) {
+        let pump = ph.get_link_index("9").unwrap();
+        let mut settings = vec![1.0; 12];
+        settings[3..6].fill(0.0);
+        let schedule = PumpSchedule {
+            period: 7200,
+            pumps: vec![PumpTimetable {
+                link_index: pump,
+                settings: settings.clone(),
+            }],
+        };
+
+        ph.apply_pump_schedule(&schedule, ScheduleOutput::Patterns)
+            .unwrap();
+        let pattern = ph.get_pattern("SCHED_9").unwrap();
+        assert_eq!(pattern.multipliers, settings);
+        assert_eq!(
+            ph.get_link_value(pump, LinkProperty::LinkPattern).unwrap(),
+            pattern.index().0 as f64
+        );
+        assert!(!ph.get_control(1).unwrap().enabled);
+        assert!(!ph.get_control(2).unwrap().enabled);
+
+        ph.apply_pump_schedule(&schedule, ScheduleOutput::Controls)
+            .unwrap();
+        assert_eq!(
+            ph.get_link_value(pump, LinkProperty::LinkPattern).unwrap(),
+            0.0
+        );
+        // On at 0 h, off at 6 h, on again at 12 h.
+        assert_eq!(ph.get_count(CountType::ControlCount).unwrap(), 5);
+        let off = ph.get_control(4).unwrap();
+        assert_eq!(off.control_type, ControlType::Timer);
+        assert_eq!(off.setting, 0.0);
+        assert_eq!(off.level, 6.0 * 3600.0);
+        assert_eq!(ph.solve_h(), Ok(()));
+    }
+
+    #[rstest]
+    fn test_pump_schedule_invalid_period(ph: EPANET) {
+        let options = PumpScheduleOptions {
+            period: 5400,
+            ..Default::default()
+        };
+        let result = ph.optimize_pump_schedule(&options);
+        assert_eq!(result.unwrap_err().code, 202);
+    }
+}
@@ -0,0 +1,458 @@
+//! Sensitivity analysis API methods for EPANET.
+//!
+//! This module contains methods for running batches of extended period
+//! simulations with perturbed model parameters and computing Morris or Sobol
+//! sensitivity indices of selected outputs.
+
+use crate::epanet_error::*;
+use crate::types::link::{LinkProperty, LinkType};
+use crate::types::node::NodeProperty;
+use crate::types::options::Option;
+use crate::types::sensitivity::*;
+use crate::types::CountType;
+use crate::EPANET;
+
+/// ## Sensitivity Analysis APIs
+impl EPANET {
+    /// Runs a global sensitivity analysis of `options.outputs` with respect to
+    /// `options.parameters`.
+    ///
+    /// Parameter values are drawn from their ranges with the chosen
+    /// [`SensitivityMethod`], each sample is simulated over the full duration, and
+    /// the resulting outputs are reduced to one [`SensitivityIndex`] per output and
+    /// parameter. Sampling is deterministic for a given [`SensitivityOptions::seed`].
+    /// The original parameter values are restored before returning.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if no parameters or outputs are given, a range is
+    /// empty, the sample sizes are too small, or a simulation fails.
+    pub fn sensitivity_analysis(&self, options: &SensitivityOptions) -> Result<SensitivityResult> {
+        if options.parameters.is_empty() || options.outputs.is_empty() {
+            return Err(EPANETError::from(202)
+                .with_context("sensitivity analysis needs at least one parameter and output"));
+        }
+        if let Some(range) = options
+            .parameters
+            .iter()
+            .find(|r| r.min > r.max || r.min.is_nan() || r.max.is_nan())
+        {
+            return Err(EPANETError::from(202)
+                .with_context(format!("invalid range for {:?}", range.parameter)));
+        }
+
+        let mut original = Vec::new();
+        for range in &options.parameters {
+            original.push(self.get_sensitivity_parameter(range.parameter)?);
+        }
+
+        let result = match options.method {
+            SensitivityMethod::Morris {
+                trajectories,
+                levels,
+            } => self.morris(options, trajectories, levels),
+            SensitivityMethod::Sobol { samples } => self.sobol(options, samples),
+        };
+
+        for (range, &value) in options.parameters.iter().zip(&original) {
+            self.set_sensitivity_parameter(range.parameter, value)?;
+        }
+        result
+    }
+
+    // Helper methods - Internal API
+
+    fn get_sensitivity_parameter(&self, parameter: SensitivityParameter) -> Result<f64> {
+        match parameter {
+            SensitivityParameter::Roughness { link_index } => {
+                self.get_link_value(link_index, LinkProperty::Roughness)
+            }
+            SensitivityParameter::BaseDemand { node_index } => {
+                self.get_node_value(node_index, NodeProperty::BaseDemand)
+            }
+            SensitivityParameter::TankInitLevel { node_index } => {
+                self.get_node_value(node_index, NodeProperty::TankLevel)
+            }
+            SensitivityParameter::PumpSpeed { link_index } => {
+                self.get_link_value(link_index, LinkProperty::InitSetting)
+            }
+            SensitivityParameter::DemandMultiplier => self.get_option(Option::DemandMult),
+        }
+    }
+
+    fn set_sensitivity_parameter(&self, parameter: SensitivityParameter, value: f64) -> Result<()> {
+        match parameter {
+            SensitivityParameter::Roughness { link_index } => {
+                self.set_link_value(link_index, LinkProperty::Roughness, value)
+            }
+            SensitivityParameter::BaseDemand { node_index } => {
+                self.set_node_value(node_index, NodeProperty::BaseDemand, value)
+            }
+            SensitivityParameter::TankInitLevel { node_index } => {
+                self.set_node_value(node_index, NodeProperty::TankLevel, value)
+            }
+            SensitivityParameter::PumpSpeed { link_index } => {
+                self.set_link_value(link_index, LinkProperty::InitSetting, value)
+            }
+            SensitivityParameter::DemandMultiplier => self.set_option(Option::DemandMult, value),
+        }
+    }
+
+    /// Sets the parameters from a point of the unit hypercube and simulates the outputs.
+    fn sensitivity_sample(&self, options: &SensitivityOptions, x: &[f64]) -> Result<Vec<f64>> {
+        for (range, &u) in options.parameters.iter().zip(x) {
+            self.set_sensitivity_parameter(
+                range.parameter,
+                range.min + u * (range.max - range.min),
+            )?;
+        }
+
+        let mut pumps = Vec::new();
+        for index in 1..=self.get_count(CountType::LinkCount)? {
+            if self.get_link_type(index)? == LinkType::Pump {
+                pumps.push(index);
+            }
+        }
+        let mut tank_elevations = Vec::new();
+        for output in &options.outputs {
+            tank_elevations.push(match *output {
+                SensitivityOutput::MinTankLevel { node_index } => {
+                    self.get_node_value(node_index, NodeProperty::Elevation)?
+                }
+                _ => 0.0,
+            });
+        }
+
+        let mut values: Vec<f64> = options
+            .outputs
+            .iter()
+            .map(|o| match o {
+                SensitivityOutput::TotalEnergy => 0.0,
+                _ => f64::INFINITY,
+            })
+            .collect();
+        // (time, total pump power in kW) of the previous hydraulic step
+        let mut previous = (0, 0.0);
+        self.run_hydraulics_with(|t| {
+            let mut power = 0.0;
+            for &pump in &pumps {
+                power += self.get_link_value(pump, LinkProperty::Energy)?;
+            }
+            let energy = previous.1 * (t - previous.0) as f64 / 3600.0;
+            previous = (t, power);
+
+            for ((value, output), elevation) in values
+                .iter_mut()
+                .zip(&options.outputs)
+                .zip(&tank_elevations)
+            {
+                match *output {
+                    SensitivityOutput::MinPressure { node_index } => {
+                        *value = value.min(self.get_node_value(node_index, NodeProperty::Pressure)?)
+                    }
+                    SensitivityOutput::TotalEnergy => *value += energy,
+                    SensitivityOutput::MinTankLevel { node_index } => {
+                        let level =
+                            self.get_node_value(node_index, NodeProperty::Head)? - elevation;
+                        *value = value.min(level)
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        Ok(values)
+    }
+
+    fn morris(
+        &self,
+        options: &SensitivityOptions,
+        trajectories: usize,
+        levels: usize,
+    ) -> Result<SensitivityResult> {
+        if trajectories < 2 || levels < 2 {
+            return Err(EPANETError::from(202)
+                .with_context("Morris analysis needs at least 2 trajectories and 2 levels"));
+        }
+        let k = options.parameters.len();
+        let m = options.outputs.len();
+        let mut rng = SplitMix64(options.seed);
+        let delta = levels as f64 / (2.0 * (levels as f64 - 1.0));
+        // Grid values from which a step of +delta stays inside [0, 1].
+        let base_levels: Vec<f64> = (0..levels)
+            .map(|l| l as f64 / (levels as f64 - 1.0))
+            .filter(|&v| v + delta <= 1.0 + 1e-12)
+            .collect();
+
+        // effects[output][parameter] = elementary effects
+        let mut effects = vec![vec![Vec::with_capacity(trajectories); k]; m];
+        let mut evaluations = 0;
+        for _ in 0..trajectories {
+            let mut x: Vec<f64> = (0..k)
+                .map(|_| base_levels[rng.below(base_levels.len())])
+                .collect();
+            let mut y = self.sensitivity_sample(options, &x)?;
+            evaluations += 1;
+            for i in rng.permutation(k) {
+                let step = if rng.next_f64() < 0.5 && x[i] - delta >= -1e-12 {
+                    -delta
+                } else {
+                    delta
+                };
+                x[i] += step;
+                let y_next = self.sensitivity_sample(options, &x)?;
+                evaluations += 1;
+                for j in 0..m {
+                    effects[j][i].push((y_next[j] - y[j]) / step);
+                }
+                y = y_next;
+            }
+        }
+
+        let outputs = options
+            .outputs
+            .iter()
+            .zip(&effects)
+            .map(|(&output, per_parameter)| OutputSensitivity {
+                output,
+                indices: per_parameter
+                    .iter()
+                    .map(|ee| {
+                        let n = ee.len() as f64;
+                        let mu = ee.iter().sum::<f64>() / n;
+                        SensitivityIndex::Morris {
+                            mu,
+                            mu_star: ee.iter().map(|e| e.abs()).sum::<f64>() / n,
+                            sigma: (ee.iter().map(|e| (e - mu).powi(2)).sum::<f64>() / (n - 1.0))
+                                .sqrt(),
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(SensitivityResult {
+            outputs,
+            evaluations,
+        })
+    }
+
+    fn sobol(&self, options: &SensitivityOptions, samples: usize) -> Result<SensitivityResult> {
+        if samples < 2 {
+            return Err(
+                EPANETError::from(202).with_context("Sobol analysis needs at least 2 samples")
+            );
+        }
+        let k = options.parameters.len();
+        let m = options.outputs.len();
+        let mut rng = SplitMix64(options.seed);
+        let a: Vec<Vec<f64>> = (0..samples)
+            .map(|_| (0..k).map(|_| rng.next_f64()).collect())
+            .collect();
+        let b: Vec<Vec<f64>> = (0..samples)
+            .map(|_| (0..k).map(|_| rng.next_f64()).collect())
+            .collect();
+
+        let mut y_a = Vec::with_capacity(samples);
+        let mut y_b = Vec::with_capacity(samples);
+        for (xa, xb) in a.iter().zip(&b) {
+            y_a.push(self.sensitivity_sample(options, xa)?);
+            y_b.push(self.sensitivity_sample(options, xb)?);
+        }
+        // y_ab[i][s] = outputs for row s of A with column i taken from B
+        let mut y_ab = Vec::with_capacity(k);
+        for i in 0..k {
+            let mut column = Vec::with_capacity(samples);
+            for (xa, xb) in a.iter().zip(&b) {
+                let mut x = xa.clone();
+                x[i] = xb[i];
+                column.push(self.sensitivity_sample(options, &x)?);
+            }
+            y_ab.push(column);
+        }
+
+        let n = samples as f64;
+        let outputs = (0..m)
+            .map(|j| {
+                let all: Vec<f64> = y_a.iter().chain(&y_b).map(|y| y[j]).collect();
+                let mean = all.iter().sum::<f64>() / all.len() as f64;
+                let variance =
+                    all.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (all.len() as f64 - 1.0);
+                let indices = (0..k)
+                    .map(|i| {
+                        if variance <= 0.0 {
+                            return SensitivityIndex::Sobol {
+                                first_order: 0.0,
+                                total_order: 0.0,
+                            };
+                        }
+                        let (mut first, mut total) = (0.0, 0.0);
+                        for s in 0..samples {
+                            let (ya, yb, yab) = (y_a[s][j], y_b[s][j], y_ab[i][s][j]);
+                            first += yb * (yab - ya);
+                            total += (ya - yab).powi(2);
+                        }
+                        SensitivityIndex::Sobol {
+                            first_order: first / n / variance,
+                            total_order: total / (2.0 * n) / variance,
+                        }
+                    })
+                    .collect();
+                OutputSensitivity {
+                    output: options.outputs[j],
+                    indices,
+                }
+            })
+            .collect();
+
+        Ok(SensitivityResult {
+            outputs,
+            evaluations: samples * (k + 2),
+        })
+    }
+}
+
+/// Small deterministic pseudo-random generator (SplitMix64) used for sampling.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform integer in `0..n`.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_f64() * n as f64) as usize
+    }
+
+    /// Random permutation of `0..n`.
+    fn permutation(&mut self, n: usize) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..n).collect();
+        for i in (1..n).rev() {
+            order.swap(i, self.below(i + 1));
+        }
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use rstest::rstest;
+
+    fn options(ph: &EPANET, method: SensitivityMethod) -> SensitivityOptions {
+        let tank = ph.get_node_index("2").unwrap();
+        SensitivityOptions {
+            parameters: vec![
+                ParameterRange {
+                    parameter: SensitivityParameter::DemandMultiplier,
+                    min: 0.8,
+                    max: 1.2,
+                },
+                ParameterRange {
+                    parameter: SensitivityParameter::Roughness {
+                        link_index: ph.get_link_index("122").unwrap(),
+                    },
+                    min: 90.0,
+                    max: 110.0,
+                },
+                ParameterRange {
+                    parameter: SensitivityParameter::TankInitLevel { node_index: tank },
+                    min: 105.0,
+                    max: 145.0,
+                },
+            ],
+            outputs: vec![
+                SensitivityOutput::MinPressure {
+                    node_index: ph.get_node_index("32").unwrap(),
+                },
+                SensitivityOutput::TotalEnergy,
+                SensitivityOutput::MinTankLevel { node_index: tank },
+            ],
+            method,
+            seed: 7,
+        }
+    }
+
+    fn mu_star(index: &SensitivityIndex) -> f64 {
+        match index {
+            SensitivityIndex::Morris { mu_star, .. } => *mu_star,
+            _ => panic!("expected Morris index"),
+        }
+    }
+
+    #[rstest]
+    fn test_morris(ph: EPANET) {
+        let method = SensitivityMethod::Morris {
+            trajectories: 4,
+            levels: 4,
+        };
+        let options = options(&ph, method);
+        let roughness = ph
+            .get_link_value(ph.get_link_index("122").unwrap(), LinkProperty::Roughness)
+            .unwrap();
+
+        let result = ph.sensitivity_analysis(&options).unwrap();
+        assert_eq!(result.evaluations, 4 * (3 + 1));
+        assert_eq!(result.outputs.len(), 3);
+
+        // Minimum tank level is driven by the initial tank level, not by a small pipe.
+        let tank = &result.outputs[2].indices;
+        assert!(mu_star(&tank[2]) > mu_star(&tank[1]));
+        // Energy responds to demand.
+        assert!(mu_star(&result.outputs[1].indices[0]) > 0.0);
+
+        // Parameters are restored.
+        assert_eq!(
+            ph.get_link_value(ph.get_link_index("122").unwrap(), LinkProperty::Roughness)
+                .unwrap(),
+            roughness
+        );
+        assert_eq!(ph.get_option(Option::DemandMult).unwrap(), 1.0);
+        assert!(approx_eq(
+            ph.get_node_value(ph.get_node_index("2").unwrap(), NodeProperty::TankLevel)
+                .unwrap(),
+            120.0,
+            1e-6
+        ));
+    }
+
+    #[rstest]
+    fn test_sobol(ph: EPANET) {
+        let options = options(&ph, SensitivityMethod::Sobol { samples: 16 });
+        let result = ph.sensitivity_analysis(&options).unwrap();
+        assert_eq!(result.evaluations, 16 * 5);
+
+        let total: Vec<f64> = result.outputs[2]
+            .indices
+            .iter()
+            .map(|i| match i {
+                SensitivityIndex::Sobol { total_order, .. } => *total_order,
+                _ => panic!("expected Sobol index"),
+            })
+            .collect();
+        assert!(total[2] > total[1]);
+        assert!(total.iter().all(|t| t.is_finite() && *t >= 0.0));
+
+        // Same seed, same answer.
+        assert_eq!(ph.sensitivity_analysis(&options).unwrap(), result);
+    }
+
+    #[rstest]
+    fn test_sensitivity_invalid_options(ph: EPANET) {
+        let mut options = options(&ph, SensitivityMethod::Sobol { samples: 1 });
+        assert_eq!(ph.sensitivity_analysis(&options).unwrap_err().code, 202);
+        options.method = SensitivityMethod::Sobol { samples: 4 };
+        options.parameters[0].min = 2.0;
+        assert_eq!(ph.sensitivity_analysis(&options).unwrap_err().code, 202);
+    }
+}
@@ -0,0 +1,570 @@
+//! Network skeletonization API methods for EPANET.
+//!
+//! This module contains methods for reducing the size of a loaded network by
+//! trimming dead-end branches, merging pipes in series and merging parallel
+//! pipes, and for checking that the reduced model reproduces the original
+//! pressures.
+
+use crate::epanet_error::*;
+use crate::types::index::NodeIndex;
+use crate::types::link::{LinkProperty, LinkStatusType, LinkType};
+use crate::types::node::{NodeProperty, NodeType};
+use crate::types::options::{HeadLossType, Option};
+use crate::types::skeleton::*;
+use crate::types::{ActionCodeType, CountType};
+use crate::EPANET;
+use num_traits::FromPrimitive;
+use std::collections::HashMap;
+
+/// Length, diameter, roughness and minor loss coefficient of a pipe.
+type PipeProps = (f64, f64, f64, f64);
+
+/// ## Skeletonization APIs
+impl EPANET {
+    /// Simplifies the network in place and reports every reduction performed.
+    ///
+    /// Reductions are repeated until none applies:
+    /// - **Branch trimming**: a junction connected by a single pipe is removed with
+    ///   that pipe, and its demand categories are moved to the junction upstream.
+    /// - **Series merging**: a junction joining exactly two pipes is removed and the
+    ///   longer pipe is extended to span both, with its roughness (Hazen-Williams,
+    ///   Chezy-Manning) or length (Darcy-Weisbach, assuming both pipes share a
+    ///   friction factor) adjusted so the head loss is unchanged. Any demand is
+    ///   split between the two end junctions.
+    /// - **Parallel merging**: of two pipes joining the same nodes, one is removed
+    ///   and the other is given the diameter that carries the combined flow.
+    ///
+    /// Only open pipes (not check valve pipes) no larger than
+    /// [`SkeletonOptions::max_diameter`] are touched, and only junctions that have no
+    /// emitter, do not appear in controls and are not listed in
+    /// [`SkeletonOptions::keep_nodes`] are removed.
+    ///
+    /// The extended period pressures at the remaining nodes are compared with those
+    /// of the original model and the largest difference is reported. The reduction is
+    /// not undone when it exceeds the tolerance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if a simulation or an element update fails.
+    pub fn skeletonize(&self, options: &SkeletonOptions) -> Result<SkeletonReport> {
+        let before = self.skeleton_pressures()?;
+
+        let mut reductions = Vec::new();
+        loop {
+            let count = reductions.len();
+            if options.trim_branches {
+                self.trim_branches(options, &mut reductions)?;
+            }
+            if options.merge_series {
+                self.merge_series_pipes(options, &mut reductions)?;
+            }
+            if options.merge_parallel {
+                self.merge_parallel_pipes(options, &mut reductions)?;
+            }
+            if reductions.len() == count {
+                break;
+            }
+        }
+
+        let after = self.skeleton_pressures()?;
+        let mut max_pressure_difference: f64 = 0.0;
+        for (time, pressures) in &after {
+            if let Some((_, original)) = before.iter().find(|(t, _)| t == time) {
+                for (id, p) in pressures {
+                    if let Some(q) = original.get(id) {
+                        max_pressure_difference = max_pressure_difference.max((p - q).abs());
+                    }
+                }
+            }
+        }
+
+        Ok(SkeletonReport {
+            reductions,
+            max_pressure_difference,
+            within_tolerance: max_pressure_difference <= options.pressure_tolerance,
+        })
+    }
+
+    // Helper methods - Internal API
+
+    /// Pressures at every node by ID for each hydraulic time step.
+    fn skeleton_pressures(&self) -> Result<Vec<(i64, HashMap<String, f64>)>> {
+        let mut ids = Vec::new();
+        for index in 1..=self.get_count(CountType::NodeCount)? {
+            ids.push(self.get_node_id(index)?);
+        }
+        let mut series = Vec::new();
+        self.run_hydraulics_with(|t| {
+            let pressures = self.get_node_values(NodeProperty::Pressure)?;
+            series.push((t, ids.iter().cloned().zip(pressures).collect()));
+            Ok(())
+        })?;
+        Ok(series)
+    }
+
+    /// [`Adjacency`] of the current network.
+    fn skeleton_adjacency(&self) -> Result<Adjacency> {
+        let mut adjacency = vec![Vec::new(); self.get_count(CountType::NodeCount)? as usize + 1];
+        for link in 1..=self.get_count(CountType::LinkCount)? {
+            let (from, to) = self.get_link_nodes(link)?;
+            adjacency[from.0 as usize].push(link);
+            adjacency[to.0 as usize].push(link);
+        }
+        Ok(Adjacency(adjacency))
+    }
+
+    fn skeleton_pipe(&self, link: i32, options: &SkeletonOptions) -> Result<bool> {
+        Ok(self.get_link_type(link)? == LinkType::Pipe
+            && self.get_link_value(link, LinkProperty::InitStatus)?
+                == LinkStatusType::Open as i32 as f64
+            && self.get_link_value(link, LinkProperty::Diameter)? <= options.max_diameter
+            && self.get_link_value(link, LinkProperty::LinkInControl)? == 0.0)
+    }
+
+    fn skeleton_removable_node(&self, node: NodeIndex, options: &SkeletonOptions) -> Result<bool> {
+        Ok(self.get_node_type(node)? == NodeType::Junction
+            && self.get_node_value(node, NodeProperty::NodeInControl)? == 0.0
+            && self.get_node_value(node, NodeProperty::Emitter)? == 0.0
+            && !options.keep_nodes.contains(&self.get_node_id(node)?))
+    }
+
+    fn pipe_props(&self, link: i32) -> Result<PipeProps> {
+        Ok((
+            self.get_link_value(link, LinkProperty::Length)?,
+            self.get_link_value(link, LinkProperty::Diameter)?,
+            self.get_link_value(link, LinkProperty::Roughness)?,
+            self.get_link_value(link, LinkProperty::MinorLoss)?,
+        ))
+    }
+
+    pub(crate) fn head_loss_type(&self) -> Result<HeadLossType> {
+        let form = self.get_option(Option::HeadLossForm)? as i32;
+        HeadLossType::from_i32(form).ok_or_else(|| EPANETError::from(213))
+    }
+
+    /// Copies every demand category of `from` to the `targets`, scaled by the
+    /// given fractions, and returns the total base demand moved.
+    fn move_demands(&self, from: NodeIndex, targets: &[(NodeIndex, f64)]) -> Result<f64> {
+        let mut total = 0.0;
+        for category in 1..=self.get_demand_count(from)? {
+            let base = self.get_base_demand(from, category)?;
+            if base == 0.0 {
+                continue;
+            }
+            let pattern = self.get_demand_pattern(from, category)?;
+            let pattern_id = if pattern.0 > 0 {
+                self.get_pattern_id(pattern)?
+            } else {
+                String::new()
+            };
+            let name = self.get_demand_name(from, category)?;
+            for &(node, fraction) in targets {
+                self.add_demand(node, base * fraction, &pattern_id, &name)?;
+            }
+            total += base;
+        }
+        Ok(total)
+    }
+
+    /// One pass of branch trimming. After a trim the upstream junction is
+    /// revisited, so a whole dead-end branch is removed in a single pass.
+    fn trim_branches(
+        &self,
+        options: &SkeletonOptions,
+        reductions: &mut Vec<Reduction>,
+    ) -> Result<()> {
+        let mut adjacency = self.skeleton_adjacency()?;
+        let mut node = 1;
+        while node < adjacency.0.len() {
+            let index = NodeIndex(node as i32);
+            let links = &adjacency.0[node];
+            if links.len() != 1
+                || !self.skeleton_removable_node(index, options)?
+                || !self.skeleton_pipe(links[0], options)?
+            {
+                node += 1;
+                continue;
+            }
+            let pipe = links[0];
+            let (from, to) = self.get_link_nodes(pipe)?;
+            let upstream = if from == index { to } else { from };
+            if self.get_node_type(upstream)? != NodeType::Junction {
+                node += 1;
+                continue;
+            }
+
+            let node_id = self.get_node_id(index)?;
+            let pipe_id = self.get_link_id(pipe)?;
+            let demand_moved_to = self.get_node_id(upstream)?;
+            let demand = self.move_demands(index, &[(upstream, 1.0)])?;
+            self.delete_link(pipe, ActionCodeType::Conditional)?;
+            adjacency.remove_link(pipe);
+            self.delete_node(index, ActionCodeType::Conditional)?;
+            adjacency.remove_node(index);
+            reductions.push(Reduction::BranchTrimmed {
+                node_id,
+                pipe_id,
+                demand_moved_to,
+                demand,
+            });
+            // Node indices above the trimmed junction have shifted down by one
+            node = node.min(upstream.0 as usize);
+        }
+        Ok(())
+    }
+
+    /// One pass of series merging.
+    fn merge_series_pipes(
+        &self,
+        options: &SkeletonOptions,
+        reductions: &mut Vec<Reduction>,
+    ) -> Result<()> {
+        let mut adjacency = self.skeleton_adjacency()?;
+        let mut node = 1;
+        while node < adjacency.0.len() {
+            let links = adjacency.0[node].clone();
+            let index = NodeIndex(node as i32);
+            node += 1;
+            if links.len() != 2
+                || links[0] == links[1]
+                || !self.skeleton_removable_node(index, options)?
+                || !self.skeleton_pipe(links[0], options)?
+                || !self.skeleton_pipe(links[1], options)?
+            {
+                continue;
+            }
+            let other_end = |link: i32| -> Result<NodeIndex> {
+                let (from, to) = self.get_link_nodes(link)?;
+                Ok(if from == index { to } else { from })
+            };
+            let (end_a, end_b) = (other_end(links[0])?, other_end(links[1])?);
+            if end_a == end_b {
+                continue;
+            }
+            let junctions: Vec<NodeIndex> = [end_a, end_b]
+                .into_iter()
+                .filter(|&n| self.get_node_type(n).ok() == Some(NodeType::Junction))
+                .collect();
+            let has_demand = (1..=self.get_demand_count(index)?)
+                .any(|c| self.get_base_demand(index, c).unwrap_or(0.0) != 0.0);
+            if has_demand && junctions.is_empty() {
+                continue;
+            }
+
+            let (pa, pb) = (self.pipe_props(links[0])?, self.pipe_props(links[1])?);
+            let (keep, remove, keep_props, remove_props, keep_end, remove_end) = if pa.0 >= pb.0 {
+                (links[0], links[1], pa, pb, end_a, end_b)
+            } else {
+                (links[1], links[0], pb, pa, end_b, end_a)
+            };
+            let (length, diameter, roughness, minor_loss) =
+                series_equivalent(self.head_loss_type()?, keep_props, remove_props);
+
+            let node_id = self.get_node_id(index)?;
+            let kept_pipe_id = self.get_link_id(keep)?;
+            let removed_pipe_id = self.get_link_id(remove)?;
+
+            let fraction = 1.0 / junctions.len().max(1) as f64;
+            let targets: Vec<(NodeIndex, f64)> = junctions.iter().map(|&n| (n, fraction)).collect();
+            self.move_demands(index, &targets)?;
+
+            self.delete_link(remove, ActionCodeType::Conditional)?;
+            adjacency.remove_link(remove);
+            // Link indices above `remove` have shifted; node indices are unchanged.
+            let keep = self.get_link_index(&kept_pipe_id)?;
+            let (from, _) = self.get_link_nodes(keep)?;
+            if from == index {
+                self.set_link_nodes(keep, remove_end, keep_end)?;
+            } else {
+                self.set_link_nodes(keep, keep_end, remove_end)?;
+            }
+            self.set_pipe_data(keep, length, diameter, roughness, minor_loss)?;
+            adjacency.add_link(remove_end, keep.0);
+            self.delete_node(index, ActionCodeType::Conditional)?;
+            adjacency.remove_node(index);
+            // The next node now sits at the removed junction's index
+            node -= 1;
+
+            reductions.push(Reduction::SeriesMerged {
+                node_id,
+                kept_pipe_id,
+                removed_pipe_id,
+            });
+        }
+        Ok(())
+    }
+
+    /// One pass of parallel merging.
+    fn merge_parallel_pipes(
+        &self,
+        options: &SkeletonOptions,
+        reductions: &mut Vec<Reduction>,
+    ) -> Result<()> {
+        let mut by_nodes: HashMap<(NodeIndex, NodeIndex), i32> = HashMap::new();
+        let mut link = 1;
+        while link <= self.get_count(CountType::LinkCount)? {
+            if !self.skeleton_pipe(link, options)? {
+                link += 1;
+                continue;
+            }
+            let (from, to) = self.get_link_nodes(link)?;
+            let key = (from.min(to), from.max(to));
+            let Some(&keep) = by_nodes.get(&key) else {
+                by_nodes.insert(key, link);
+                link += 1;
+                continue;
+            };
+
+            let (length, _, roughness, minor_loss) = self.pipe_props(keep)?;
+            let diameter = parallel_equivalent_diameter(
+                self.head_loss_type()?,
+                self.pipe_props(keep)?,
+                self.pipe_props(link)?,
+            );
+            let kept_pipe_id = self.get_link_id(keep)?;
+            let removed_pipe_id = self.get_link_id(link)?;
+            self.set_pipe_data(keep, length, diameter, roughness, minor_loss)?;
+            // Every pipe in `by_nodes` lies below `link`, so none is shifted
+            // by the deletion, and `link` now holds the next link.
+            self.delete_link(link, ActionCodeType::Conditional)?;
+            if diameter > options.max_diameter {
+                by_nodes.remove(&key);
+            }
+            reductions.push(Reduction::ParallelMerged {
+                kept_pipe_id,
+                removed_pipe_id,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Link indices connected to each node, built once per skeletonization pass
+/// and kept in step with the engine's reindexing as elements are deleted;
+/// entry 0 is unused.
+struct Adjacency(Vec<Vec<i32>>);
+
+impl Adjacency {
+    /// Drops `link` and shifts the links above it down by one.
+    fn remove_link(&mut self, link: i32) {
+        for links in &mut self.0 {
+            links.retain(|&l| l != link);
+            for l in links.iter_mut().filter(|l| **l > link) {
+                *l -= 1;
+            }
+        }
+    }
+
+    /// Drops `node` and shifts the nodes above it down by one.
+    fn remove_node(&mut self, node: NodeIndex) {
+        self.0.remove(node.0 as usize);
+    }
+
+    fn add_link(&mut self, node: NodeIndex, link: i32) {
+        self.0[node.0 as usize].push(link);
+    }
+}
+
+/// Pipe with the diameter of `keep` and the combined length of both pipes whose
+/// head loss equals that of `keep` and `other` in series.
+///
+/// Under Darcy-Weisbach the friction factor depends on flow as well as
+/// roughness, so both pipes are assumed to share one: the roughness of `keep`
+/// is kept and that of `other` is ignored.
+fn series_equivalent(form: HeadLossType, keep: PipeProps, other: PipeProps) -> PipeProps {
+    let (l1, d, c1, k1) = keep;
+    let (l2, d2, c2, k2) = other;
+    let minor_loss = k1 + k2 * (d / d2).powi(4);
+    match form {
+        HeadLossType::DarcyWeisbach => (l1 + l2 * (d / d2).powi(5), d, c1, minor_loss),
+        HeadLossType::ChezyManning => {
+            let r = |l: f64, dd: f64, n: f64| n * n * l / dd.powf(16.0 / 3.0);
+            let length = l1 + l2;
+            let n = ((r(l1, d, c1) + r(l2, d2, c2)) * d.powf(16.0 / 3.0) / length).sqrt();
+            (length, d, n, minor_loss)
+        }
+        _ => {
+            let r = |l: f64, dd: f64, c: f64| l / (c.powf(1.852) * dd.powf(4.87));
+            let length = l1 + l2;
+            let c = (length / (d.powf(4.87) * (r(l1, d, c1) + r(l2, d2, c2)))).powf(1.0 / 1.852);
+            (length, d, c, minor_loss)
+        }
+    }
+}
+
+/// Diameter that, with the length and roughness of `keep`, carries the combined
+/// flow of `keep` and `other` in parallel at the same head loss.
+fn parallel_equivalent_diameter(form: HeadLossType, keep: PipeProps, other: PipeProps) -> f64 {
+    let (l1, d1, c1, _) = keep;
+    let (l2, d2, c2, _) = other;
+    match form {
+        HeadLossType::DarcyWeisbach => {
+            let k = |l: f64, d: f64| d.powf(2.5) / l.sqrt();
+            ((k(l1, d1) + k(l2, d2)) * l1.sqrt()).powf(1.0 / 2.5)
+        }
+        HeadLossType::ChezyManning => {
+            let k = |l: f64, d: f64, n: f64| d.powf(8.0 / 3.0) / (n * l.sqrt());
+            ((k(l1, d1, c1) + k(l2, d2, c2)) * c1 * l1.sqrt()).powf(3.0 / 8.0)
+        }
+        _ => {
+            let k = |l: f64, d: f64, c: f64| c * d.powf(2.63) / l.powf(0.54);
+            ((k(l1, d1, c1) + k(l2, d2, c2)) * l1.powf(0.54) / c1).powf(1.0 / 2.63)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use rstest::rstest;
+
+    fn only(trim: bool, series: bool, parallel: bool) -> SkeletonOptions {
+        SkeletonOptions {
+            max_diameter: 12.0,
+            trim_branches: trim,
+            merge_series: series,
+            merge_parallel: parallel,
+            ..Default::default()
+        }
+    }
+
+    fn total_demand(ph: &EPANET, node: NodeIndex) -> f64 {
+        (1..=ph.get_demand_count(node).unwrap())
+            .map(|c| ph.get_base_demand(node, c).unwrap())
+            .sum()
+    }
+
+    #[rstest]
+    fn test_trim_branches(ph: EPANET) {
+        // Dead-end branch 32 -> D1 -> D2
+        for (id, from, demand) in [("D1", "32", 10.0), ("D2", "D1", 5.0)] {
+            let node = ph.add_node(id, NodeType::Junction).unwrap();
+            ph.set_junction_data(node, 710.0, demand, "1").unwrap();
+            let pipe = ph
+                .add_link(&format!("P{id}"), LinkType::Pipe, from, id)
+                .unwrap();
+            ph.set_pipe_data(pipe, 500.0, 4.0, 100.0, 0.0).unwrap();
+        }
+        let node_32 = ph.get_node_index("32").unwrap();
+        let demand_32 = total_demand(&ph, node_32);
+        let nodes = ph.get_count(CountType::NodeCount).unwrap();
+
+        let report = ph.skeletonize(&only(true, false, false)).unwrap();
+        assert_eq!(report.reductions.len(), 2);
+        assert_eq!(
+            report.reductions[0],
+            Reduction::BranchTrimmed {
+                node_id: "D2".into(),
+                pipe_id: "PD2".into(),
+                demand_moved_to: "D1".into(),
+                demand: 5.0,
+            }
+        );
+        assert_eq!(ph.get_count(CountType::NodeCount).unwrap(), nodes - 2);
+        let node_32 = ph.get_node_index("32").unwrap();
+        assert!(approx_eq(
+            total_demand(&ph, node_32),
+            demand_32 + 15.0,
+            1e-9
+        ));
+        assert!(report.within_tolerance, "{report:?}");
+    }
+
+    #[rstest]
+    fn test_merge_series(ph: EPANET) {
+        let mut options = only(false, true, false);
+        options.pressure_tolerance = 5.0;
+        options.keep_nodes = vec!["32".into()];
+        let links = ph.get_count(CountType::LinkCount).unwrap();
+
+        let report = ph.skeletonize(&options).unwrap();
+        assert!(!report.reductions.is_empty());
+        assert!(report
+            .reductions
+            .iter()
+            .all(|r| matches!(r, Reduction::SeriesMerged { node_id, .. } if node_id != "32")));
+        assert!(ph.get_node_index("32").is_ok());
+        assert_eq!(
+            ph.get_count(CountType::LinkCount).unwrap(),
+            links - report.reductions.len() as i32
+        );
+        assert!(report.within_tolerance, "{report:?}");
+    }
+
+    #[rstest]
+    fn test_merge_parallel(ph: EPANET) {
+        let pipe = ph.add_link("P12B", LinkType::Pipe, "12", "13").unwrap();
+        let original = ph.get_link_index("12").unwrap();
+        let (length, diameter, roughness, _) = ph.pipe_props(original.0).unwrap();
+        ph.set_pipe_data(pipe, length, diameter, roughness, 0.0)
+            .unwrap();
+
+        let report = ph.skeletonize(&only(false, false, true)).unwrap();
+        assert_eq!(
+            report.reductions,
+            vec![Reduction::ParallelMerged {
+                kept_pipe_id: "12".into(),
+                removed_pipe_id: "P12B".into(),
+            }]
+        );
+        let merged = ph.get_link_value(ph.get_link_index("12").unwrap(), LinkProperty::Diameter);
+        assert!(approx_eq(
+            merged.unwrap(),
+            diameter * 2f64.powf(1.0 / 2.63),
+            1e-3
+        ));
+        assert!(report.within_tolerance, "{report:?}");
+    }
+
+    #[rstest]
+    fn test_skeletonize_all(ph: EPANET) {
+        let node = ph.add_node("D1", NodeType::Junction).unwrap();
+        ph.set_junction_data(node, 710.0, 10.0, "1").unwrap();
+        let pipe = ph.add_link("PD1", LinkType::Pipe, "32", "D1").unwrap();
+        ph.set_pipe_data(pipe, 500.0, 4.0, 100.0, 0.0).unwrap();
+        let pipe = ph.add_link("P12B", LinkType::Pipe, "12", "13").unwrap();
+        ph.set_pipe_data(pipe, 5280.0, 10.0, 100.0, 0.0).unwrap();
+        let nodes = ph.get_count(CountType::NodeCount).unwrap();
+        let links = ph.get_count(CountType::LinkCount).unwrap();
+
+        let mut options = only(true, true, true);
+        options.max_diameter = 24.0;
+        let report = ph.skeletonize(&options).unwrap();
+        let count = |f: fn(&Reduction) -> bool| report.reductions.iter().filter(|r| f(r)).count();
+        let trimmed = count(|r| matches!(r, Reduction::BranchTrimmed { .. }));
+        let series = count(|r| matches!(r, Reduction::SeriesMerged { .. }));
+        let parallel = count(|r| matches!(r, Reduction::ParallelMerged { .. }));
+        assert!(trimmed >= 1 && series >= 1 && parallel >= 1, "{report:?}");
+
+        // Every reduction removes one link; trims and series merges one node
+        assert_eq!(
+            ph.get_count(CountType::LinkCount).unwrap(),
+            links - report.reductions.len() as i32
+        );
+        assert_eq!(
+            ph.get_count(CountType::NodeCount).unwrap(),
+            nodes - (trimmed + series) as i32
+        );
+        for link in 1..=ph.get_count(CountType::LinkCount).unwrap() {
+            let (from, to) = ph.get_link_nodes(link).unwrap();
+            assert_ne!(from, to);
+        }
+    }
+
+    #[test]
+    fn test_series_equivalent_identical_pipes() {
+        let pipe = (1000.0, 12.0, 100.0, 0.5);
+        for form in [
+            HeadLossType::HazenWilliams,
+            HeadLossType::DarcyWeisbach,
+            HeadLossType::ChezyManning,
+        ] {
+            let (length, diameter, roughness, minor_loss) = series_equivalent(form, pipe, pipe);
+            assert!(approx_eq(length, 2000.0, 1e-9));
+            assert_eq!(diameter, 12.0);
+            assert!(approx_eq(roughness, 100.0, 1e-9));
+            assert!(approx_eq(minor_loss, 1.0, 1e-12));
+        }
+    }
+}
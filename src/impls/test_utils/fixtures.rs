@@ -1,4 +1,5 @@
 use crate::types::analysis::InitHydOption;
+use crate::types::index::NodeIndex;
 use crate::types::node::NodeType::Junction;
 use crate::types::options::{FlowUnits, HeadLossType};
 use crate::EPANET;
@@ -53,7 +54,7 @@ pub fn after_step(ph: EPANET) -> EPANET {
             .step_q()
             .expect("Failed to step through quality simulation");
         // Intentionally silent — no println to avoid noisy test output
-        if t_step <= 0 || t >= t_stop {
+        if t_step == 0 || t >= t_stop {
             break;
         }
     }
@@ -61,7 +62,7 @@ pub fn after_step(ph: EPANET) -> EPANET {
 }
 
 #[fixture]
-pub fn ph_single_node(ph_close: EPANET) -> (EPANET, i32) {
+pub fn ph_single_node(ph_close: EPANET) -> (EPANET, NodeIndex) {
     let node_id = ph_close.add_node("CUB_SCOUT_QUONSET_HUT", Junction).expect("Failed to add node");
     (ph_close, node_id)
 }
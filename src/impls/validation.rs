@@ -0,0 +1,492 @@
+//! Model validation API methods for EPANET.
+//!
+//! This module contains a lint engine that checks the network for connectivity
+//! problems, implausible or inconsistent element data and dangling references,
+//! without running a simulation.
+
+use crate::epanet_error::*;
+use crate::types::control::ControlType;
+use crate::types::index::{NodeIndex, PatternIndex};
+use crate::types::link::{LinkKind, LinkProperty};
+use crate::types::node::{NodeKind, NodeProperty};
+use crate::types::options::{self, HeadLossType};
+use crate::types::rule::PremiseObject;
+use crate::types::validation::*;
+use crate::types::{CountType, ObjectType};
+use crate::EPANET;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::ops::RangeInclusive;
+
+/// Plausible Hazen-Williams C factors.
+const HAZEN_WILLIAMS_C: RangeInclusive<f64> = 40.0..=160.0;
+/// Plausible Darcy-Weisbach roughness heights (millifeet or mm).
+const DARCY_WEISBACH_E: RangeInclusive<f64> = 0.0..=15.0;
+/// Plausible Manning's n values.
+const MANNING_N: RangeInclusive<f64> = 0.008..=0.035;
+
+/// ## Validation APIs
+impl EPANET {
+    /// Checks the model for problems and returns every finding, in the order the
+    /// checks run: connectivity, node data, link data, patterns, then controls
+    /// and rules.
+    ///
+    /// The checks are:
+    /// - nodes with no links (error) or no path to a tank or reservoir (warning);
+    /// - nodes sharing coordinates with an earlier node (warning);
+    /// - tanks whose minimum level exceeds the maximum (error) or whose initial
+    ///   level lies outside them (error);
+    /// - pipes with a non-positive length or diameter, and valves with a
+    ///   non-positive diameter (error);
+    /// - pipe roughness outside the plausible range for the head loss formula
+    ///   (warning), or non-positive Hazen-Williams and Manning values (error);
+    /// - pumps without a head curve: constant-power pumps (info) and pumps with no
+    ///   power either (error);
+    /// - valves joined by a node with no other links (warning);
+    /// - pipes connecting the same pair of nodes as an earlier pipe (warning);
+    /// - demand, head and energy patterns that are referenced but have no factors
+    ///   (error) or only zero factors (warning);
+    /// - controls and rules referring to nodes or links that do not exist (error).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if the model cannot be read.
+    pub fn validate(&self) -> Result<Vec<Finding>> {
+        let mut findings = Vec::new();
+        let mut report = |severity, kind, object, id: &str, message: String| {
+            findings.push(Finding {
+                severity,
+                kind,
+                object,
+                id: id.to_string(),
+                message,
+            })
+        };
+        let nodes = self.nodes()?;
+        let links = self.links()?;
+
+        // Connectivity, ignoring link status.
+        let mut adjacent = vec![Vec::new(); nodes.len()];
+        for (i, link) in links.iter().enumerate() {
+            adjacent[link.from_node.offset()].push(i);
+            adjacent[link.to_node.offset()].push(i);
+        }
+        let mut reached = vec![false; nodes.len()];
+        let mut queue: VecDeque<usize> = nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| !matches!(node.kind, NodeKind::Junction(_)))
+            .map(|(i, _)| i)
+            .collect();
+        for &i in &queue {
+            reached[i] = true;
+        }
+        while let Some(i) = queue.pop_front() {
+            for &l in &adjacent[i] {
+                let (from, to) = links[l].nodes();
+                for next in [from.offset(), to.offset()] {
+                    if !reached[next] {
+                        reached[next] = true;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        for (i, node) in nodes.iter().enumerate() {
+            if adjacent[i].is_empty() {
+                report(
+                    Severity::Error,
+                    FindingKind::UnconnectedNode,
+                    ObjectType::Node,
+                    &node.id,
+                    format!("node {} is not connected to any link", node.id),
+                );
+            } else if !reached[i] {
+                report(
+                    Severity::Warning,
+                    FindingKind::NoSourcePath,
+                    ObjectType::Node,
+                    &node.id,
+                    format!("node {} has no path to a tank or reservoir", node.id),
+                );
+            }
+        }
+
+        // Node data.
+        let mut positions: HashMap<(u64, u64), &str> = HashMap::new();
+        for node in &nodes {
+            if let Ok((x, y)) = self.get_coordinates(node.index()) {
+                match positions.get(&(x.to_bits(), y.to_bits())) {
+                    Some(other) => report(
+                        Severity::Warning,
+                        FindingKind::DuplicateCoordinates,
+                        ObjectType::Node,
+                        &node.id,
+                        format!("node {} has the same coordinates as node {other}", node.id),
+                    ),
+                    None => {
+                        positions.insert((x.to_bits(), y.to_bits()), &node.id);
+                    }
+                }
+            }
+            if let NodeKind::Tank(tank) = &node.kind {
+                if tank.min_level > tank.max_level {
+                    report(
+                        Severity::Error,
+                        FindingKind::TankLevelRange,
+                        ObjectType::Node,
+                        &node.id,
+                        format!(
+                            "tank {} has a minimum level of {} above its maximum level of {}",
+                            node.id, tank.min_level, tank.max_level
+                        ),
+                    );
+                } else if !(tank.min_level..=tank.max_level).contains(&tank.init_level) {
+                    report(
+                        Severity::Error,
+                        FindingKind::TankInitialLevel,
+                        ObjectType::Node,
+                        &node.id,
+                        format!(
+                            "tank {} has an initial level of {} outside {} to {}",
+                            node.id, tank.init_level, tank.min_level, tank.max_level
+                        ),
+                    );
+                }
+            }
+        }
+
+        // Link data.
+        let head_loss = self.head_loss_type()?;
+        let mut node_pairs: HashMap<(NodeIndex, NodeIndex), &str> = HashMap::new();
+        for link in &links {
+            let id = link.id.as_str();
+            match &link.kind {
+                LinkKind::Pipe(pipe) | LinkKind::CvPipe(pipe) => {
+                    if pipe.length <= 0.0 {
+                        report(
+                            Severity::Error,
+                            FindingKind::NonPositiveLength,
+                            ObjectType::Link,
+                            id,
+                            format!("pipe {id} has a length of {}", pipe.length),
+                        );
+                    }
+                    if pipe.diameter <= 0.0 {
+                        report(
+                            Severity::Error,
+                            FindingKind::NonPositiveDiameter,
+                            ObjectType::Link,
+                            id,
+                            format!("pipe {id} has a diameter of {}", pipe.diameter),
+                        );
+                    }
+                    let (range, formula) = match head_loss {
+                        HeadLossType::HazenWilliams => (HAZEN_WILLIAMS_C, "Hazen-Williams"),
+                        HeadLossType::DarcyWeisbach => (DARCY_WEISBACH_E, "Darcy-Weisbach"),
+                        HeadLossType::ChezyManning => (MANNING_N, "Chezy-Manning"),
+                    };
+                    if !range.contains(&pipe.roughness) {
+                        let invalid = pipe.roughness < 0.0
+                            || (pipe.roughness == 0.0 && head_loss != HeadLossType::DarcyWeisbach);
+                        report(
+                            if invalid {
+                                Severity::Error
+                            } else {
+                                Severity::Warning
+                            },
+                            FindingKind::ImplausibleRoughness,
+                            ObjectType::Link,
+                            id,
+                            format!(
+                                "pipe {id} has a {formula} roughness of {} outside {} to {}",
+                                pipe.roughness,
+                                range.start(),
+                                range.end()
+                            ),
+                        );
+                    }
+                    let pair = (
+                        link.from_node.min(link.to_node),
+                        link.from_node.max(link.to_node),
+                    );
+                    match node_pairs.get(&pair) {
+                        Some(other) => report(
+                            Severity::Warning,
+                            FindingKind::DuplicatePipe,
+                            ObjectType::Link,
+                            id,
+                            format!("pipe {id} connects the same nodes as pipe {other}"),
+                        ),
+                        None => {
+                            node_pairs.insert(pair, id);
+                        }
+                    }
+                }
+                LinkKind::Pump(pump) => {
+                    if pump.head_curve_index.is_none_or(|curve| curve.0 <= 0) {
+                        let (severity, message) = match pump.power > 0.0 {
+                            true => (
+                                Severity::Info,
+                                format!("pump {id} has no head curve and runs at constant power"),
+                            ),
+                            false => (
+                                Severity::Error,
+                                format!("pump {id} has neither a head curve nor a power rating"),
+                            ),
+                        };
+                        report(
+                            severity,
+                            FindingKind::PumpWithoutCurve,
+                            ObjectType::Link,
+                            id,
+                            message,
+                        );
+                    }
+                }
+                LinkKind::Valve(valve) => {
+                    if valve.diameter <= 0.0 {
+                        report(
+                            Severity::Error,
+                            FindingKind::NonPositiveDiameter,
+                            ObjectType::Link,
+                            id,
+                            format!("valve {id} has a diameter of {}", valve.diameter),
+                        );
+                    }
+                }
+            }
+        }
+        for (i, node) in nodes.iter().enumerate() {
+            if let [a, b] = adjacent[i][..] {
+                let is_valve = |l: usize| matches!(links[l].kind, LinkKind::Valve(_));
+                if is_valve(a) && is_valve(b) {
+                    let (first, second) = (&links[a.min(b)].id, &links[a.max(b)].id);
+                    report(
+                        Severity::Warning,
+                        FindingKind::ValvesInSeries,
+                        ObjectType::Link,
+                        second,
+                        format!(
+                            "valves {first} and {second} are in series at node {}",
+                            node.id
+                        ),
+                    );
+                }
+            }
+        }
+
+        // Referenced patterns.
+        let mut patterns = BTreeSet::new();
+        let default_pattern = PatternIndex(self.get_option(options::Option::DemandPattern)? as i32);
+        for node in &nodes {
+            match node.kind {
+                NodeKind::Junction(_) => {
+                    for category in 1..=self.get_demand_count(node.index())? {
+                        match self.get_demand_pattern(node.index(), category)? {
+                            PatternIndex(0) => patterns.insert(default_pattern),
+                            pattern => patterns.insert(pattern),
+                        };
+                    }
+                }
+                NodeKind::Reservoir(_) => {
+                    patterns
+                        .insert(PatternIndex(self.get_node_value(node.index(), NodeProperty::Pattern)? as i32));
+                }
+                NodeKind::Tank(_) => {}
+            }
+        }
+        for link in &links {
+            if let LinkKind::Pump(pump) = &link.kind {
+                patterns.extend(pump.energy_pattern_index);
+                let speed = self.get_link_value(link.index(), LinkProperty::LinkPattern)?;
+                patterns.insert(PatternIndex(speed as i32));
+            }
+        }
+        for pattern in patterns.into_iter().filter(|p| p.0 > 0) {
+            let id = self.get_pattern_id(pattern)?;
+            let length = self.get_pattern_length(pattern)?;
+            if length == 0 {
+                report(
+                    Severity::Error,
+                    FindingKind::EmptyPattern,
+                    ObjectType::TimePattern,
+                    &id,
+                    format!("pattern {id} is referenced but has no factors"),
+                );
+            } else if (1..=length)
+                .map(|period| self.get_pattern_value(pattern, period))
+                .collect::<Result<Vec<_>>>()?
+                .iter()
+                .all(|factor| *factor == 0.0)
+            {
+                report(
+                    Severity::Warning,
+                    FindingKind::EmptyPattern,
+                    ObjectType::TimePattern,
+                    &id,
+                    format!("pattern {id} is referenced but all its factors are zero"),
+                );
+            }
+        }
+
+        // Controls and rules.
+        let node_count = self.get_count(CountType::NodeCount)?;
+        let link_count = self.get_count(CountType::LinkCount)?;
+        for (i, control) in self.controls()?.iter().enumerate() {
+            let id = (i + 1).to_string();
+            if !(1..=link_count).contains(&control.link_index.0) {
+                report(
+                    Severity::Error,
+                    FindingKind::DanglingReference,
+                    ObjectType::Control,
+                    &id,
+                    format!("control {id} refers to missing link {}", control.link_index),
+                );
+            }
+            let level = matches!(
+                control.control_type,
+                ControlType::LowLevel | ControlType::HiLevel
+            );
+            if level && !(1..=node_count).contains(&control.node_index.0) {
+                report(
+                    Severity::Error,
+                    FindingKind::DanglingReference,
+                    ObjectType::Control,
+                    &id,
+                    format!("control {id} refers to missing node {}", control.node_index),
+                );
+            }
+        }
+        for rule in self.rules()? {
+            for premise in &rule.premises {
+                let (kind, index, count) = match premise.object {
+                    PremiseObject::Node(node) => ("node", node.0, node_count),
+                    PremiseObject::Link(link) => ("link", link.0, link_count),
+                    PremiseObject::System => continue,
+                };
+                if !(1..=count).contains(&index) {
+                    report(
+                        Severity::Error,
+                        FindingKind::DanglingReference,
+                        ObjectType::Rule,
+                        &rule.rule_id,
+                        format!(
+                            "rule {} refers to missing {kind} {}",
+                            rule.rule_id, index
+                        ),
+                    );
+                }
+            }
+            for action in rule.then_actions.iter().chain(&rule.else_actions) {
+                if !(1..=link_count).contains(&action.link_index.0) {
+                    report(
+                        Severity::Error,
+                        FindingKind::DanglingReference,
+                        ObjectType::Rule,
+                        &rule.rule_id,
+                        format!(
+                            "rule {} acts on missing link {}",
+                            rule.rule_id, action.link_index
+                        ),
+                    );
+                }
+            }
+        }
+        Ok(findings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use crate::types::link::LinkType;
+    use crate::types::node::NodeType;
+    use rstest::rstest;
+
+    fn kinds(findings: &[Finding]) -> Vec<(FindingKind, &str)> {
+        findings.iter().map(|f| (f.kind, f.id.as_str())).collect()
+    }
+
+    #[rstest]
+    fn test_validate_net1(ph: EPANET) {
+        let findings = ph.validate().unwrap();
+        assert!(
+            findings.iter().all(|f| f.severity < Severity::Warning),
+            "{findings:?}"
+        );
+    }
+
+    #[rstest]
+    fn test_validate_findings(ph: EPANET) {
+        // An isolated junction at the same place as junction 10.
+        let lonely = ph.add_node("LONELY", NodeType::Junction).unwrap();
+        ph.set_coordinates(lonely, 20.0, 70.0).unwrap();
+        // A parallel pipe with a roughness meant for Darcy-Weisbach.
+        let twin = ph.add_link("TWIN", LinkType::Pipe, "10", "11").unwrap();
+        ph.set_pipe_data(twin, 100.0, 12.0, 0.5, 0.0).unwrap();
+        // Two valves in series through a new junction.
+        ph.add_node("MID", NodeType::Junction).unwrap();
+        ph.add_link("V1", LinkType::Prv, "21", "MID").unwrap();
+        ph.add_link("V2", LinkType::Tcv, "MID", "22").unwrap();
+        // A pump with neither a curve nor a power rating.
+        ph.add_link("P2", LinkType::Pump, "9", "10").unwrap();
+        // An all-zero demand pattern.
+        let pattern = ph.get_pattern_index("1").unwrap();
+        ph.set_pattern(pattern, &[0.0; 4]).unwrap();
+
+        let findings = ph.validate().unwrap();
+        let found = kinds(&findings);
+        assert!(found.contains(&(FindingKind::UnconnectedNode, "LONELY")));
+        assert!(found.contains(&(FindingKind::DuplicateCoordinates, "LONELY")));
+        assert!(found.contains(&(FindingKind::DuplicatePipe, "TWIN")));
+        assert!(found.contains(&(FindingKind::ImplausibleRoughness, "TWIN")));
+        assert!(found.contains(&(FindingKind::ValvesInSeries, "V2")));
+        assert!(found.contains(&(FindingKind::PumpWithoutCurve, "P2")));
+        assert!(found.contains(&(FindingKind::EmptyPattern, "1")));
+        let lonely = findings
+            .iter()
+            .find(|f| f.kind == FindingKind::UnconnectedNode)
+            .unwrap();
+        assert_eq!(lonely.severity, Severity::Error);
+        assert_eq!(lonely.object, ObjectType::Node);
+        assert_eq!(
+            lonely.to_string(),
+            "error: node LONELY is not connected to any link"
+        );
+        assert_eq!(
+            ph.get_link_value(ph.get_link_index("TWIN").unwrap(), LinkProperty::Roughness)
+                .unwrap(),
+            0.5
+        );
+    }
+
+    #[rstest]
+    fn test_validate_pump_speed_pattern(ph: EPANET) {
+        ph.add_pattern("SPEED").unwrap();
+        let pattern = ph.get_pattern_index("SPEED").unwrap();
+        ph.set_pattern(pattern, &[0.0; 2]).unwrap();
+        // Unreferenced patterns are not checked.
+        assert!(!kinds(&ph.validate().unwrap()).contains(&(FindingKind::EmptyPattern, "SPEED")));
+
+        let pump = ph.get_link_index("9").unwrap();
+        ph.set_link_value(pump, LinkProperty::LinkPattern, pattern.0 as f64)
+            .unwrap();
+        assert!(kinds(&ph.validate().unwrap()).contains(&(FindingKind::EmptyPattern, "SPEED")));
+    }
+
+    #[rstest]
+    fn test_validate_no_source_path(ph_close: EPANET) {
+        ph_close.add_node("A", NodeType::Junction).unwrap();
+        ph_close.add_node("B", NodeType::Junction).unwrap();
+        ph_close.add_link("AB", LinkType::Pipe, "A", "B").unwrap();
+        let findings = ph_close.validate().unwrap();
+        assert_eq!(
+            kinds(&findings),
+            vec![
+                (FindingKind::NoSourcePath, "A"),
+                (FindingKind::NoSourcePath, "B")
+            ]
+        );
+    }
+}
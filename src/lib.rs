@@ -105,9 +105,11 @@
 pub mod types;
 use bindings as ffi;
 use epanet_error::*;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::ffi::CString;
 use std::os::raw::c_void;
+use std::sync::atomic::{AtomicU64, Ordering};
+use types::handle::HandleRegistry;
 use types::options::{FlowUnits, HeadLossType};
 use types::report::ReportCallback;
 
@@ -147,8 +149,21 @@ pub struct EPANET {
     /// Whether the project has been closed by the C API (e.g. via `EN_runproject`).
     /// When true, `Drop` skips calling `EN_close` to avoid double-free.
     closed: Cell<bool>,
+
+    /// IDs behind the [`NodeHandle`](types::handle::NodeHandle)s and
+    /// [`LinkHandle`](types::handle::LinkHandle)s issued for this project, kept
+    /// up to date by the delete and rename wrappers.
+    pub(crate) handles: RefCell<HandleRegistry>,
+
+    /// Identifier unique to this project within the process, recorded in the
+    /// handles it issues. Unlike the address of the C project, it is never
+    /// reused after the project is dropped.
+    pub(crate) project_id: u64,
 }
 
+/// Source of [`EPANET::project_id`]s.
+static NEXT_PROJECT_ID: AtomicU64 = AtomicU64::new(1);
+
 // Manual Debug implementation since *mut c_void doesn't implement Debug nicely
 impl std::fmt::Debug for EPANET {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -240,6 +255,8 @@ impl EPANET {
             ph,
             report_callback_ptr: None,
             closed: Cell::new(false),
+            handles: RefCell::default(),
+            project_id: NEXT_PROJECT_ID.fetch_add(1, Ordering::Relaxed),
         })
     }
 
@@ -286,6 +303,8 @@ impl EPANET {
             ph,
             report_callback_ptr: None,
             closed: Cell::new(false),
+            handles: RefCell::default(),
+            project_id: NEXT_PROJECT_ID.fetch_add(1, Ordering::Relaxed),
         })
     }
 
@@ -314,6 +333,8 @@ impl EPANET {
             ph,
             report_callback_ptr: None,
             closed: Cell::new(false),
+            handles: RefCell::default(),
+            project_id: NEXT_PROJECT_ID.fetch_add(1, Ordering::Relaxed),
         })
     }
 }
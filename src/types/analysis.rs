@@ -917,12 +917,7 @@ mod tests {
             .run_h()
             .expect("Failed to run hydraulics");
 
-        loop {
-            match hyd.next_h().expect("Failed to step hydraulics") {
-                StepResult::Continue { .. } => {}
-                StepResult::Done { .. } => break,
-            }
-        }
+        while let StepResult::Continue { .. } = hyd.next_h().expect("Failed to step hydraulics") {}
 
         let mut qual = hyd
             .close_h()
@@ -0,0 +1,142 @@
+//! Columnar network snapshot types: [`NetworkArrays`] and [`CsrMatrix`].
+//!
+//! [`EPANET::network_arrays`](crate::EPANET::network_arrays) reads the network
+//! into one contiguous vector per attribute, aligned by element: entry `i` of
+//! every node vector describes the node with index `i + 1`, and likewise for
+//! links. Element references inside the snapshot (link end nodes, matrix
+//! columns) are such 0-based positions, so they index the vectors directly.
+
+use crate::epanet_error::*;
+
+/// Struct-of-arrays snapshot of the network data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkArrays {
+    /// Node IDs.
+    pub node_ids: Vec<String>,
+    /// Node types as [`NodeType`](crate::types::node::NodeType) codes.
+    pub node_types: Vec<i32>,
+    /// Node elevations.
+    pub elevations: Vec<f64>,
+    /// Primary base demands; zero for tanks and reservoirs.
+    pub base_demands: Vec<f64>,
+    /// Node X coordinates, NaN for nodes without coordinates.
+    pub x: Vec<f64>,
+    /// Node Y coordinates, NaN for nodes without coordinates.
+    pub y: Vec<f64>,
+    /// Link IDs.
+    pub link_ids: Vec<String>,
+    /// Link types as [`LinkType`](crate::types::link::LinkType) codes.
+    pub link_types: Vec<i32>,
+    /// 0-based position of each link's start node.
+    pub start_nodes: Vec<i32>,
+    /// 0-based position of each link's end node.
+    pub end_nodes: Vec<i32>,
+    /// Link lengths; zero for pumps and valves.
+    pub lengths: Vec<f64>,
+    /// Link diameters; zero for pumps.
+    pub diameters: Vec<f64>,
+    /// Link roughness coefficients; zero for pumps and valves.
+    pub roughness: Vec<f64>,
+    /// Initial link statuses as [`LinkStatusType`](crate::types::link::LinkStatusType) codes.
+    pub statuses: Vec<i32>,
+    /// Node-link incidence matrix, one row per node and one column per link:
+    /// -1 where a link starts and +1 where it ends.
+    pub incidence: CsrMatrix,
+}
+
+impl NetworkArrays {
+    /// Number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.node_ids.len()
+    }
+
+    /// Number of links.
+    pub fn link_count(&self) -> usize {
+        self.link_ids.len()
+    }
+}
+
+/// A sparse matrix in compressed sparse row form, with 0-based `i32` indices
+/// as used by SciPy and most sparse solvers.
+///
+/// The entries of row `r` are `values[row_offsets[r]..row_offsets[r + 1]]`, in
+/// the columns given by the same range of `columns`, sorted by column.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CsrMatrix {
+    /// Number of rows.
+    pub rows: usize,
+    /// Number of columns.
+    pub cols: usize,
+    /// Start of each row in `columns` and `values`, with a final entry equal
+    /// to the number of stored entries.
+    pub row_offsets: Vec<i32>,
+    /// Column of each stored entry.
+    pub columns: Vec<i32>,
+    /// Value of each stored entry.
+    pub values: Vec<f64>,
+}
+
+impl CsrMatrix {
+    /// Builds a matrix from `(row, column, value)` entries in any order.
+    /// Entries at the same position are summed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 250 if an entry lies outside the `rows` x `cols` matrix.
+    pub fn from_triplets(
+        rows: usize,
+        cols: usize,
+        mut entries: Vec<(usize, usize, f64)>,
+    ) -> Result<Self> {
+        if let Some((row, col, _)) = entries
+            .iter()
+            .find(|(row, col, _)| *row >= rows || *col >= cols)
+        {
+            return Err(EPANETError::from(250).with_context(format!(
+                "entry ({row}, {col}) outside a {rows} x {cols} matrix"
+            )));
+        }
+        entries.sort_by_key(|&(row, col, _)| (row, col));
+        let mut matrix = CsrMatrix {
+            rows,
+            cols,
+            row_offsets: vec![0; rows + 1],
+            columns: Vec::with_capacity(entries.len()),
+            values: Vec::with_capacity(entries.len()),
+        };
+        let mut last = None;
+        for (row, col, value) in entries {
+            if last == Some((row, col)) {
+                *matrix.values.last_mut().unwrap() += value;
+                continue;
+            }
+            last = Some((row, col));
+            matrix.row_offsets[row + 1] += 1;
+            matrix.columns.push(col as i32);
+            matrix.values.push(value);
+        }
+        for row in 0..rows {
+            matrix.row_offsets[row + 1] += matrix.row_offsets[row];
+        }
+        Ok(matrix)
+    }
+
+    /// Number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Columns and values of the stored entries of `row`.
+    pub fn row(&self, row: usize) -> (&[i32], &[f64]) {
+        let range = self.row_offsets[row] as usize..self.row_offsets[row + 1] as usize;
+        (&self.columns[range.clone()], &self.values[range])
+    }
+
+    /// The value at `(row, col)`, zero if no entry is stored there.
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        let (columns, values) = self.row(row);
+        columns
+            .binary_search(&(col as i32))
+            .map_or(0.0, |position| values[position])
+    }
+}
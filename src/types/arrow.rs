@@ -0,0 +1,187 @@
+//! Apache Arrow result types: [`RecordedResults`] and [`write_parquet`].
+//!
+//! Available with the `arrow` feature. Results are recorded in memory with
+//! [`EPANET::record_results`](crate::EPANET::record_results) and converted to a
+//! [`RecordBatch`] in long or wide layout; static network attributes come from
+//! [`EPANET::node_attributes_batch`](crate::EPANET::node_attributes_batch) and
+//! [`EPANET::link_attributes_batch`](crate::EPANET::link_attributes_batch).
+//! Every schema carries the project's units as metadata under the keys
+//! `epanet:flow_units`, `epanet:pressure_units`, `epanet:quality_type` and
+//! `epanet:quality_units`.
+
+use crate::epanet_error::*;
+use crate::types::export::{property_name, ExportLayout, TimeIndex};
+use crate::types::link::LinkProperty;
+use crate::types::node::NodeProperty;
+use arrow_array::{
+    ArrayRef, DurationSecondArray, Float64Array, Int64Array, RecordBatch, StringArray,
+};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+/// Node and link results recorded at each time step of a simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedResults {
+    /// Elapsed time of each recorded step, in seconds.
+    pub times: Vec<i64>,
+    /// IDs of the recorded nodes.
+    pub node_ids: Vec<String>,
+    /// IDs of the recorded links.
+    pub link_ids: Vec<String>,
+    /// Recorded node properties.
+    pub node_properties: Vec<NodeProperty>,
+    /// Recorded link properties.
+    pub link_properties: Vec<LinkProperty>,
+    /// Node values ordered by step, then node, then property.
+    pub node_values: Vec<f64>,
+    /// Link values ordered by step, then link, then property.
+    pub link_values: Vec<f64>,
+    /// Units metadata attached to every schema built from these results.
+    pub metadata: HashMap<String, String>,
+}
+
+impl RecordedResults {
+    /// Value of `node_properties[property]` at `node_ids[node]` for step `step`.
+    pub fn node_value(&self, step: usize, node: usize, property: usize) -> f64 {
+        let per_step = self.node_ids.len() * self.node_properties.len();
+        self.node_values[step * per_step + node * self.node_properties.len() + property]
+    }
+
+    /// Value of `link_properties[property]` at `link_ids[link]` for step `step`.
+    pub fn link_value(&self, step: usize, link: usize, property: usize) -> f64 {
+        let per_step = self.link_ids.len() * self.link_properties.len();
+        self.link_values[step * per_step + link * self.link_properties.len() + property]
+    }
+
+    /// Converts the results to a single [`RecordBatch`].
+    ///
+    /// The `time` column is `Int64` seconds for [`TimeIndex::Seconds`] and
+    /// `Duration(Second)` for [`TimeIndex::Clock`]. The long layout adds `element`,
+    /// `id`, `property` and `value` columns; the wide layout adds one `Float64`
+    /// column per element and property named `node:<id>:<property>` or
+    /// `link:<id>:<property>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 308 if Arrow rejects the batch.
+    pub fn to_record_batch(
+        &self,
+        layout: ExportLayout,
+        time_index: TimeIndex,
+    ) -> Result<RecordBatch> {
+        let steps = self.times.len();
+        let node_names: Vec<String> = self.node_properties.iter().map(property_name).collect();
+        let link_names: Vec<String> = self.link_properties.iter().map(property_name).collect();
+        let groups = [
+            ("node", &self.node_ids, &node_names, &self.node_values),
+            ("link", &self.link_ids, &link_names, &self.link_values),
+        ];
+
+        let mut fields = Vec::new();
+        let mut columns: Vec<ArrayRef> = Vec::new();
+        let time_values: Vec<i64> = match layout {
+            ExportLayout::Wide => self.times.clone(),
+            ExportLayout::Long => {
+                let per_step = self.node_ids.len() * self.node_properties.len()
+                    + self.link_ids.len() * self.link_properties.len();
+                self.times
+                    .iter()
+                    .flat_map(|t| std::iter::repeat_n(*t, per_step))
+                    .collect()
+            }
+        };
+        match time_index {
+            TimeIndex::Seconds => {
+                fields.push(Field::new("time", DataType::Int64, false));
+                columns.push(Arc::new(Int64Array::from(time_values)));
+            }
+            TimeIndex::Clock => {
+                fields.push(Field::new(
+                    "time",
+                    DataType::Duration(TimeUnit::Second),
+                    false,
+                ));
+                columns.push(Arc::new(DurationSecondArray::from(time_values)));
+            }
+        }
+
+        match layout {
+            ExportLayout::Wide => {
+                for (element, ids, names, values) in groups {
+                    let per_step = ids.len() * names.len();
+                    for (row, id) in ids.iter().enumerate() {
+                        for (column, name) in names.iter().enumerate() {
+                            let offset = row * names.len() + column;
+                            let series: Vec<f64> =
+                                (0..steps).map(|s| values[s * per_step + offset]).collect();
+                            fields.push(Field::new(
+                                format!("{element}:{id}:{name}"),
+                                DataType::Float64,
+                                false,
+                            ));
+                            columns.push(Arc::new(Float64Array::from(series)));
+                        }
+                    }
+                }
+            }
+            ExportLayout::Long => {
+                let (mut elements, mut ids_column, mut properties, mut value_column) =
+                    (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+                for step in 0..steps {
+                    for (element, ids, names, values) in groups {
+                        let per_step = ids.len() * names.len();
+                        for (row, id) in ids.iter().enumerate() {
+                            for (column, name) in names.iter().enumerate() {
+                                elements.push(element);
+                                ids_column.push(id.as_str());
+                                properties.push(name.as_str());
+                                value_column
+                                    .push(values[step * per_step + row * names.len() + column]);
+                            }
+                        }
+                    }
+                }
+                fields.push(Field::new("element", DataType::Utf8, false));
+                fields.push(Field::new("id", DataType::Utf8, false));
+                fields.push(Field::new("property", DataType::Utf8, false));
+                fields.push(Field::new("value", DataType::Float64, false));
+                columns.push(Arc::new(StringArray::from(elements)));
+                columns.push(Arc::new(StringArray::from(ids_column)));
+                columns.push(Arc::new(StringArray::from(properties)));
+                columns.push(Arc::new(Float64Array::from(value_column)));
+            }
+        }
+        record_batch(fields, columns, &self.metadata)
+    }
+}
+
+/// Writes `batch` to `out` as a Parquet file and returns the sink. The Arrow
+/// schema, including its metadata, is stored in the file.
+///
+/// # Errors
+///
+/// Returns error 308 if the file cannot be written.
+pub fn write_parquet<W: Write + Send>(batch: &RecordBatch, out: W) -> Result<W> {
+    let mut writer =
+        parquet::arrow::ArrowWriter::try_new(out, batch.schema(), None).map_err(arrow_error)?;
+    writer.write(batch).map_err(arrow_error)?;
+    writer.into_inner().map_err(arrow_error)
+}
+
+/// Builds a [`RecordBatch`] whose schema carries `metadata`.
+pub(crate) fn record_batch(
+    fields: Vec<Field>,
+    columns: Vec<ArrayRef>,
+    metadata: &HashMap<String, String>,
+) -> Result<RecordBatch> {
+    let schema = Schema::new_with_metadata(fields, metadata.clone());
+    RecordBatch::try_new(Arc::new(schema), columns).map_err(arrow_error)
+}
+
+/// Arrow and Parquet failures are reported as error 308 ("cannot save results to
+/// file") with the underlying message as context.
+pub(crate) fn arrow_error(error: impl std::fmt::Display) -> EPANETError {
+    EPANETError::from(308).with_context(error.to_string())
+}
@@ -4,6 +4,7 @@ use crate::{
         EN_ControlType_EN_HILEVEL, EN_ControlType_EN_LOWLEVEL, EN_ControlType_EN_TIMEOFDAY,
         EN_ControlType_EN_TIMER,
     },
+    types::index::{ControlIndex, LinkIndex, NodeIndex},
     EPANET,
 };
 use num_derive::FromPrimitive;
@@ -20,16 +21,16 @@ pub struct Control<'a> {
     /// Reference to the owning EPANET project.
     pub(crate) project: &'a EPANET,
     /// EPANET project index of the control.
-    pub(crate) index: i32,
+    pub(crate) index: ControlIndex,
     /// The control type. (see [`ControlType`])
     pub control_type: ControlType,
     /// The index of the link to control starting from 1.
-    pub link_index: i32,
+    pub link_index: LinkIndex,
     /// Control setting applied to the link.
     pub setting: f64,
     /// The index of the node used to control the link.
     /// 0 for [`ControlType::Timer`] and [`ControlType::TimeOfDay`].
-    pub node_index: i32,
+    pub node_index: NodeIndex,
     /// action level (tank level, junction pressure, or time in seconds) that triggers the control.
     pub level: f64,
     /// Enabled status of the control.
@@ -47,9 +48,9 @@ impl<'a> Control<'a> {
     /// - `level`: Level or pressure setpoint that triggers the control
     pub fn new_lowlevel(
         project: &'a EPANET,
-        link_index: i32,
+        link_index: impl Into<LinkIndex>,
         setting: f64,
-        node_index: i32,
+        node_index: impl Into<NodeIndex>,
         level: f64,
     ) -> crate::epanet_error::Result<Self> {
         project.add_control(ControlType::LowLevel, link_index, setting, node_index, level, true)
@@ -65,9 +66,9 @@ impl<'a> Control<'a> {
     /// - `level`: Level or pressure setpoint that triggers the control
     pub fn new_hilevel(
         project: &'a EPANET,
-        link_index: i32,
+        link_index: impl Into<LinkIndex>,
         setting: f64,
-        node_index: i32,
+        node_index: impl Into<NodeIndex>,
         level: f64,
     ) -> crate::epanet_error::Result<Self> {
         project.add_control(ControlType::HiLevel, link_index, setting, node_index, level, true)
@@ -82,7 +83,7 @@ impl<'a> Control<'a> {
     /// - `time`: Elapsed time in seconds when the control activates
     pub fn new_timer(
         project: &'a EPANET,
-        link_index: i32,
+        link_index: impl Into<LinkIndex>,
         setting: f64,
         time: f64,
     ) -> crate::epanet_error::Result<Self> {
@@ -98,7 +99,7 @@ impl<'a> Control<'a> {
     /// - `time_of_day`: Time of day in seconds since midnight when the control activates
     pub fn new_timeofday(
         project: &'a EPANET,
-        link_index: i32,
+        link_index: impl Into<LinkIndex>,
         setting: f64,
         time_of_day: f64,
     ) -> crate::epanet_error::Result<Self> {
@@ -106,7 +107,7 @@ impl<'a> Control<'a> {
     }
 
     /// Returns the EPANET project index of the control.
-    pub fn index(&self) -> i32 {
+    pub fn index(&self) -> ControlIndex {
         self.index
     }
 
@@ -121,6 +122,32 @@ impl<'a> Control<'a> {
     pub fn delete(self) -> crate::epanet_error::Result<()> {
         self.project.delete_control(self)
     }
+
+    /// Re-reads all cached fields from the engine, discarding local changes.
+    pub fn refresh(&mut self) -> crate::epanet_error::Result<()> {
+        *self = self.project.get_control_by_index(self.index)?;
+        Ok(())
+    }
+
+    /// Returns whether any cached field differs from the engine.
+    pub fn is_stale(&self) -> crate::epanet_error::Result<bool> {
+        Ok(!self.diff_from_engine()?.is_empty())
+    }
+
+    /// Returns the names of the cached fields that differ from the engine.
+    pub fn diff_from_engine(&self) -> crate::epanet_error::Result<Vec<&'static str>> {
+        let engine = self.project.get_control_by_index(self.index)?;
+        Ok(changed_fields!(
+            self,
+            engine,
+            control_type,
+            link_index,
+            setting,
+            node_index,
+            level,
+            enabled
+        ))
+    }
 }
 
 #[non_exhaustive]
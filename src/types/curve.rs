@@ -1,4 +1,5 @@
 //! Curve types: [`Curve`] RAII struct and [`CurveType`] enum.
+use crate::types::index::CurveIndex;
 use crate::{bindings::*, EPANET, epanet_error::*};
 use num_derive::FromPrimitive;
 
@@ -13,7 +14,7 @@ pub struct Curve<'a> {
     /// Reference to the owning EPANET project
     pub(crate) project: &'a EPANET,
     /// EPANET project index of the curve
-    pub(crate) index: i32,
+    pub(crate) index: CurveIndex,
     /// Curve ID
     pub id: String,
     /// Curve type of type [`CurveType`]
@@ -94,6 +95,23 @@ impl<'a> Curve<'a> {
     pub fn delete(self) -> Result<()> {
         self.project.delete_curve_by_id(self.index)
     }
+
+    /// Re-reads all cached fields from the engine, discarding local changes.
+    pub fn refresh(&mut self) -> Result<()> {
+        *self = self.project.get_curve_by_index(self.index)?;
+        Ok(())
+    }
+
+    /// Returns whether any cached field differs from the engine.
+    pub fn is_stale(&self) -> Result<bool> {
+        Ok(!self.diff_from_engine()?.is_empty())
+    }
+
+    /// Returns the names of the cached fields that differ from the engine.
+    pub fn diff_from_engine(&self) -> Result<Vec<&'static str>> {
+        let engine = self.project.get_curve_by_index(self.index)?;
+        Ok(changed_fields!(self, engine, id, curve_type, points))
+    }
 }
 
 /// Represents the type of a curve in an EPANET project.
@@ -1,5 +1,6 @@
 //! Demand types: [`Demand`] RAII struct, [`DemandModel`] enum, and [`DemandModelInfo`].
 use crate::bindings::*;
+use crate::types::index::{NodeIndex, PatternIndex};
 use crate::{epanet_error::*, EPANET};
 use num_derive::FromPrimitive;
 
@@ -43,13 +44,13 @@ pub struct Demand<'a> {
     /// Reference to the owning EPANET project.
     pub(crate) project: &'a EPANET,
     /// Index of the node this demand belongs to (1-based).
-    pub node_index: i32,
+    pub node_index: NodeIndex,
     /// Index of this demand category on the node (1-based).
     pub(crate) demand_index: i32,
     /// Base demand value.
     pub base_demand: f64,
     /// Index of the time pattern applied to this demand (0 = no pattern).
-    pub pattern_index: i32,
+    pub pattern_index: PatternIndex,
     /// Name of the demand category.
     pub name: String,
 }
@@ -75,7 +76,7 @@ impl<'a> Demand<'a> {
     /// index or non-existent pattern ID).
     pub fn new(
         project: &'a EPANET,
-        node_index: i32,
+        node_index: impl Into<NodeIndex>,
         base_demand: f64,
         demand_pattern: &str,
         name: &str,
@@ -117,6 +118,36 @@ impl<'a> Demand<'a> {
         self.project
             .delete_demand(self.node_index, self.demand_index)
     }
+
+    /// Re-reads all cached fields from the engine, discarding local changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if the demand category no longer exists.
+    pub fn refresh(&mut self) -> Result<()> {
+        *self = self
+            .project
+            .get_demand_by_index(self.node_index, self.demand_index)?;
+        Ok(())
+    }
+
+    /// Returns whether any cached field differs from the engine.
+    pub fn is_stale(&self) -> Result<bool> {
+        Ok(!self.diff_from_engine()?.is_empty())
+    }
+
+    /// Returns the names of the cached fields that differ from the engine,
+    /// e.g. `["base_demand"]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if the demand category no longer exists.
+    pub fn diff_from_engine(&self) -> Result<Vec<&'static str>> {
+        let engine = self
+            .project
+            .get_demand_by_index(self.node_index, self.demand_index)?;
+        Ok(changed_fields!(self, engine, base_demand, pattern_index, name))
+    }
 }
 
 #[non_exhaustive]
@@ -0,0 +1,83 @@
+//! Pipe sizing types: [`PipeSize`], [`LoadingCondition`], [`PipeSizingOptions`],
+//! [`PipeDesign`] and [`PipeSizingResult`].
+
+use crate::types::index::{LinkIndex, NodeIndex};
+
+/// A commercially available pipe diameter and its cost.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PipeSize {
+    /// Pipe diameter, in project diameter units (inches or millimeters).
+    pub diameter: f64,
+    /// Cost per unit of pipe length (feet or meters).
+    pub unit_cost: f64,
+}
+
+/// A steady-state loading condition a design must satisfy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadingCondition {
+    /// Name used in reports (e.g. `"peak hour"`, `"fire flow at J-12"`).
+    pub name: String,
+    /// Pattern time, in seconds, at which demand patterns are evaluated.
+    pub time: i32,
+    /// Global demand multiplier applied for this condition.
+    pub demand_multiplier: f64,
+    /// Additional demands `(node index, flow)` in project flow units, e.g. fire flows.
+    pub extra_demands: Vec<(NodeIndex, f64)>,
+    /// Minimum pressure required at the monitored nodes.
+    pub min_pressure: f64,
+}
+
+impl LoadingCondition {
+    /// Creates a loading condition at time zero with a demand multiplier of 1.
+    pub fn new(name: &str, min_pressure: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            time: 0,
+            demand_multiplier: 1.0,
+            extra_demands: Vec::new(),
+            min_pressure,
+        }
+    }
+}
+
+/// Settings for [`EPANET::optimize_pipe_sizes`](crate::EPANET::optimize_pipe_sizes).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipeSizingOptions {
+    /// Indices of the pipes whose diameter may be chosen.
+    pub pipes: Vec<LinkIndex>,
+    /// Available diameters and their unit costs.
+    pub catalogue: Vec<PipeSize>,
+    /// Loading conditions that must all be satisfied.
+    pub conditions: Vec<LoadingCondition>,
+    /// Indices of the nodes whose pressure is checked. An empty list checks every junction.
+    pub pressure_nodes: Vec<NodeIndex>,
+    /// Maximum number of designs the optimizer may evaluate. Each evaluation
+    /// solves the loading conditions in turn.
+    pub max_evaluations: usize,
+}
+
+/// Diameter chosen for a single pipe.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PipeDesign {
+    /// Index of the pipe.
+    pub link_index: LinkIndex,
+    /// Selected diameter.
+    pub diameter: f64,
+    /// Capital cost of the pipe (length times unit cost).
+    pub cost: f64,
+}
+
+/// Result of [`EPANET::optimize_pipe_sizes`](crate::EPANET::optimize_pipe_sizes).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipeSizingResult {
+    /// Selected diameter for every candidate pipe.
+    pub pipes: Vec<PipeDesign>,
+    /// Total capital cost of the design.
+    pub total_cost: f64,
+    /// Lowest pressure at the monitored nodes for each loading condition.
+    pub min_pressures: Vec<f64>,
+    /// `true` when every loading condition is satisfied.
+    pub feasible: bool,
+    /// Number of designs evaluated.
+    pub evaluations: usize,
+}
@@ -0,0 +1,214 @@
+//! Model comparison types: [`DiffOptions`], [`Change`] and [`ModelDiff`].
+//!
+//! [`EPANET::diff`](crate::EPANET::diff) compares two projects element by element,
+//! e.g. a model revision against the previous one, and reports the changes as
+//! text or JSON.
+
+use crate::types::export::{json_number, json_string};
+use std::fmt::{self, Display};
+
+/// Settings for [`EPANET::diff`](crate::EPANET::diff).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffOptions {
+    /// Largest difference between two numbers that is not reported, relative to
+    /// the larger magnitude (absolute below 1), so that round-off from unit
+    /// conversions or re-saving is ignored.
+    pub tolerance: f64,
+    /// Treat an element removed from one position and an element added at the
+    /// same position as one renamed element, and compare their properties.
+    pub detect_renames: bool,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-6,
+            detect_renames: true,
+        }
+    }
+}
+
+/// The part of the model a change belongs to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DiffObject {
+    /// Analysis options and time parameters; changes have an empty ID.
+    Options,
+    Node,
+    Link,
+    Pattern,
+    Curve,
+    /// Simple controls, identified by their 1-based position.
+    Control,
+    Rule,
+}
+
+impl Display for DiffObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DiffObject::Options => "options",
+            DiffObject::Node => "node",
+            DiffObject::Link => "link",
+            DiffObject::Pattern => "pattern",
+            DiffObject::Curve => "curve",
+            DiffObject::Control => "control",
+            DiffObject::Rule => "rule",
+        })
+    }
+}
+
+/// An old or new property value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffValue {
+    Number(f64),
+    Text(String),
+}
+
+impl Display for DiffValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffValue::Number(value) => write!(f, "{value}"),
+            DiffValue::Text(value) => f.write_str(value),
+        }
+    }
+}
+
+/// A single difference between two models. IDs are those of the second model,
+/// except for removed elements.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Added {
+        object: DiffObject,
+        id: String,
+    },
+    Removed {
+        object: DiffObject,
+        id: String,
+    },
+    /// An element at the same position with a different ID; see
+    /// [`DiffOptions::detect_renames`].
+    Renamed {
+        object: DiffObject,
+        old_id: String,
+        new_id: String,
+    },
+    Modified {
+        object: DiffObject,
+        id: String,
+        /// Property name, e.g. `elevation` or `factor 3`.
+        property: String,
+        old: DiffValue,
+        new: DiffValue,
+    },
+}
+
+impl Change {
+    /// The part of the model the change belongs to.
+    pub fn object(&self) -> DiffObject {
+        match self {
+            Change::Added { object, .. }
+            | Change::Removed { object, .. }
+            | Change::Renamed { object, .. }
+            | Change::Modified { object, .. } => *object,
+        }
+    }
+
+    /// JSON object describing the change.
+    pub fn to_json(&self) -> String {
+        let value = |value: &DiffValue| match value {
+            DiffValue::Number(number) => json_number(*number),
+            DiffValue::Text(text) => json_string(text),
+        };
+        let head = |change: &str, object: &DiffObject, id: &str| {
+            format!(
+                "{{\"change\":\"{change}\",\"object\":\"{object}\",\"id\":{}",
+                json_string(id)
+            )
+        };
+        match self {
+            Change::Added { object, id } => head("added", object, id) + "}",
+            Change::Removed { object, id } => head("removed", object, id) + "}",
+            Change::Renamed {
+                object,
+                old_id,
+                new_id,
+            } => format!(
+                "{},\"old_id\":{}}}",
+                head("renamed", object, new_id),
+                json_string(old_id)
+            ),
+            Change::Modified {
+                object,
+                id,
+                property,
+                old,
+                new,
+            } => format!(
+                "{},\"property\":{},\"old\":{},\"new\":{}}}",
+                head("modified", object, id),
+                json_string(property),
+                value(old),
+                value(new)
+            ),
+        }
+    }
+}
+
+/// One line per change: `+` added, `-` removed, `>` renamed and `~` modified,
+/// e.g. `~ node 10 elevation: 710 -> 712`.
+impl Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |object: &DiffObject, id: &str| match id {
+            "" => object.to_string(),
+            id => format!("{object} {id}"),
+        };
+        match self {
+            Change::Added { object, id } => write!(f, "+ {}", name(object, id)),
+            Change::Removed { object, id } => write!(f, "- {}", name(object, id)),
+            Change::Renamed {
+                object,
+                old_id,
+                new_id,
+            } => write!(f, "> {} -> {new_id}", name(object, old_id)),
+            Change::Modified {
+                object,
+                id,
+                property,
+                old,
+                new,
+            } => write!(f, "~ {} {property}: {old} -> {new}", name(object, id)),
+        }
+    }
+}
+
+/// The differences between two models, grouped by [`DiffObject`]: options,
+/// patterns, curves, nodes, links, controls, then rules. Patterns and curves
+/// come before the elements that reference them, so that their renames are
+/// listed first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelDiff {
+    pub changes: Vec<Change>,
+}
+
+impl ModelDiff {
+    /// True if the models are equivalent within the tolerance.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Renders the changes as a JSON document of the form `{"changes": [...]}`,
+    /// with numeric old and new values written as numbers.
+    pub fn to_json(&self) -> String {
+        let changes: Vec<String> = self.changes.iter().map(Change::to_json).collect();
+        format!("{{\"changes\":[{}]}}", changes.join(","))
+    }
+}
+
+/// One change per line, see [`Change`].
+impl Display for ModelDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+//! Emitter types: [`Emitter`], [`LeakLocalizationOptions`] and [`LeakCandidate`].
+//!
+//! An emitter discharges `coefficient * pressure^exponent` from a junction. The
+//! coefficient belongs to each junction
+//! ([`JunctionData::emitter`](crate::types::node::JunctionData::emitter)); the
+//! exponent and whether emitters may take in water at negative pressure are
+//! shared by the whole project and described by [`Emitter`].
+
+/// The emitter discharge law shared by all junctions of a project.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Emitter {
+    /// Pressure exponent of the discharge law.
+    pub exponent: f64,
+    /// `true` if emitters can flow back into the network at negative pressure.
+    pub backflow: bool,
+}
+
+impl Default for Emitter {
+    /// EPANET's defaults: exponent 0.5, backflow allowed.
+    fn default() -> Self {
+        Emitter {
+            exponent: 0.5,
+            backflow: true,
+        }
+    }
+}
+
+impl Emitter {
+    /// Discharge of an emitter with `coefficient` at `pressure`, in project flow
+    /// units. Negative pressures give a negative flow with backflow allowed and
+    /// zero flow otherwise.
+    pub fn flow(&self, coefficient: f64, pressure: f64) -> f64 {
+        if pressure >= 0.0 {
+            coefficient * pressure.powf(self.exponent)
+        } else if self.backflow {
+            -coefficient * (-pressure).powf(self.exponent)
+        } else {
+            0.0
+        }
+    }
+
+    /// Coefficient of an emitter that discharges `flow` at `pressure`, or
+    /// `None` if the pressure is not positive.
+    pub fn coefficient_for(&self, flow: f64, pressure: f64) -> Option<f64> {
+        (pressure > 0.0).then(|| flow / pressure.powf(self.exponent))
+    }
+}
+
+/// Options for [`EPANET::localize_leak`](crate::EPANET::localize_leak).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeakLocalizationOptions {
+    /// Time of day of the minimum night flow, in seconds.
+    pub time: i32,
+    /// Measured minimum night flow in excess of the flow the model expects, in
+    /// project flow units.
+    pub excess_flow: f64,
+    /// IDs of the candidate junctions; all junctions when `None`.
+    pub candidates: Option<Vec<String>>,
+    /// Pressures measured at the minimum night flow, by node ID. Candidates are
+    /// ranked by how well they reproduce these.
+    pub pressure_sensors: Vec<(String, f64)>,
+    /// Relative tolerance on the emitter flow.
+    pub tolerance: f64,
+    /// Maximum number of snapshots solved per candidate.
+    pub max_iterations: usize,
+}
+
+impl Default for LeakLocalizationOptions {
+    fn default() -> Self {
+        LeakLocalizationOptions {
+            time: 3 * 3600,
+            excess_flow: 0.0,
+            candidates: None,
+            pressure_sensors: Vec::new(),
+            tolerance: 1e-3,
+            max_iterations: 20,
+        }
+    }
+}
+
+/// A candidate leak location with the emitter that explains the excess flow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeakCandidate {
+    /// ID of the junction.
+    pub id: String,
+    /// Emitter coefficient that discharges the excess flow at the junction, in
+    /// addition to any emitter it already has.
+    pub coefficient: f64,
+    /// Pressure at the junction with the leak, in project pressure units.
+    pub pressure: f64,
+    /// Root mean square difference between the simulated and measured sensor
+    /// pressures with the leak; `None` without sensors.
+    pub sensor_error: Option<f64>,
+    /// `false` if the emitter flow did not reach the excess flow within the
+    /// tolerance, e.g. because the pressure collapses.
+    pub converged: bool,
+}
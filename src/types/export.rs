@@ -0,0 +1,340 @@
+//! Result export types: [`ExportOptions`] and the streaming [`ResultWriter`].
+//!
+//! A [`ResultWriter`] is created with
+//! [`EPANET::result_writer`](crate::EPANET::result_writer) and fed one time step
+//! at a time, so a run can be written to disk as it is solved:
+//!
+//! ```no_run
+//! use epanet::EPANET;
+//! use epanet::types::analysis::{InitHydOption, StepResult};
+//! use epanet::types::export::ExportOptions;
+//!
+//! let ph = EPANET::with_inp_file("network.inp", "report.rpt", "")?;
+//! let file = std::io::BufWriter::new(std::fs::File::create("results.csv")?);
+//! let mut writer = ph.result_writer(file, ExportOptions::default())?;
+//! let mut solver = ph.solver().init_h(InitHydOption::NoSave)?.run_h()?;
+//! writer.write_step(solver.project(), 0)?;
+//! while let StepResult::Continue { current_time, .. } = solver.next_h()? {
+//!     writer.write_step(solver.project(), current_time)?;
+//! }
+//! writer.finish()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::epanet_error::*;
+use crate::types::link::LinkProperty;
+use crate::types::node::NodeProperty;
+use crate::EPANET;
+use std::io::Write;
+
+/// Output file format.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row.
+    Csv,
+    /// Newline-delimited JSON, one object per row.
+    NdJson,
+}
+
+/// Arrangement of the exported values.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ExportLayout {
+    /// One row per time step with a column per element and property, named
+    /// `node:<id>:<property>` or `link:<id>:<property>`. NDJSON rows nest the values
+    /// as `{"nodes": {<id>: {<property>: value}}, "links": {...}}`.
+    Wide,
+    /// One row per time step, element and property, with `element`, `id`,
+    /// `property` and `value` columns.
+    Long,
+}
+
+/// How the time of each row is written.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TimeIndex {
+    /// Elapsed simulation time in seconds.
+    Seconds,
+    /// Elapsed simulation time formatted as `H:MM:SS`, as in EPANET reports.
+    Clock,
+}
+
+/// Settings for a [`ResultWriter`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportOptions {
+    /// Output format: CSV or newline-delimited JSON.
+    pub format: ExportFormat,
+    /// Whether values are written one per row or one row per time step.
+    pub layout: ExportLayout,
+    /// How the time of each row is written.
+    pub time_index: TimeIndex,
+    /// Node properties to write, in column order.
+    pub node_properties: Vec<NodeProperty>,
+    /// Link properties to write, in column order.
+    pub link_properties: Vec<LinkProperty>,
+    /// IDs of the nodes to write, or `None` for every node.
+    pub nodes: std::option::Option<Vec<String>>,
+    /// IDs of the links to write, or `None` for every link.
+    pub links: std::option::Option<Vec<String>>,
+    /// Only write steps falling on a reporting time (`ReportStart` plus a multiple
+    /// of `ReportStep`); otherwise every step passed to the writer is written.
+    pub report_times_only: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Csv,
+            layout: ExportLayout::Long,
+            time_index: TimeIndex::Seconds,
+            node_properties: vec![
+                NodeProperty::Pressure,
+                NodeProperty::Head,
+                NodeProperty::Demand,
+            ],
+            link_properties: vec![LinkProperty::Flow, LinkProperty::Velocity],
+            nodes: None,
+            links: None,
+            report_times_only: true,
+        }
+    }
+}
+
+/// Streams node and link results to a CSV or NDJSON sink, one time step at a time.
+///
+/// Element IDs and indices are resolved when the writer is created; only the
+/// current step's values are held in memory.
+pub struct ResultWriter<W: Write> {
+    pub(crate) out: W,
+    pub(crate) options: ExportOptions,
+    /// `(index, id)` of the exported nodes.
+    pub(crate) nodes: Vec<(i32, String)>,
+    /// `(index, id)` of the exported links.
+    pub(crate) links: Vec<(i32, String)>,
+    /// Column names of [`ExportOptions::node_properties`].
+    pub(crate) node_names: Vec<String>,
+    /// Column names of [`ExportOptions::link_properties`].
+    pub(crate) link_names: Vec<String>,
+    pub(crate) report_start: i64,
+    pub(crate) report_step: i64,
+    pub(crate) header_written: bool,
+    pub(crate) steps_written: usize,
+}
+
+impl<W: Write> ResultWriter<W> {
+    /// Writes the results currently held by `project` for time `time` (in seconds).
+    ///
+    /// Steps that do not fall on a reporting time are skipped when
+    /// [`ExportOptions::report_times_only`] is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if a value cannot be read, or error 308 if the
+    /// sink fails.
+    pub fn write_step(&mut self, project: &EPANET, time: i64) -> Result<()> {
+        if self.options.report_times_only
+            && !is_report_time(time, self.report_start, self.report_step)
+        {
+            return Ok(());
+        }
+        if !self.header_written {
+            self.write_header()?;
+        }
+        let time = match self.options.time_index {
+            TimeIndex::Seconds => time.to_string(),
+            TimeIndex::Clock => clock_time(time),
+        };
+        let mut node_values = Vec::with_capacity(self.nodes.len() * self.node_names.len());
+        for (index, _) in &self.nodes {
+            for property in &self.options.node_properties {
+                node_values.push(project.get_node_value(*index, *property)?);
+            }
+        }
+        let mut link_values = Vec::with_capacity(self.links.len() * self.link_names.len());
+        for (index, _) in &self.links {
+            for property in &self.options.link_properties {
+                link_values.push(project.get_link_value(*index, *property)?);
+            }
+        }
+
+        let out = &mut self.out;
+        let groups = [
+            ("node", &self.nodes, &self.node_names, &node_values),
+            ("link", &self.links, &self.link_names, &link_values),
+        ];
+        match (self.options.format, self.options.layout) {
+            (ExportFormat::Csv, ExportLayout::Wide) => {
+                write!(out, "{}", csv_field(&time)).map_err(save_error)?;
+                for value in node_values.iter().chain(&link_values) {
+                    write!(out, ",{value}").map_err(save_error)?;
+                }
+                writeln!(out).map_err(save_error)?;
+            }
+            (ExportFormat::Csv, ExportLayout::Long) => {
+                let time = csv_field(&time);
+                for (element, ids, names, values) in groups {
+                    let mut values = values.iter();
+                    for (_, id) in ids {
+                        let id = csv_field(id);
+                        for (name, value) in names.iter().zip(&mut values) {
+                            writeln!(out, "{time},{element},{id},{name},{value}")
+                                .map_err(save_error)?;
+                        }
+                    }
+                }
+            }
+            (ExportFormat::NdJson, ExportLayout::Wide) => {
+                write!(out, "{{\"time\":{}", json_time(&time)).map_err(save_error)?;
+                for (element, ids, names, values) in groups {
+                    let mut values = values.iter();
+                    write!(out, ",\"{element}s\":{{").map_err(save_error)?;
+                    for (row, (_, id)) in ids.iter().enumerate() {
+                        let separator = if row > 0 { "," } else { "" };
+                        write!(out, "{separator}{}:{{", json_string(id)).map_err(save_error)?;
+                        for (column, (name, value)) in names.iter().zip(&mut values).enumerate() {
+                            let separator = if column > 0 { "," } else { "" };
+                            write!(out, "{separator}\"{name}\":{}", json_number(*value))
+                                .map_err(save_error)?;
+                        }
+                        write!(out, "}}").map_err(save_error)?;
+                    }
+                    write!(out, "}}").map_err(save_error)?;
+                }
+                writeln!(out, "}}").map_err(save_error)?;
+            }
+            (ExportFormat::NdJson, ExportLayout::Long) => {
+                let time = json_time(&time);
+                for (element, ids, names, values) in groups {
+                    let mut values = values.iter();
+                    for (_, id) in ids {
+                        let id = json_string(id);
+                        for (name, value) in names.iter().zip(&mut values) {
+                            writeln!(
+                                out,
+                                "{{\"time\":{time},\"element\":\"{element}\",\"id\":{id},\"property\":\"{name}\",\"value\":{}}}",
+                                json_number(*value)
+                            ).map_err(save_error)?;
+                        }
+                    }
+                }
+            }
+        }
+        self.steps_written += 1;
+        Ok(())
+    }
+
+    /// Number of time steps written so far.
+    pub fn steps_written(&self) -> usize {
+        self.steps_written
+    }
+
+    /// Flushes the sink and returns it. A CSV header is written even if no step was.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 308 if the sink cannot be flushed.
+    pub fn finish(mut self) -> Result<W> {
+        if !self.header_written {
+            self.write_header()?;
+        }
+        self.out.flush().map_err(save_error)?;
+        Ok(self.out)
+    }
+
+    fn write_header(&mut self) -> Result<()> {
+        self.header_written = true;
+        if self.options.format != ExportFormat::Csv {
+            return Ok(());
+        }
+        match self.options.layout {
+            ExportLayout::Long => {
+                writeln!(self.out, "time,element,id,property,value").map_err(save_error)?
+            }
+            ExportLayout::Wide => {
+                let mut header = String::from("time");
+                for (element, ids, names) in [
+                    ("node", &self.nodes, &self.node_names),
+                    ("link", &self.links, &self.link_names),
+                ] {
+                    for (_, id) in ids {
+                        for name in names {
+                            header.push(',');
+                            header.push_str(&csv_field(&format!("{element}:{id}:{name}")));
+                        }
+                    }
+                }
+                writeln!(self.out, "{header}").map_err(save_error)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lower-case property name used in column headers, e.g. `pressure` or `headloss`.
+/// Link quality is written as `quality`, like node quality.
+pub(crate) fn property_name(property: &impl std::fmt::Debug) -> String {
+    match format!("{property:?}").to_lowercase() {
+        name if name == "linkqual" => "quality".to_string(),
+        name => name,
+    }
+}
+
+/// Maps an I/O error raised while writing results to an [`EPANETError`] with
+/// code 308 ("cannot save results to file"), keeping the I/O error as context.
+pub(crate) fn save_error(error: std::io::Error) -> EPANETError {
+    EPANETError::from(308).with_context(error.to_string())
+}
+
+/// `true` if `time` falls on a reporting time, given the report start time and
+/// (positive) report time step from `EPANET::report_times`.
+pub(crate) fn is_report_time(time: i64, report_start: i64, report_step: i64) -> bool {
+    time >= report_start && (time - report_start) % report_step == 0
+}
+
+/// Formats elapsed seconds as `H:MM:SS`.
+pub(crate) fn clock_time(seconds: i64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Seconds are written as numbers, clock times as strings.
+fn json_time(time: &str) -> String {
+    if time.contains(':') {
+        json_string(time)
+    } else {
+        time.to_string()
+    }
+}
+
+pub(crate) fn json_string(value: &str) -> String {
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// JSON has no NaN or infinity, so they are written as `null`.
+pub(crate) fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
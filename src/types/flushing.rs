@@ -0,0 +1,66 @@
+//! Unidirectional flushing types: [`HydrantDischarge`], [`FlushingStep`],
+//! [`FlushingOptions`] and [`FlushingStepReport`].
+
+/// How water leaves the network at an open hydrant.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum HydrantDischarge {
+    /// Pressure-dependent discharge through an emitter with the given coefficient
+    /// (flow units per unit of pressure to the emitter exponent).
+    Emitter { coefficient: f64 },
+    /// Fixed discharge added to the hydrant node's demand, in project flow units.
+    Demand { flow: f64 },
+}
+
+/// One step of a unidirectional flushing sequence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlushingStep {
+    /// Name used in reports.
+    pub name: String,
+    /// IDs of the links (isolation valves or pipes) closed during the step.
+    pub close_links: Vec<String>,
+    /// ID of the node where the hydrant is opened.
+    pub hydrant: String,
+    /// Hydrant discharge model.
+    pub discharge: HydrantDischarge,
+    /// How long the hydrant is left open, in seconds.
+    pub duration: i32,
+    /// IDs of the pipes being flushed by this step.
+    pub target_pipes: Vec<String>,
+}
+
+/// Settings for [`EPANET::simulate_flushing`](crate::EPANET::simulate_flushing).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlushingOptions {
+    /// Time of day, in seconds, at which the first step starts. Later steps start
+    /// when the previous one ends.
+    pub start_time: i32,
+    /// Velocity the target pipes must reach for scouring, in ft/s or m/s.
+    pub target_velocity: f64,
+    /// IDs of nodes whose pressure is reported for every step.
+    pub critical_nodes: Vec<String>,
+}
+
+/// Hydraulic results of one flushing step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlushingStepReport {
+    /// Name of the step.
+    pub name: String,
+    /// Time of day, in seconds, at which the step was simulated.
+    pub start_time: i32,
+    /// Hydrant discharge, in project flow units.
+    pub hydrant_flow: f64,
+    /// Pressure at the hydrant node.
+    pub hydrant_pressure: f64,
+    /// Volume discharged over the step: gallons for GPM, liters for LPS/LPM, cubic
+    /// feet for CFS, cubic meters for CMH/CMD/CMS, and the volume unit of the flow
+    /// unit (million gallons, acre-feet, megaliters) otherwise.
+    pub volume_discharged: f64,
+    /// Velocity reached in each target pipe, by pipe ID.
+    pub pipe_velocities: Vec<(String, f64)>,
+    /// `true` when every target pipe reaches [`FlushingOptions::target_velocity`].
+    pub scouring_achieved: bool,
+    /// Pressure at each critical node, by node ID.
+    pub critical_pressures: Vec<(String, f64)>,
+    /// Number of hydraulic warnings raised by the solver (e.g. negative pressures).
+    pub warnings: u32,
+}
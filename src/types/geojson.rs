@@ -0,0 +1,140 @@
+//! GeoJSON interchange types: [`GeoJsonFields`], [`GeoJsonExportOptions`],
+//! [`GeoJsonImportOptions`] and [`GeoJsonImport`].
+//!
+//! Available with the `geojson` feature. Nodes are written as `Point` features and
+//! links as `LineString` features running from the start node through the link's
+//! vertices to the end node. The same [`GeoJsonFields`] name the feature
+//! properties on export and import, so a file written by
+//! [`EPANET::export_geojson`](crate::EPANET::export_geojson) can be read back with
+//! [`EPANET::import_geojson`](crate::EPANET::import_geojson).
+
+use crate::types::link::{LinkProperty, LinkType};
+use crate::types::node::{NodeProperty, NodeType};
+
+/// Feature property names used for each static attribute.
+///
+/// Change these to read GIS layers whose columns are named differently, e.g.
+/// `diameter` to `"DIAM_MM"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoJsonFields {
+    /// Element ID.
+    pub id: String,
+    /// Element type, e.g. `Junction`, `Tank`, `Pipe` or `Prv` (case-insensitive).
+    pub kind: String,
+    pub elevation: String,
+    pub base_demand: String,
+    pub demand_pattern: String,
+    pub init_level: String,
+    pub min_level: String,
+    pub max_level: String,
+    /// Tank, pipe and valve diameter.
+    pub diameter: String,
+    pub min_volume: String,
+    pub volume_curve: String,
+    /// ID of the link's start node.
+    pub from_node: String,
+    /// ID of the link's end node.
+    pub to_node: String,
+    /// `Open` or `Closed`.
+    pub initial_status: String,
+    pub length: String,
+    pub roughness: String,
+    pub minor_loss: String,
+    pub power: String,
+    pub speed: String,
+    /// ID of a pump's head curve.
+    pub head_curve: String,
+    pub setting: String,
+}
+
+impl Default for GeoJsonFields {
+    fn default() -> Self {
+        Self {
+            id: "id".to_string(),
+            kind: "type".to_string(),
+            elevation: "elevation".to_string(),
+            base_demand: "base_demand".to_string(),
+            demand_pattern: "demand_pattern".to_string(),
+            init_level: "init_level".to_string(),
+            min_level: "min_level".to_string(),
+            max_level: "max_level".to_string(),
+            diameter: "diameter".to_string(),
+            min_volume: "min_volume".to_string(),
+            volume_curve: "volume_curve".to_string(),
+            from_node: "from_node".to_string(),
+            to_node: "to_node".to_string(),
+            initial_status: "initial_status".to_string(),
+            length: "length".to_string(),
+            roughness: "roughness".to_string(),
+            minor_loss: "minor_loss".to_string(),
+            power: "power".to_string(),
+            speed: "speed".to_string(),
+            head_curve: "head_curve".to_string(),
+            setting: "setting".to_string(),
+        }
+    }
+}
+
+/// Settings for [`EPANET::export_geojson`](crate::EPANET::export_geojson).
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoJsonExportOptions {
+    /// Write a `Point` feature for every node.
+    pub nodes: bool,
+    /// Write a `LineString` feature for every link.
+    pub links: bool,
+    /// Property names of the static attributes.
+    pub fields: GeoJsonFields,
+    /// Node results to add as properties, read from the current solver state (e.g.
+    /// after [`solve_h`](crate::EPANET::solve_h) or at a solver step). Each is
+    /// named like an export column, e.g. `pressure`.
+    pub node_results: Vec<NodeProperty>,
+    /// Link results to add as properties, e.g. `flow` or `velocity`.
+    pub link_results: Vec<LinkProperty>,
+}
+
+impl Default for GeoJsonExportOptions {
+    fn default() -> Self {
+        Self {
+            nodes: true,
+            links: true,
+            fields: GeoJsonFields::default(),
+            node_results: Vec::new(),
+            link_results: Vec::new(),
+        }
+    }
+}
+
+/// Settings for [`EPANET::import_geojson`](crate::EPANET::import_geojson).
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoJsonImportOptions {
+    /// Property names of the static attributes.
+    pub fields: GeoJsonFields,
+    /// Type of `Point` features without a type property.
+    pub default_node_type: NodeType,
+    /// Type of `LineString` features without a type property.
+    pub default_link_type: LinkType,
+    /// When a link has no start or end node property, the node within this
+    /// distance of the first or last line position is used.
+    pub snap_tolerance: f64,
+}
+
+impl Default for GeoJsonImportOptions {
+    fn default() -> Self {
+        Self {
+            fields: GeoJsonFields::default(),
+            default_node_type: NodeType::Junction,
+            default_link_type: LinkType::Pipe,
+            snap_tolerance: 1e-6,
+        }
+    }
+}
+
+/// Result of [`EPANET::import_geojson`](crate::EPANET::import_geojson).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GeoJsonImport {
+    /// IDs of the nodes created, in feature order. IDs are returned rather than
+    /// indices because adding a junction renumbers existing tanks and reservoirs.
+    pub nodes: Vec<String>,
+    /// IDs of the links created, in feature order.
+    pub links: Vec<String>,
+}
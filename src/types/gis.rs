@@ -0,0 +1,278 @@
+//! GIS model building types: [`Polyline`], [`ElevationSource`],
+//! [`PolylineModelOptions`] and [`PolylineModel`].
+//!
+//! Pipe asset layers usually hold line features without explicit nodes.
+//! [`EPANET::build_from_polylines`](crate::EPANET::build_from_polylines) snaps
+//! their ends together into junctions, optionally splits them where they cross,
+//! and adds the resulting pipes to the project. Polylines can be read from WKT
+//! with [`Polyline::from_wkt`], or from GeoJSON with
+//! `Polyline::from_geojson` when the `geojson` feature is enabled.
+
+use crate::epanet_error::*;
+
+/// A pipe asset as a line in the model's coordinate system.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline {
+    /// Asset ID, used as the pipe ID (with a `-1`, `-2`, ... suffix if split).
+    pub id: String,
+    /// Positions from start to end; at least two.
+    pub points: Vec<(f64, f64)>,
+    /// Pipe diameter (in project diameter units).
+    pub diameter: f64,
+    /// Pipe material, used to look up roughness and stored as the link tag.
+    pub material: Option<String>,
+    /// Year of installation, stored in the link comment.
+    pub install_year: Option<i32>,
+}
+
+impl Polyline {
+    /// Reads the positions of a WKT `LINESTRING`, e.g.
+    /// `LINESTRING (0 0, 10 0, 10 5)`. Z and M values are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 250 if `wkt` is not a `LINESTRING` with at least two
+    /// positions.
+    pub fn from_wkt(id: &str, wkt: &str, diameter: f64) -> Result<Self> {
+        let invalid = || EPANETError::from(250).with_context(format!("{id}: invalid WKT {wkt:?}"));
+        let text = wkt.trim();
+        let (keyword, rest) = text.split_at(text.find('(').ok_or_else(invalid)?);
+        let mut keyword = keyword.split_whitespace();
+        if !keyword
+            .next()
+            .is_some_and(|k| k.eq_ignore_ascii_case("LINESTRING"))
+        {
+            return Err(invalid());
+        }
+        let body = rest
+            .strip_prefix('(')
+            .and_then(|r| r.trim_end().strip_suffix(')'))
+            .ok_or_else(invalid)?;
+        let points = body
+            .split(',')
+            .map(|position| {
+                let mut values = position.split_whitespace().map(str::parse::<f64>);
+                match (values.next(), values.next()) {
+                    (Some(Ok(x)), Some(Ok(y))) => Ok((x, y)),
+                    _ => Err(invalid()),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if points.len() < 2 {
+            return Err(invalid());
+        }
+        Ok(Polyline {
+            id: id.to_string(),
+            points,
+            diameter,
+            material: None,
+            install_year: None,
+        })
+    }
+
+    /// Reads the `LineString` and `MultiLineString` features of a GeoJSON
+    /// `FeatureCollection`. Each part of a `MultiLineString` becomes its own
+    /// polyline, with `-1`, `-2`, ... appended to the ID. Other geometries are
+    /// skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 201 if the text is not valid JSON, or 250 if it is not a
+    /// `FeatureCollection` or a line feature lacks an ID, diameter or valid
+    /// coordinates.
+    #[cfg(feature = "geojson")]
+    pub fn from_geojson(geojson: &str, fields: &PolylineFields) -> Result<Vec<Self>> {
+        use serde_json::Value;
+
+        let invalid = |context: String| EPANETError::from(250).with_context(context);
+        let value: Value = serde_json::from_str(geojson)
+            .map_err(|e| EPANETError::from(201).with_context(e.to_string()))?;
+        let features = value
+            .get("features")
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid("expected a GeoJSON FeatureCollection".to_string()))?;
+
+        let text = |properties: &Value, field: &str| match properties.get(field) {
+            Some(Value::String(text)) if !text.is_empty() => Some(text.clone()),
+            Some(Value::Number(number)) => Some(number.to_string()),
+            _ => None,
+        };
+        let position = |value: &Value| match value.as_array().map(Vec::as_slice) {
+            Some([x, y, ..]) => x.as_f64().zip(y.as_f64()),
+            _ => None,
+        };
+        let mut polylines = Vec::new();
+        for feature in features {
+            let geometry = feature.get("geometry").unwrap_or(&Value::Null);
+            let parts: Vec<&Value> = match geometry.get("type").and_then(Value::as_str) {
+                Some("LineString") => geometry.get("coordinates").into_iter().collect(),
+                Some("MultiLineString") => geometry
+                    .get("coordinates")
+                    .and_then(Value::as_array)
+                    .map(|parts| parts.iter().collect())
+                    .unwrap_or_default(),
+                _ => continue,
+            };
+            let properties = feature.get("properties").unwrap_or(&Value::Null);
+            let id = text(properties, &fields.id)
+                .ok_or_else(|| invalid(format!("line feature without {}", fields.id)))?;
+            let diameter = text(properties, &fields.diameter)
+                .and_then(|d| d.trim().parse::<f64>().ok())
+                .ok_or_else(|| invalid(format!("{id}: missing {}", fields.diameter)))?;
+            let material = text(properties, &fields.material);
+            let install_year = text(properties, &fields.install_year)
+                .and_then(|year| year.trim().parse::<f64>().ok())
+                .map(|year| year as i32);
+            for (part, coordinates) in parts.iter().enumerate() {
+                let points = coordinates
+                    .as_array()
+                    .map(|positions| positions.iter().map(position).collect::<Option<Vec<_>>>())
+                    .unwrap_or_default()
+                    .filter(|points| points.len() >= 2)
+                    .ok_or_else(|| invalid(format!("{id}: malformed coordinates")))?;
+                polylines.push(Polyline {
+                    id: match parts.len() {
+                        1 => id.clone(),
+                        _ => format!("{id}-{}", part + 1),
+                    },
+                    points,
+                    diameter,
+                    material: material.clone(),
+                    install_year,
+                });
+            }
+        }
+        Ok(polylines)
+    }
+}
+
+/// Feature property names read by [`Polyline::from_geojson`].
+#[cfg(feature = "geojson")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolylineFields {
+    pub id: String,
+    pub diameter: String,
+    pub material: String,
+    pub install_year: String,
+}
+
+#[cfg(feature = "geojson")]
+impl Default for PolylineFields {
+    fn default() -> Self {
+        Self {
+            id: "id".to_string(),
+            diameter: "diameter".to_string(),
+            material: "material".to_string(),
+            install_year: "install_year".to_string(),
+        }
+    }
+}
+
+/// A regular grid of elevations, e.g. resampled from a digital elevation model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElevationGrid {
+    /// Coordinates of the centre of the first cell.
+    pub origin: (f64, f64),
+    /// Distance between cell centres, in both directions.
+    pub cell_size: f64,
+    /// Number of cells in each row.
+    pub columns: usize,
+    /// Elevations in row-major order, starting at `origin` with `y` increasing
+    /// from row to row.
+    pub values: Vec<f64>,
+}
+
+impl ElevationGrid {
+    /// Bilinearly interpolated elevation at `(x, y)`; positions outside the grid
+    /// take the value at the nearest edge.
+    pub fn sample(&self, x: f64, y: f64) -> f64 {
+        let rows = self.values.len() / self.columns.max(1);
+        if rows == 0 {
+            return 0.0;
+        }
+        let locate = |value: f64, cells: usize| {
+            let position = value.clamp(0.0, (cells - 1) as f64);
+            let lower = (position.floor() as usize).min(cells.saturating_sub(2));
+            (lower, (lower + 1).min(cells - 1), position - lower as f64)
+        };
+        let (c0, c1, fx) = locate((x - self.origin.0) / self.cell_size, self.columns);
+        let (r0, r1, fy) = locate((y - self.origin.1) / self.cell_size, rows);
+        let at = |row: usize, column: usize| self.values[row * self.columns + column];
+        let bottom = at(r0, c0) * (1.0 - fx) + at(r0, c1) * fx;
+        let top = at(r1, c0) * (1.0 - fx) + at(r1, c1) * fx;
+        bottom * (1.0 - fy) + top * fy
+    }
+}
+
+/// Where junction elevations come from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElevationSource {
+    /// Every junction is placed at elevation zero.
+    None,
+    /// Surveyed `(x, y, elevation)` points; each junction takes the elevation of
+    /// the nearest point.
+    Points(Vec<(f64, f64, f64)>),
+    /// A regular elevation grid.
+    Grid(ElevationGrid),
+}
+
+impl ElevationSource {
+    /// Elevation at `(x, y)`.
+    pub fn elevation(&self, x: f64, y: f64) -> f64 {
+        match self {
+            ElevationSource::None => 0.0,
+            ElevationSource::Points(points) => points
+                .iter()
+                .map(|(px, py, z)| ((px - x).hypot(py - y), *z))
+                .min_by(|a, b| a.0.total_cmp(&b.0))
+                .map_or(0.0, |(_, z)| z),
+            ElevationSource::Grid(grid) => grid.sample(x, y),
+        }
+    }
+}
+
+/// Settings for [`EPANET::build_from_polylines`](crate::EPANET::build_from_polylines).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolylineModelOptions {
+    /// Line ends closer than this (in coordinate units) share a junction. Ends
+    /// within this distance of an existing node with coordinates connect to it.
+    pub snap_tolerance: f64,
+    /// Split lines where they cross or where a line ends on another line's
+    /// interior, adding a junction at each split.
+    pub split_at_intersections: bool,
+    /// Source of the new junctions' elevations.
+    pub elevations: ElevationSource,
+    /// Prefix of generated junction IDs, numbered from 1 (e.g. `J1`, `J2`).
+    pub node_prefix: String,
+    /// Roughness by material (case-insensitive).
+    pub material_roughness: Vec<(String, f64)>,
+    /// Roughness of pipes whose material is missing or not in the table.
+    pub default_roughness: f64,
+}
+
+impl Default for PolylineModelOptions {
+    fn default() -> Self {
+        Self {
+            snap_tolerance: 0.1,
+            split_at_intersections: false,
+            elevations: ElevationSource::None,
+            node_prefix: "J".to_string(),
+            material_roughness: Vec::new(),
+            default_roughness: 100.0,
+        }
+    }
+}
+
+/// Result of [`EPANET::build_from_polylines`](crate::EPANET::build_from_polylines).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PolylineModel {
+    /// IDs of the junctions created.
+    pub nodes: Vec<String>,
+    /// IDs of the pipes created.
+    pub links: Vec<String>,
+    /// Number of split points inserted at intersections.
+    pub splits: usize,
+    /// IDs of polyline pieces dropped because both ends snapped to the same
+    /// junction.
+    pub dropped: Vec<String>,
+}
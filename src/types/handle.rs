@@ -0,0 +1,166 @@
+//! Stable element handles: [`NodeHandle`] and [`LinkHandle`].
+//!
+//! EPANET indices shift when elements are added or deleted, so a cached index
+//! can silently refer to a different element later on. A handle instead names an
+//! element through a registry kept by its [`EPANET`] project: the registry
+//! follows renames made with [`set_node_id`](EPANET::set_node_id) and
+//! [`set_link_id`](EPANET::set_link_id), and forgets elements removed with
+//! [`delete_node`](EPANET::delete_node) and [`delete_link`](EPANET::delete_link),
+//! so that using the handle of a deleted element is an error.
+
+use crate::epanet_error::*;
+use crate::types::index::{LinkIndex, NodeIndex};
+use crate::types::link::Link;
+use crate::types::node::Node;
+use crate::EPANET;
+use std::collections::HashMap;
+
+/// A node reference that stays valid across reindexing and renames.
+///
+/// Handles are cheap to copy and compare; two handles to the same node of the
+/// same project are equal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct NodeHandle {
+    pub(crate) project: u64,
+    pub(crate) slot: usize,
+}
+
+/// A link reference that stays valid across reindexing and renames.
+///
+/// Handles are cheap to copy and compare; two handles to the same link of the
+/// same project are equal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LinkHandle {
+    pub(crate) project: u64,
+    pub(crate) slot: usize,
+}
+
+impl NodeHandle {
+    /// Current 1-based index of the node.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 203 if the node was deleted or the handle belongs to
+    /// another project.
+    pub fn index(self, project: &EPANET) -> Result<NodeIndex> {
+        project.get_node_index(&self.id(project)?)
+    }
+
+    /// Current ID of the node.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 203 if the node was deleted or the handle belongs to
+    /// another project.
+    pub fn id(self, project: &EPANET) -> Result<String> {
+        project.handle_id(self.project, |handles| handles.nodes.id(self.slot), 203)
+    }
+
+    /// The node as a domain struct.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 203 if the node was deleted or the handle belongs to
+    /// another project.
+    pub fn get(self, project: &EPANET) -> Result<Node<'_>> {
+        project.get_node_by_index(self.index(project)?)
+    }
+
+    /// True if the handle belongs to `project` and its node still exists.
+    pub fn is_valid(self, project: &EPANET) -> bool {
+        self.index(project).is_ok()
+    }
+}
+
+impl LinkHandle {
+    /// Current 1-based index of the link.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 204 if the link was deleted, also as part of deleting one
+    /// of its nodes, or the handle belongs to another project.
+    pub fn index(self, project: &EPANET) -> Result<LinkIndex> {
+        project.get_link_index(&self.id(project)?)
+    }
+
+    /// Current ID of the link.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 204 if the link was deleted, also as part of deleting one
+    /// of its nodes, or the handle belongs to another project.
+    pub fn id(self, project: &EPANET) -> Result<String> {
+        project.handle_id(self.project, |handles| handles.links.id(self.slot), 204)
+    }
+
+    /// The link as a domain struct.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 204 if the link was deleted, also as part of deleting one
+    /// of its nodes, or the handle belongs to another project.
+    pub fn get(self, project: &EPANET) -> Result<Link<'_>> {
+        project.get_link_by_index(self.index(project)?)
+    }
+
+    /// True if the handle belongs to `project` and its link still exists.
+    pub fn is_valid(self, project: &EPANET) -> bool {
+        self.index(project).is_ok()
+    }
+}
+
+/// The handles issued by a project, for nodes and for links.
+#[derive(Debug, Default)]
+pub(crate) struct HandleRegistry {
+    pub(crate) nodes: Slots,
+    pub(crate) links: Slots,
+}
+
+/// Current ID of each handle slot, or `None` once its element was deleted.
+#[derive(Debug, Default)]
+pub(crate) struct Slots {
+    ids: Vec<Option<String>>,
+    live: HashMap<String, usize>,
+}
+
+impl Slots {
+    /// The slot for `id`, reusing the slot of an existing handle.
+    pub(crate) fn slot(&mut self, id: &str) -> usize {
+        if let Some(slot) = self.live.get(id) {
+            return *slot;
+        }
+        self.ids.push(Some(id.to_string()));
+        self.live.insert(id.to_string(), self.ids.len() - 1);
+        self.ids.len() - 1
+    }
+
+    /// Current ID of `slot`, or `None` if its element was deleted.
+    pub(crate) fn id(&self, slot: usize) -> Option<&str> {
+        self.ids.get(slot).and_then(|id| id.as_deref())
+    }
+
+    pub(crate) fn rename(&mut self, old: &str, new: &str) {
+        if let Some(slot) = self.live.remove(old) {
+            self.ids[slot] = Some(new.to_string());
+            self.live.insert(new.to_string(), slot);
+        }
+    }
+
+    pub(crate) fn remove(&mut self, id: &str) {
+        if let Some(slot) = self.live.remove(id) {
+            self.ids[slot] = None;
+        }
+    }
+
+    /// Removes the elements whose ID no longer `exists`.
+    pub(crate) fn retain(&mut self, exists: impl Fn(&str) -> bool) {
+        let gone: Vec<String> = self.live.keys().filter(|id| !exists(id)).cloned().collect();
+        for id in gone {
+            self.remove(&id);
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.live.is_empty()
+    }
+}
@@ -0,0 +1,321 @@
+//! Typed element indices and IDs: [`NodeIndex`], [`LinkIndex`], [`PatternIndex`],
+//! [`CurveIndex`], [`ControlIndex`], [`RuleIndex`] and [`NodeId`], [`LinkId`],
+//! [`PatternId`], [`CurveId`], [`RuleId`].
+//!
+//! EPANET numbers each kind of element separately from 1, so a bare `i32` does
+//! not say whether it is a node or a link index. Methods taking an index accept
+//! `impl Into<NodeIndex>` (and so on), which admits a plain `i32` such as a loop
+//! counter but rejects an index of another kind. Methods returning an index
+//! return the typed index.
+//!
+//! IDs are resolved to indices through the project, e.g.
+//! [`NodeId::index`], and indices back to IDs, e.g. [`NodeIndex::id`].
+//! Simple controls have no IDs.
+//!
+//! Unlike the indices, the ID types are opt-in. ID getters such as
+//! [`get_node_id`](EPANET::get_node_id) return `String`, lookups such as
+//! [`get_node_index`](EPANET::get_node_index) take `&str` (which an ID derefs
+//! to), and domain struct fields such as
+//! [`Node::id`](crate::types::node::Node::id) are `String`, so that code
+//! written against them keeps compiling. The ID iterators, e.g.
+//! [`iter_node_ids`](EPANET::iter_node_ids), return the typed IDs.
+
+use crate::epanet_error::*;
+use crate::EPANET;
+use std::borrow::Borrow;
+use std::fmt::{self, Display};
+use std::ops::Deref;
+
+macro_rules! index_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[repr(transparent)]
+        pub struct $name(pub i32);
+
+        impl $name {
+            /// 0-based offset, for indexing vectors in index order such as
+            /// those returned by the bulk getters.
+            pub fn offset(self) -> usize {
+                (self.0 - 1) as usize
+            }
+        }
+
+        impl From<i32> for $name {
+            fn from(index: i32) -> Self {
+                Self(index)
+            }
+        }
+
+        impl From<$name> for i32 {
+            fn from(index: $name) -> Self {
+                index.0
+            }
+        }
+
+        impl PartialEq<i32> for $name {
+            fn eq(&self, other: &i32) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+    };
+}
+
+macro_rules! id_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(pub String);
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                Self(id.to_string())
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+    };
+}
+
+index_type!(
+    /// 1-based index of a node: junctions first, then tanks and reservoirs.
+    NodeIndex
+);
+index_type!(
+    /// 1-based index of a link.
+    LinkIndex
+);
+index_type!(
+    /// 1-based index of a time pattern; 0 stands for no pattern.
+    PatternIndex
+);
+index_type!(
+    /// 1-based index of a data curve; 0 stands for no curve.
+    CurveIndex
+);
+index_type!(
+    /// 1-based position of a simple control.
+    ControlIndex
+);
+index_type!(
+    /// 1-based position of a rule-based control.
+    RuleIndex
+);
+
+id_type!(
+    /// ID of a node.
+    NodeId
+);
+id_type!(
+    /// ID of a link.
+    LinkId
+);
+id_type!(
+    /// ID of a time pattern.
+    PatternId
+);
+id_type!(
+    /// ID of a data curve.
+    CurveId
+);
+id_type!(
+    /// ID (label) of a rule-based control.
+    RuleId
+);
+
+impl NodeIndex {
+    /// ID of the node at this index.
+    pub fn id(self, project: &EPANET) -> Result<NodeId> {
+        project.get_node_id(self).map(NodeId)
+    }
+}
+
+impl LinkIndex {
+    /// ID of the link at this index.
+    pub fn id(self, project: &EPANET) -> Result<LinkId> {
+        project.get_link_id(self).map(LinkId)
+    }
+}
+
+impl PatternIndex {
+    /// ID of the pattern at this index.
+    pub fn id(self, project: &EPANET) -> Result<PatternId> {
+        project.get_pattern_id(self).map(PatternId)
+    }
+}
+
+impl CurveIndex {
+    /// ID of the curve at this index.
+    pub fn id(self, project: &EPANET) -> Result<CurveId> {
+        project.get_curve_id(self).map(CurveId)
+    }
+}
+
+impl RuleIndex {
+    /// ID of the rule at this index.
+    pub fn id(self, project: &EPANET) -> Result<RuleId> {
+        project.get_rule_id(self).map(RuleId)
+    }
+}
+
+impl NodeId {
+    /// Index of the node with this ID in `project`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 203 if there is no such node.
+    pub fn index(&self, project: &EPANET) -> Result<NodeIndex> {
+        project.get_node_index(self)
+    }
+}
+
+impl LinkId {
+    /// Index of the link with this ID in `project`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 204 if there is no such link.
+    pub fn index(&self, project: &EPANET) -> Result<LinkIndex> {
+        project.get_link_index(self)
+    }
+}
+
+impl PatternId {
+    /// Index of the pattern with this ID in `project`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 205 if there is no such pattern.
+    pub fn index(&self, project: &EPANET) -> Result<PatternIndex> {
+        project.get_pattern_index(self)
+    }
+}
+
+impl CurveId {
+    /// Index of the curve with this ID in `project`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 206 if there is no such curve.
+    pub fn index(&self, project: &EPANET) -> Result<CurveIndex> {
+        project.get_curve_index(self)
+    }
+}
+
+impl RuleId {
+    /// Index of the rule with this ID in `project`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 257 if there is no such rule.
+    pub fn index(&self, project: &EPANET) -> Result<RuleIndex> {
+        project.get_rule_index(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::test_utils::fixtures::*;
+    use crate::types::rule::PremiseObject;
+    use rstest::rstest;
+
+    #[rstest]
+    fn test_index_id_round_trip(ph: EPANET) {
+        let tank = NodeId::from("2").index(&ph).unwrap();
+        assert_eq!(tank, NodeIndex(11));
+        assert_eq!(tank.id(&ph).unwrap(), "2");
+        assert_eq!(ph.get_node_by_index(tank).unwrap().index(), tank);
+
+        let pump = LinkId::from("9").index(&ph).unwrap();
+        assert_eq!(pump.id(&ph).unwrap(), "9");
+        assert_eq!(ph.get_link_nodes(pump).unwrap().1, NodeIndex(1));
+
+        let pattern = PatternIndex(1);
+        assert_eq!(pattern.id(&ph).unwrap().index(&ph).unwrap(), pattern);
+        assert_eq!(NodeId::from("XX").index(&ph).unwrap_err().code, 203);
+        assert_eq!(LinkId::from("XX").index(&ph).unwrap_err().code, 204);
+    }
+
+    #[rstest]
+    fn test_rule_index_and_premise_object(ph: EPANET) {
+        ph.add_rule("RULE A\nIF NODE 2 LEVEL < 100\nTHEN LINK 9 STATUS = OPEN")
+            .unwrap();
+        ph.add_rule("RULE B\nIF SYSTEM TIME = 4\nTHEN LINK 9 STATUS = CLOSED")
+            .unwrap();
+
+        let index = RuleId::from("B").index(&ph).unwrap();
+        assert_eq!(index, RuleIndex(2));
+        assert_eq!(index.id(&ph).unwrap(), "B");
+        assert_eq!(RuleId::from("C").index(&ph).unwrap_err().code, 257);
+
+        let rule = ph.get_rule(1).unwrap();
+        assert_eq!(rule.premises[0].object, PremiseObject::Node(NodeIndex(11)));
+        assert_eq!(
+            rule.then_actions[0].link_index,
+            ph.get_link_index("9").unwrap()
+        );
+        let rule = ph.get_rule(index).unwrap();
+        assert_eq!(rule.premises[0].object, PremiseObject::System);
+    }
+}
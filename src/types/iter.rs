@@ -0,0 +1,190 @@
+//! Lazy element iteration: [`NodeRef`], [`LinkRef`], [`NodeIter`] and [`LinkIter`].
+//!
+//! [`nodes`](EPANET::nodes) and [`links`](EPANET::links) build a `Vec` of fully
+//! populated domain structs, reading every property from the engine up front.
+//! The iterators here yield lightweight references instead, holding only the
+//! project and the element index; properties are read from the engine when a
+//! method on the reference is called, and [`NodeRef::get`] / [`LinkRef::get`]
+//! load the full domain struct for the elements that need it.
+//!
+//! The index range is fixed when the iterator is created, so adding or deleting
+//! elements while iterating leaves it out of step with the model.
+
+use crate::epanet_error::*;
+use crate::types::handle::{LinkHandle, NodeHandle};
+use crate::types::index::{LinkId, LinkIndex, NodeId, NodeIndex};
+use crate::types::link::{Link, LinkProperty, LinkType};
+use crate::types::node::{Node, NodeProperty, NodeType};
+use crate::types::ObjectType;
+use crate::EPANET;
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+/// A node of a project, read from the engine on demand.
+#[derive(Debug, Copy, Clone)]
+pub struct NodeRef<'a> {
+    pub(crate) project: &'a EPANET,
+    pub(crate) index: NodeIndex,
+}
+
+/// A link of a project, read from the engine on demand.
+#[derive(Debug, Copy, Clone)]
+pub struct LinkRef<'a> {
+    pub(crate) project: &'a EPANET,
+    pub(crate) index: LinkIndex,
+}
+
+impl<'a> NodeRef<'a> {
+    /// 1-based index of the node.
+    pub fn index(&self) -> NodeIndex {
+        self.index
+    }
+
+    /// ID of the node.
+    pub fn id(&self) -> Result<NodeId> {
+        self.project.get_node_id(self.index).map(NodeId)
+    }
+
+    /// Type of the node.
+    pub fn node_type(&self) -> Result<NodeType> {
+        self.project.get_node_type(self.index)
+    }
+
+    /// Current value of a node property.
+    pub fn value(&self, property: NodeProperty) -> Result<f64> {
+        self.project.get_node_value(self.index, property)
+    }
+
+    /// Tag of the node, empty if it has none.
+    pub fn tag(&self) -> Result<String> {
+        self.project.get_tag(ObjectType::Node, self.index)
+    }
+
+    /// A [`NodeHandle`] to the node that stays valid when indices shift.
+    pub fn handle(&self) -> Result<NodeHandle> {
+        self.project.get_node_handle_by_index(self.index)
+    }
+
+    /// Loads the node as a fully populated domain struct.
+    pub fn get(&self) -> Result<Node<'a>> {
+        self.project.get_node_by_index(self.index)
+    }
+}
+
+impl<'a> LinkRef<'a> {
+    /// 1-based index of the link.
+    pub fn index(&self) -> LinkIndex {
+        self.index
+    }
+
+    /// ID of the link.
+    pub fn id(&self) -> Result<LinkId> {
+        self.project.get_link_id(self.index).map(LinkId)
+    }
+
+    /// Type of the link.
+    pub fn link_type(&self) -> Result<LinkType> {
+        self.project.get_link_type(self.index)
+    }
+
+    /// Start and end node indices of the link.
+    pub fn nodes(&self) -> Result<(NodeIndex, NodeIndex)> {
+        self.project.get_link_nodes(self.index)
+    }
+
+    /// Current value of a link property.
+    pub fn value(&self, property: LinkProperty) -> Result<f64> {
+        self.project.get_link_value(self.index, property)
+    }
+
+    /// Tag of the link, empty if it has none.
+    pub fn tag(&self) -> Result<String> {
+        self.project.get_tag(ObjectType::Link, self.index)
+    }
+
+    /// A [`LinkHandle`] to the link that stays valid when indices shift.
+    pub fn handle(&self) -> Result<LinkHandle> {
+        self.project.get_link_handle_by_index(self.index)
+    }
+
+    /// Loads the link as a fully populated domain struct.
+    pub fn get(&self) -> Result<Link<'a>> {
+        self.project.get_link_by_index(self.index)
+    }
+}
+
+/// Iterator over the nodes of a project in index order, created with
+/// [`EPANET::iter_nodes`].
+#[derive(Debug, Clone)]
+pub struct NodeIter<'a> {
+    pub(crate) project: &'a EPANET,
+    pub(crate) indices: Range<i32>,
+}
+
+/// Iterator over the links of a project in index order, created with
+/// [`EPANET::iter_links`].
+#[derive(Debug, Clone)]
+pub struct LinkIter<'a> {
+    pub(crate) project: &'a EPANET,
+    pub(crate) indices: Range<i32>,
+}
+
+impl<'a> Iterator for NodeIter<'a> {
+    type Item = NodeRef<'a>;
+
+    fn next(&mut self) -> Option<NodeRef<'a>> {
+        let index = NodeIndex(self.indices.next()?);
+        Some(NodeRef {
+            project: self.project,
+            index,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for NodeIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = NodeIndex(self.indices.next_back()?);
+        Some(NodeRef {
+            project: self.project,
+            index,
+        })
+    }
+}
+
+impl ExactSizeIterator for NodeIter<'_> {}
+
+impl FusedIterator for NodeIter<'_> {}
+
+impl<'a> Iterator for LinkIter<'a> {
+    type Item = LinkRef<'a>;
+
+    fn next(&mut self) -> Option<LinkRef<'a>> {
+        let index = LinkIndex(self.indices.next()?);
+        Some(LinkRef {
+            project: self.project,
+            index,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for LinkIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = LinkIndex(self.indices.next_back()?);
+        Some(LinkRef {
+            project: self.project,
+            index,
+        })
+    }
+}
+
+impl ExactSizeIterator for LinkIter<'_> {}
+
+impl FusedIterator for LinkIter<'_> {}
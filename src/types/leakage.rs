@@ -0,0 +1,83 @@
+//! Pipe leakage types: [`LeakClass`], [`LeakageStep`], [`PipeLeakage`],
+//! [`NodeLeakage`] and [`LeakageReport`].
+//!
+//! EPANET 2.3 models leakage from pipes with a fixed leak area and an area that
+//! expands with pressure head, both per 100 length units of pipe. Leakage from
+//! a pipe is split between its end nodes that are junctions. Volumes are in the volume
+//! unit of the project flow units: gallons for GPM, liters for LPS/LPM, cubic
+//! feet for CFS, cubic meters for CMH/CMD/CMS, and million gallons, acre-feet
+//! or megaliters for MGD/IMGD, AFD and MLD.
+
+use crate::types::index::{LinkIndex, NodeIndex};
+
+/// Leak parameters for the pipes carrying one tag, e.g. a material and
+/// installation period such as `"CI_1960"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeakClass {
+    /// Tag of the pipes in the class.
+    pub tag: String,
+    /// Leak area, in square millimeters per 100 length units of pipe.
+    pub leak_area: f64,
+    /// Increase of the leak area per unit of pressure head, in square
+    /// millimeters per 100 length units of pipe.
+    pub leak_expansion: f64,
+}
+
+/// System leakage at one hydraulic time step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeakageStep {
+    /// Elapsed time of the step, in seconds.
+    pub time: i64,
+    /// Total leakage from all pipes, in project flow units.
+    pub leakage: f64,
+    /// Leakage volume as a percentage of the volume supplied to the system
+    /// since the start of the run, as reported by the engine; zero at the
+    /// first step.
+    pub cumulative_percent_loss: f64,
+}
+
+/// Leakage from one pipe over a hydraulic run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipeLeakage {
+    /// Index of the pipe.
+    pub link: LinkIndex,
+    /// ID of the pipe.
+    pub id: String,
+    /// Volume lost over the run.
+    pub volume: f64,
+    /// Largest leakage rate at any step, in project flow units.
+    pub peak: f64,
+}
+
+/// Leakage assigned to one node over a hydraulic run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeLeakage {
+    /// Index of the node.
+    pub node: NodeIndex,
+    /// ID of the node.
+    pub id: String,
+    /// Volume lost over the run.
+    pub volume: f64,
+    /// Largest leakage rate at any step, in project flow units.
+    pub peak: f64,
+}
+
+/// Leakage over a hydraulic run, produced by
+/// [`EPANET::leakage_report`](crate::EPANET::leakage_report).
+///
+/// Each step is weighted by the time until the next step, so the final step
+/// of an extended period run, and a single-period run, carry no volume.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeakageReport {
+    /// System leakage at each hydraulic time step.
+    pub steps: Vec<LeakageStep>,
+    /// Pipes with leak parameters, in index order.
+    pub pipes: Vec<PipeLeakage>,
+    /// Nodes that received leakage at some step, in index order.
+    pub nodes: Vec<NodeLeakage>,
+    /// Total volume lost over the run.
+    pub total_volume: f64,
+    /// Leakage volume as a percentage of the volume supplied to the system
+    /// over the run.
+    pub percent_loss: f64,
+}
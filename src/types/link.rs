@@ -2,6 +2,8 @@
 use crate::bindings::*;
 use num_derive::FromPrimitive;
 use crate::EPANET;
+use crate::types::handle::LinkHandle;
+use crate::types::index::{CurveIndex, LinkIndex, NodeIndex, PatternIndex};
 use crate::types::ActionCodeType;
 use crate::epanet_error::*;
 
@@ -91,10 +93,13 @@ pub enum PumpStateType {
 /// the live query methods (flow(), velocity(), etc.) for computed results.
 pub struct Link<'a> {
     pub(crate) project: &'a EPANET,
-    pub(crate) index: i32,
+    pub(crate) index: LinkIndex,
     pub id: String,
-    pub from_node: i32,
-    pub to_node: i32,
+    pub from_node: NodeIndex,
+    pub to_node: NodeIndex,
+    /// Initial (input) status of the link, as set in the `[STATUS]` section.
+    /// This is not the status computed by the solver, which changes during a
+    /// run; read that with `get_link_value(index, LinkProperty::Status)`.
     pub status: LinkStatusType,
     pub kind: LinkKind,
 }
@@ -121,6 +126,11 @@ pub struct PipeData {
     pub roughness: f64,
     /// Minor loss coefficient.
     pub minor_loss: f64,
+    /// Leak area, in square millimeters per 100 length units of pipe.
+    pub leak_area: f64,
+    /// Increase of the leak area per unit of pressure head, in square
+    /// millimeters per 100 length units of pipe.
+    pub leak_expansion: f64,
 }
 
 /// Properties of a pump link.
@@ -132,11 +142,11 @@ pub struct PumpData {
     /// Initial speed multiplier (1.0 = design speed).
     pub speed: f64,
     /// Index of the head-vs-flow curve, if any.
-    pub head_curve_index: Option<i32>,
+    pub head_curve_index: Option<CurveIndex>,
     /// Index of the efficiency-vs-flow curve, if any.
-    pub efficiency_curve_index: Option<i32>,
+    pub efficiency_curve_index: Option<CurveIndex>,
     /// Index of the energy price time pattern, if any.
-    pub energy_pattern_index: Option<i32>,
+    pub energy_pattern_index: Option<PatternIndex>,
     /// Average energy price (cost per kWh).
     pub energy_cost: f64,
 }
@@ -148,7 +158,7 @@ pub struct ValveData {
     /// Valve setting (pressure for PRV/PSV/PBV, flow for FCV, loss coeff. for TCV/GPV/PCV).
     pub setting: f64,
     /// Head-loss or loss-coefficient curve index (GPV and PCV only).
-    pub curve_index: Option<i32>,
+    pub curve_index: Option<CurveIndex>,
 }
 
 impl<'a> Link<'a> {
@@ -181,6 +191,8 @@ impl<'a> Link<'a> {
                 diameter,
                 roughness,
                 minor_loss,
+                leak_area: 0.0,
+                leak_expansion: 0.0,
             }),
         })
     }
@@ -193,7 +205,7 @@ impl<'a> Link<'a> {
         to_node: &str,
         power: f64,
         speed: f64,
-        head_curve_index: Option<i32>,
+        head_curve_index: Option<CurveIndex>,
     ) -> Result<Self> {
         let index = project.add_link(id, LinkType::Pump, from_node, to_node)?;
         project.set_link_value(index, LinkProperty::PumpPower, power)?;
@@ -261,7 +273,7 @@ impl<'a> Link<'a> {
     }
 
     /// Returns the 1-based EPANET index of this link.
-    pub fn index(&self) -> i32 { self.index }
+    pub fn index(&self) -> LinkIndex { self.index }
 
     /// Push cached fields back to the C engine.
     pub fn update(&self) -> Result<()> {
@@ -278,7 +290,9 @@ impl<'a> Link<'a> {
             LinkKind::Pipe(d) | LinkKind::CvPipe(d) => {
                 self.project.set_pipe_data(
                     self.index, d.length, d.diameter, d.roughness, d.minor_loss,
-                )
+                )?;
+                self.project.set_link_value(self.index, LinkProperty::LeakArea, d.leak_area)?;
+                self.project.set_link_value(self.index, LinkProperty::LeakExpan, d.leak_expansion)
             }
             LinkKind::Pump(d) => {
                 self.project.set_link_value(self.index, LinkProperty::PumpPower, d.power)?;
@@ -302,6 +316,67 @@ impl<'a> Link<'a> {
         self.project.delete_link(self.index, action_code)
     }
 
+    /// Re-reads all cached fields from the engine, discarding local changes.
+    /// [`status`](Self::status) is re-read as the initial status, so refreshing
+    /// during a run does not pick up the solver's status.
+    ///
+    /// The link is looked up by its index; if links before it were deleted,
+    /// hold a [`LinkHandle`] and re-fetch the link through it instead.
+    pub fn refresh(&mut self) -> Result<()> {
+        *self = self.project.get_link_by_index(self.index)?;
+        Ok(())
+    }
+
+    /// Returns whether any cached field differs from the engine.
+    pub fn is_stale(&self) -> Result<bool> {
+        Ok(!self.diff_from_engine()?.is_empty())
+    }
+
+    /// Returns the names of the cached fields that differ from the engine,
+    /// e.g. `["status", "diameter"]`. A change of link type is reported as `"kind"`.
+    /// `"status"` compares against the initial status, so a link is not stale
+    /// just because the solver opened or closed it.
+    pub fn diff_from_engine(&self) -> Result<Vec<&'static str>> {
+        let engine = self.project.get_link_by_index(self.index)?;
+        let mut changed = changed_fields!(self, engine, id, from_node, to_node, status);
+        match (&self.kind, &engine.kind) {
+            (LinkKind::Pipe(cached), LinkKind::Pipe(engine))
+            | (LinkKind::CvPipe(cached), LinkKind::CvPipe(engine)) => changed.extend(
+                changed_fields!(
+                    cached,
+                    engine,
+                    length,
+                    diameter,
+                    roughness,
+                    minor_loss,
+                    leak_area,
+                    leak_expansion
+                ),
+            ),
+            (LinkKind::Pump(cached), LinkKind::Pump(engine)) => changed.extend(changed_fields!(
+                cached,
+                engine,
+                pump_type,
+                power,
+                speed,
+                head_curve_index,
+                efficiency_curve_index,
+                energy_pattern_index,
+                energy_cost
+            )),
+            (LinkKind::Valve(cached), LinkKind::Valve(engine)) => {
+                changed.extend(changed_fields!(cached, engine, diameter, setting, curve_index))
+            }
+            _ => changed.push("kind"),
+        }
+        Ok(changed)
+    }
+
+    /// Returns a [`LinkHandle`] to this link that stays valid when indices shift.
+    pub fn handle(&self) -> Result<LinkHandle> {
+        self.project.get_link_handle_by_index(self.index)
+    }
+
     // --- Live computed results (read from C engine, not cached) ---
 
     /// Returns the current computed flow rate (positive = from-node to to-node).
@@ -413,7 +488,7 @@ impl<'a> Link<'a> {
     }
 
     /// Get the start and end node indices.
-    pub fn nodes(&self) -> (i32, i32) {
+    pub fn nodes(&self) -> (NodeIndex, NodeIndex) {
         (self.from_node, self.to_node)
     }
 }
@@ -494,5 +569,47 @@ mod tests {
         assert_eq!(retrieved_data.length, 2000.0);
         assert_eq!(retrieved_data.diameter, 14.0);
     }
-}
 
+    #[rstest]
+    fn test_link_refresh_and_diff(ph_close: crate::EPANET) {
+        let _n1 = crate::types::node::Node::new_junction(&ph_close, "N1", 100.0, 50.0, "").unwrap();
+        let _n2 = crate::types::node::Node::new_junction(&ph_close, "N2", 100.0, 50.0, "").unwrap();
+        let mut link = Link::new_pipe(&ph_close, "P1", "N1", "N2", 1000.0, 12.0, 100.0, 0.0).unwrap();
+        assert!(!link.is_stale().unwrap());
+
+        // Local edits are reported too, until they are pushed with update()
+        link.as_pipe_mut().unwrap().roughness = 120.0;
+        ph_close.set_link_value(link.index(), LinkProperty::Diameter, 16.0).unwrap();
+        assert_eq!(link.diff_from_engine().unwrap(), vec!["diameter", "roughness"]);
+
+        // A type change replaces the whole kind
+        ph_close.set_link_type(link.index(), LinkType::Prv, ActionCodeType::Unconditional).unwrap();
+        assert_eq!(link.diff_from_engine().unwrap(), vec!["kind"]);
+
+        link.refresh().unwrap();
+        assert!(link.is_valve());
+        assert!(!link.is_stale().unwrap());
+    }
+
+    #[rstest]
+    fn test_link_status_is_initial_status(ph: crate::EPANET) {
+        use crate::types::analysis::InitHydOption;
+
+        // Run until a control shuts pump 9 off
+        let pump = ph.get_link("9").unwrap();
+        ph.open_h().unwrap();
+        ph.init_h(InitHydOption::NoSave).unwrap();
+        loop {
+            ph.run_h().unwrap();
+            if ph.get_link_value(pump.index(), LinkProperty::Status).unwrap() == 0.0 {
+                break;
+            }
+            assert!(ph.next_h().unwrap() > 0, "pump 9 never closed");
+        }
+
+        // The cached status is the input status, which the solver leaves alone
+        assert_eq!(ph.get_link("9").unwrap().status, LinkStatusType::Open);
+        assert!(!pump.diff_from_engine().unwrap().contains(&"status"));
+        ph.close_h().unwrap();
+    }
+}
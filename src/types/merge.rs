@@ -0,0 +1,105 @@
+//! Model merge types: [`MergeOptions`], [`ConflictRule`] and [`MergeReport`].
+//!
+//! [`EPANET::merge`](crate::EPANET::merge) imports a second model, such as a
+//! proposed sub-network kept in its own INP file, into the current project.
+
+use crate::types::index::ControlIndex;
+
+/// What to do with an incoming element whose ID is already used in the project.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConflictRule {
+    /// Keep the existing element and use it wherever the incoming model refers
+    /// to the incoming one. For nodes this makes them boundary nodes: incoming
+    /// links connect to the existing node.
+    Keep,
+    /// Overwrite the existing element with the incoming element's data. Nodes
+    /// and links must be of the same type.
+    Replace,
+    /// Add the incoming element under its ID with the lowest free numeric
+    /// suffix, e.g. `P1_2`.
+    Rename,
+    /// Fail with error 215 (duplicate ID).
+    Fail,
+}
+
+/// Settings for [`EPANET::merge`](crate::EPANET::merge).
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeOptions {
+    /// Prepended to the ID of every incoming element, e.g. `P1-`. Incoming nodes
+    /// whose unprefixed ID exists in the project are boundary nodes when
+    /// `nodes` is [`ConflictRule::Keep`], and are not prefixed.
+    pub prefix: String,
+    pub nodes: ConflictRule,
+    pub links: ConflictRule,
+    pub patterns: ConflictRule,
+    pub curves: ConflictRule,
+    pub rules: ConflictRule,
+    /// Copy the incoming simple controls, with their node and link references
+    /// remapped to the merged elements.
+    pub copy_controls: bool,
+    /// Copy the incoming rule-based controls, remapped like the controls.
+    pub copy_rules: bool,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            prefix: String::new(),
+            nodes: ConflictRule::Keep,
+            links: ConflictRule::Rename,
+            patterns: ConflictRule::Keep,
+            curves: ConflictRule::Keep,
+            rules: ConflictRule::Rename,
+            copy_controls: true,
+            copy_rules: true,
+        }
+    }
+}
+
+/// How an incoming element was merged.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MergeOutcome {
+    /// Added under its (prefixed) ID.
+    Added,
+    /// Added under a new ID because its (prefixed) ID was taken.
+    Renamed,
+    /// Its data overwrote the existing element with the same ID.
+    Replaced,
+    /// The existing element with the same ID was used instead, e.g. a boundary
+    /// node.
+    Kept,
+}
+
+/// An incoming element and the project element it became.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergedElement {
+    /// ID in the incoming model.
+    pub source_id: String,
+    /// ID in the project after the merge.
+    pub id: String,
+    pub outcome: MergeOutcome,
+}
+
+/// The result of [`EPANET::merge`](crate::EPANET::merge), listing the incoming
+/// elements in their original order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    pub nodes: Vec<MergedElement>,
+    pub links: Vec<MergedElement>,
+    pub patterns: Vec<MergedElement>,
+    pub curves: Vec<MergedElement>,
+    pub rules: Vec<MergedElement>,
+    /// Project indices of the added simple controls.
+    pub controls: Vec<ControlIndex>,
+}
+
+impl MergeReport {
+    /// IDs of the boundary nodes: incoming nodes that were connected to existing
+    /// nodes instead of being added.
+    pub fn boundary_nodes(&self) -> impl Iterator<Item = &str> {
+        self.nodes
+            .iter()
+            .filter(|n| n.outcome == MergeOutcome::Kept)
+            .map(|n| n.id.as_str())
+    }
+}
@@ -3,7 +3,9 @@
 //! Types are organized into submodules by domain. RAII domain structs
 //! ([`Control`], [`Curve`], [`Demand`], [`Pattern`], [`Rule`]) hold a reference
 //! to their parent [`EPANET`](crate::EPANET) project and expose `.update()` /
-//! `.delete()` methods for syncing changes back to the C engine.
+//! `.delete()` methods for syncing changes back to the C engine, and
+//! `.refresh()` / `.is_stale()` / `.diff_from_engine()` for picking up changes
+//! made through the index-based API.
 //!
 //! | Submodule | Key types |
 //! |---|---|
@@ -16,25 +18,91 @@
 //! | [`pattern`] | [`Pattern`](pattern::Pattern) |
 //! | [`rule`] | [`Rule`](rule::Rule), [`Premise`](rule::Premise), [`ActionClause`](rule::ActionClause) |
 //! | [`options`] | [`FlowUnits`](options::FlowUnits), [`HeadLossType`](options::HeadLossType), [`TimeParameter`](options::TimeParameter), [`Option`](options::Option) |
+//! | [`schedule`] | [`PumpScheduleOptions`](schedule::PumpScheduleOptions), [`PumpSchedule`](schedule::PumpSchedule), [`ScheduleEvaluation`](schedule::ScheduleEvaluation) |
+//! | [`design`] | [`PipeSize`](design::PipeSize), [`LoadingCondition`](design::LoadingCondition), [`PipeSizingOptions`](design::PipeSizingOptions), [`PipeSizingResult`](design::PipeSizingResult) |
+//! | [`sensitivity`] | [`SensitivityOptions`](sensitivity::SensitivityOptions), [`SensitivityParameter`](sensitivity::SensitivityParameter), [`SensitivityResult`](sensitivity::SensitivityResult) |
+//! | [`skeleton`] | [`SkeletonOptions`](skeleton::SkeletonOptions), [`Reduction`](skeleton::Reduction), [`SkeletonReport`](skeleton::SkeletonReport) |
+//! | [`flushing`] | [`FlushingStep`](flushing::FlushingStep), [`HydrantDischarge`](flushing::HydrantDischarge), [`FlushingStepReport`](flushing::FlushingStepReport) |
+//! | [`export`] | [`ExportOptions`](export::ExportOptions), [`ResultWriter`](export::ResultWriter), [`ExportLayout`](export::ExportLayout) |
+//! | `arrow` | `RecordedResults`, `write_parquet` (`arrow` feature) |
+//! | `geojson` | `GeoJsonFields`, `GeoJsonExportOptions`, `GeoJsonImportOptions` (`geojson` feature) |
+//! | [`gis`] | [`Polyline`](gis::Polyline), [`ElevationSource`](gis::ElevationSource), [`PolylineModelOptions`](gis::PolylineModelOptions), [`PolylineModel`](gis::PolylineModel) |
+//! | [`render`] | [`SvgOptions`](render::SvgOptions), [`Coloring`](render::Coloring), [`Legend`](render::Legend) |
+//! | [`validation`] | [`Finding`](validation::Finding), [`FindingKind`](validation::FindingKind), [`Severity`](validation::Severity) |
+//! | [`diff`] | [`DiffOptions`](diff::DiffOptions), [`Change`](diff::Change), [`ModelDiff`](diff::ModelDiff) |
+//! | [`merge`] | [`MergeOptions`](merge::MergeOptions), [`ConflictRule`](merge::ConflictRule), [`MergeReport`](merge::MergeReport) |
+//! | [`index`] | [`NodeIndex`], [`LinkIndex`], [`PatternIndex`], [`CurveIndex`], [`ControlIndex`], [`RuleIndex`] and the matching ID types |
+//! | [`handle`] | [`NodeHandle`](handle::NodeHandle), [`LinkHandle`](handle::LinkHandle) |
+//! | [`iter`] | [`NodeRef`](iter::NodeRef), [`LinkRef`](iter::LinkRef), [`NodeIter`](iter::NodeIter), [`LinkIter`](iter::LinkIter) |
+//! | [`arrays`] | [`NetworkArrays`](arrays::NetworkArrays), [`CsrMatrix`](arrays::CsrMatrix) |
+//! | `ndarray` | `ResultArrays` (`ndarray` feature) |
+//! | [`pda`] | [`PdaSummary`](pda::PdaSummary), [`PdaStepSummary`](pda::PdaStepSummary), [`DeficientNode`](pda::DeficientNode), [`NodeService`](pda::NodeService) |
+//! | [`leakage`] | [`LeakClass`](leakage::LeakClass), [`LeakageReport`](leakage::LeakageReport), [`LeakageStep`](leakage::LeakageStep), [`PipeLeakage`](leakage::PipeLeakage), [`NodeLeakage`](leakage::NodeLeakage) |
+//! | [`emitter`] | [`Emitter`](emitter::Emitter), [`LeakLocalizationOptions`](emitter::LeakLocalizationOptions), [`LeakCandidate`](emitter::LeakCandidate) |
+//! | [`allocation`] | [`BillingRecord`](allocation::BillingRecord), [`AllocationMethod`](allocation::AllocationMethod), [`AllocationOptions`](allocation::AllocationOptions), [`AllocationReport`](allocation::AllocationReport) |
 //! | [`report`] | [`ReportCallback`](report::ReportCallback) type alias and trampoline |
 //!
 //! Common types re-exported at this level: [`ObjectType`], [`CountType`], [`ActionCodeType`],
-//! [`Control`], [`Curve`], [`Demand`], [`Pattern`], [`Rule`], [`ReportCallback`].
+//! [`Control`], [`Curve`], [`Demand`], [`Pattern`], [`Rule`], [`ReportCallback`], and the
+//! typed indices and IDs from [`index`].
 
+/// Names of the listed fields that differ between a cached domain struct and
+/// a fresh copy read from the engine, for the `diff_from_engine` methods.
+macro_rules! changed_fields {
+    ($cached:expr, $engine:expr, $($field:ident),+ $(,)?) => {{
+        let mut changed: Vec<&'static str> = Vec::new();
+        $(
+            if $cached.$field != $engine.$field {
+                changed.push(stringify!($field));
+            }
+        )+
+        changed
+    }};
+}
+
+pub mod allocation;
 pub mod analysis;
+pub mod arrays;
+#[cfg(feature = "arrow")]
+pub mod arrow;
 pub mod control;
 pub mod curve;
 pub mod demand;
+pub mod design;
+pub mod diff;
+pub mod emitter;
+pub mod export;
+pub mod flushing;
+#[cfg(feature = "geojson")]
+pub mod geojson;
+pub mod gis;
+pub mod handle;
+pub mod index;
+pub mod iter;
+pub mod leakage;
 pub mod link;
+pub mod merge;
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
 pub mod node;
 pub mod options;
 pub mod pattern;
+pub mod pda;
+pub mod render;
 pub mod report;
 pub mod rule;
+pub mod schedule;
+pub mod sensitivity;
+pub mod skeleton;
+pub mod validation;
 
 pub use control::Control;
 pub use curve::Curve;
 pub use demand::Demand;
+pub use index::{
+    ControlIndex, CurveId, CurveIndex, LinkId, LinkIndex, NodeId, NodeIndex, PatternId, PatternIndex,
+    RuleId, RuleIndex,
+};
 pub use num_derive::FromPrimitive;
 pub use num_traits::FromPrimitive;
 pub use pattern::Pattern;
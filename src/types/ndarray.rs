@@ -0,0 +1,60 @@
+//! ndarray result types: [`ResultArrays`].
+//!
+//! Available with the `ndarray` feature. Results recorded with
+//! [`EPANET::record_result_arrays`](crate::EPANET::record_result_arrays) are
+//! held as one [`Array2`] per property, with a row per recorded time step and a
+//! column per element, ready for numerical code without reshaping. Batch
+//! getters returning [`Array1`] are
+//! [`EPANET::get_node_values_array`](crate::EPANET::get_node_values_array) and
+//! [`EPANET::get_link_values_array`](crate::EPANET::get_link_values_array).
+
+use crate::types::link::LinkProperty;
+use crate::types::node::NodeProperty;
+use ndarray::{Array1, Array2};
+
+/// Node and link results recorded at each time step of a simulation, one
+/// `time × element` matrix per property.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultArrays {
+    /// Elapsed time of each recorded step, in seconds.
+    pub times: Array1<i64>,
+    /// IDs of the recorded nodes, in column order.
+    pub node_ids: Vec<String>,
+    /// IDs of the recorded links, in column order.
+    pub link_ids: Vec<String>,
+    /// Recorded node properties.
+    pub node_properties: Vec<NodeProperty>,
+    /// Recorded link properties.
+    pub link_properties: Vec<LinkProperty>,
+    /// One matrix per entry of `node_properties`, with a row per step and a
+    /// column per node.
+    pub node_values: Vec<Array2<f64>>,
+    /// One matrix per entry of `link_properties`, with a row per step and a
+    /// column per link.
+    pub link_values: Vec<Array2<f64>>,
+}
+
+impl ResultArrays {
+    /// The `time × node` matrix of a node property, or `None` if it was not
+    /// recorded.
+    pub fn node(&self, property: NodeProperty) -> Option<&Array2<f64>> {
+        self.node_properties
+            .iter()
+            .position(|p| *p == property)
+            .map(|position| &self.node_values[position])
+    }
+
+    /// The `time × link` matrix of a link property, or `None` if it was not
+    /// recorded.
+    pub fn link(&self, property: LinkProperty) -> Option<&Array2<f64>> {
+        self.link_properties
+            .iter()
+            .position(|p| *p == property)
+            .map(|position| &self.link_values[position])
+    }
+
+    /// Number of recorded time steps.
+    pub fn step_count(&self) -> usize {
+        self.times.len()
+    }
+}
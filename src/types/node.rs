@@ -3,6 +3,8 @@ use crate::bindings::*;
 use crate::epanet_error::*;
 use crate::EPANET;
 use num_derive::FromPrimitive;
+use crate::types::handle::NodeHandle;
+use crate::types::index::NodeIndex;
 use crate::types::ActionCodeType;
 
 #[non_exhaustive]
@@ -90,7 +92,7 @@ pub enum SourceType {
 /// ```
 pub struct Node<'a> {
     pub(crate) project: &'a EPANET,
-    pub(crate) index: i32,
+    pub(crate) index: NodeIndex,
     pub id: String,
     pub kind: NodeKind,
 }
@@ -104,7 +106,10 @@ pub enum NodeKind {
 pub struct JunctionData {
     pub elevation: f64,
     pub demand: f64,
-    pub demand_pattern: String
+    pub demand_pattern: String,
+    /// Emitter discharge coefficient, zero for no emitter. The discharge law
+    /// shared by all emitters is set with [`EPANET::set_emitter`].
+    pub emitter: f64,
 }
 
 pub struct TankData {
@@ -141,6 +146,7 @@ impl<'a> Node<'a> {
                 elevation,
                 demand,
                 demand_pattern: demand_pattern.to_string(),
+                emitter: 0.0,
             }),
         })
     }
@@ -208,7 +214,7 @@ impl<'a> Node<'a> {
         match &self.kind {
             NodeKind::Junction(d) => {
                 self.project.set_junction_data(self.index, d.elevation, d.demand, &d.demand_pattern)?;
-                Ok(())
+                self.project.set_node_value(self.index, NodeProperty::Emitter, d.emitter)
             }
             NodeKind::Tank(d) => {
                 self.project.set_tank_data(
@@ -231,6 +237,56 @@ impl<'a> Node<'a> {
     pub fn delete(self, action_code: ActionCodeType) -> Result<()> {
         self.project.delete_node(self.index, action_code)
     }
+    /// Re-reads all cached fields from the engine, discarding local changes.
+    ///
+    /// The node is looked up by its index; if nodes before it were deleted,
+    /// hold a [`NodeHandle`] and re-fetch the node through it instead.
+    pub fn refresh(&mut self) -> Result<()> {
+        *self = self.project.get_node_by_index(self.index)?;
+        Ok(())
+    }
+    /// Whether any cached field differs from the engine.
+    pub fn is_stale(&self) -> Result<bool> {
+        Ok(!self.diff_from_engine()?.is_empty())
+    }
+    /// Names of the cached fields that differ from the engine, e.g. `["id", "elevation"]`.
+    /// A change of node type is reported as `"kind"`.
+    pub fn diff_from_engine(&self) -> Result<Vec<&'static str>> {
+        let engine = self.project.get_node_by_index(self.index)?;
+        let mut changed = changed_fields!(self, engine, id);
+        match (&self.kind, &engine.kind) {
+            (NodeKind::Junction(cached), NodeKind::Junction(engine)) => {
+                changed.extend(changed_fields!(
+                    cached,
+                    engine,
+                    elevation,
+                    demand,
+                    demand_pattern,
+                    emitter
+                ))
+            }
+            (NodeKind::Tank(cached), NodeKind::Tank(engine)) => changed.extend(changed_fields!(
+                cached,
+                engine,
+                elevation,
+                init_level,
+                min_level,
+                max_level,
+                diameter,
+                min_volume,
+                volume_curve
+            )),
+            (NodeKind::Reservoir(cached), NodeKind::Reservoir(engine)) => {
+                changed.extend(changed_fields!(cached, engine, elevation))
+            }
+            _ => changed.push("kind"),
+        }
+        Ok(changed)
+    }
+    /// A [`NodeHandle`] to this node that stays valid when indices shift.
+    pub fn handle(&self) -> Result<NodeHandle> {
+        self.project.get_node_handle_by_index(self.index)
+    }
     pub fn pressure(&self) -> Result<f64> {
         self.project.get_node_value(self.index, NodeProperty::Pressure)
     }
@@ -286,7 +342,7 @@ impl<'a> Node<'a> {
         }
     }
 
-    pub fn index(&self) -> i32 {
+    pub fn index(&self) -> NodeIndex {
         self.index
     }
 
@@ -383,4 +439,20 @@ mod tests {
         assert_eq!(retrieved_data.elevation, 150.0);
         assert_eq!(retrieved_data.demand, 75.0);
     }
+
+    #[rstest]
+    fn test_node_refresh_and_diff(ph_close: crate::EPANET) {
+        let mut node = Node::new_junction(&ph_close, "J1", 100.0, 50.0, "").unwrap();
+        assert!(!node.is_stale().unwrap());
+
+        // Edit through the index-based API
+        ph_close.set_node_value(node.index(), NodeProperty::Elevation, 120.0).unwrap();
+        ph_close.set_node_id(node.index(), "J1A").unwrap();
+        assert_eq!(node.diff_from_engine().unwrap(), vec!["id", "elevation"]);
+
+        node.refresh().unwrap();
+        assert_eq!(node.id, "J1A");
+        assert_eq!(node.as_junction().unwrap().elevation, 120.0);
+        assert!(!node.is_stale().unwrap());
+    }
 }
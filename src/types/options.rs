@@ -1,6 +1,7 @@
 //! Analysis option enumerations: [`FlowUnits`], [`HeadLossType`], [`TimeParameter`],
 //! [`Option`], [`QualityType`], and related types.
 use crate::bindings::*;
+use crate::types::index::NodeIndex;
 use num_derive::FromPrimitive;
 #[cfg(test)]
 use strum_macros::EnumIter;
@@ -43,6 +44,23 @@ pub enum FlowUnits {
     Cms = EN_FlowUnits_EN_CMS as i32, // Cubic meters per second
 }
 
+impl FlowUnits {
+    /// Seconds in the time unit of the flow unit, so that `flow * seconds /
+    /// seconds_per_unit()` is a volume: gallons for GPM, liters for LPS/LPM, cubic
+    /// feet for CFS, cubic meters for CMH/CMD/CMS, and the volume unit of the flow
+    /// unit (million gallons, acre-feet, megaliters) otherwise.
+    pub(crate) fn seconds_per_unit(self) -> f64 {
+        match self {
+            FlowUnits::Cfs | FlowUnits::Lps | FlowUnits::Cms => 1.0,
+            FlowUnits::Gpm | FlowUnits::Lpm => 60.0,
+            FlowUnits::Cmh => 3600.0,
+            FlowUnits::Mgd | FlowUnits::Imgd | FlowUnits::Afd | FlowUnits::Mld | FlowUnits::Cmd => {
+                86400.0
+            }
+        }
+    }
+}
+
 #[non_exhaustive]
 #[derive(Debug, Copy, Clone, PartialEq, FromPrimitive)]
 #[repr(i32)]
@@ -163,5 +181,5 @@ pub struct QualityAnalysisInfo {
     pub quality_type: QualityType,
     pub chem_name: String,
     pub chem_units: String,
-    pub trace_node_index: i32,
+    pub trace_node_index: NodeIndex,
 }
@@ -1,4 +1,5 @@
 //! Time pattern types: [`Pattern`] RAII struct.
+use crate::types::index::PatternIndex;
 use crate::{epanet_error::*, EPANET};
 
 /// A struct representing a time pattern in an EPANET project.
@@ -41,7 +42,7 @@ pub struct Pattern<'a> {
     /// Reference to the owning EPANET project.
     pub(crate) project: &'a EPANET,
     /// EPANET project index of the pattern (1-based).
-    pub(crate) index: i32,
+    pub(crate) index: PatternIndex,
     /// Pattern ID.
     pub id: String,
     /// Pattern multipliers, one per time period.
@@ -73,7 +74,7 @@ impl<'a> Pattern<'a> {
     }
 
     /// Returns the EPANET project index of the pattern (1-based).
-    pub fn index(&self) -> i32 {
+    pub fn index(&self) -> PatternIndex {
         self.index
     }
 
@@ -104,6 +105,36 @@ impl<'a> Pattern<'a> {
         self.project.delete_pattern(self.index)
     }
 
+    /// Re-reads the ID and multipliers from the engine, discarding local changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if the pattern no longer exists.
+    pub fn refresh(&mut self) -> Result<()> {
+        *self = self.project.get_pattern_by_index(self.index)?;
+        Ok(())
+    }
+
+    /// Returns whether the ID or multipliers differ from the engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if the pattern no longer exists.
+    pub fn is_stale(&self) -> Result<bool> {
+        Ok(!self.diff_from_engine()?.is_empty())
+    }
+
+    /// Returns the names of the cached fields that differ from the engine,
+    /// e.g. `["multipliers"]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EPANETError`] if the pattern no longer exists.
+    pub fn diff_from_engine(&self) -> Result<Vec<&'static str>> {
+        let engine = self.project.get_pattern_by_index(self.index)?;
+        Ok(changed_fields!(self, engine, id, multipliers))
+    }
+
     /// Returns the average of the pattern multipliers as computed by the C engine.
     ///
     /// This queries the C API directly rather than computing from the cached
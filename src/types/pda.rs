@@ -0,0 +1,121 @@
+//! Pressure driven analysis summary types: [`DeficientNode`], [`PdaStepSummary`],
+//! [`NodeService`] and [`PdaSummary`].
+//!
+//! Under pressure driven analysis (PDA) a junction receives less than its full
+//! demand when its pressure falls below the required pressure. The summaries
+//! here combine the engine's `FullDemand`, `DemandFlow` and `DemandDeficit`
+//! node results with its `DeficientNodes` and `DemandReduction` statistics.
+//! Volumes are in the volume unit of the project flow units: gallons for GPM,
+//! liters for LPS/LPM, cubic feet for CFS, cubic meters for CMH/CMD/CMS, and
+//! million gallons, acre-feet or megaliters for MGD/IMGD, AFD and MLD.
+
+use crate::types::index::NodeIndex;
+
+/// A junction that receives less than its full demand at one time step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeficientNode {
+    /// Index of the junction.
+    pub node: NodeIndex,
+    /// ID of the junction.
+    pub id: String,
+    /// Demand requested, in project flow units.
+    pub full_demand: f64,
+    /// Demand delivered, in project flow units.
+    pub delivered: f64,
+    /// Delivered demand as a percentage of the full demand.
+    pub percent_satisfied: f64,
+}
+
+/// Demand delivery at one hydraulic time step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdaStepSummary {
+    /// Elapsed time of the step, in seconds.
+    pub time: i64,
+    /// Junctions receiving less than their full demand, in index order.
+    pub deficient_nodes: Vec<DeficientNode>,
+    /// Total demand requested by all junctions, in project flow units.
+    pub full_demand: f64,
+    /// Total demand delivered to all junctions, in project flow units.
+    pub delivered: f64,
+    /// Number of pressure deficient nodes reported by the engine.
+    pub engine_deficient_nodes: i32,
+    /// Percent demand reduction at pressure deficient nodes reported by the
+    /// engine.
+    pub engine_demand_reduction: f64,
+}
+
+impl PdaStepSummary {
+    /// Demand not delivered, in project flow units.
+    pub fn unmet_demand(&self) -> f64 {
+        self.full_demand - self.delivered
+    }
+}
+
+/// Demand delivery to one junction over a whole run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeService {
+    /// Index of the junction.
+    pub node: NodeIndex,
+    /// ID of the junction.
+    pub id: String,
+    /// Volume requested over the run.
+    pub requested_volume: f64,
+    /// Volume delivered over the run.
+    pub delivered_volume: f64,
+    /// Percentage of the full demand delivered, averaged over the time in
+    /// which the junction had a demand; 100 for a junction that never had one.
+    /// A run of zero duration averages its steps unweighted.
+    pub service_level: f64,
+    /// Time in which the junction received less than its full demand, in
+    /// seconds.
+    pub deficient_time: i64,
+    /// Number of time steps at which the junction received less than its
+    /// full demand.
+    pub deficient_steps: usize,
+}
+
+impl NodeService {
+    /// Volume requested but not delivered over the run.
+    pub fn unmet_volume(&self) -> f64 {
+        self.requested_volume - self.delivered_volume
+    }
+}
+
+/// Demand delivery over a hydraulic run, produced by
+/// [`EPANET::pda_summary`](crate::EPANET::pda_summary).
+///
+/// Each step is weighted by the time until the next step, so the final step
+/// of an extended period run, and a single-period run, carry no volume.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdaSummary {
+    /// Summary of each hydraulic time step.
+    pub steps: Vec<PdaStepSummary>,
+    /// Service of every junction, in index order.
+    pub nodes: Vec<NodeService>,
+    /// Total volume requested over the run.
+    pub requested_volume: f64,
+    /// Total volume delivered over the run.
+    pub delivered_volume: f64,
+}
+
+impl PdaSummary {
+    /// Total volume requested but not delivered over the run.
+    pub fn unmet_volume(&self) -> f64 {
+        self.requested_volume - self.delivered_volume
+    }
+
+    /// Delivered volume as a percentage of the requested volume; 100 when no
+    /// volume was requested.
+    pub fn percent_delivered(&self) -> f64 {
+        if self.requested_volume > 0.0 {
+            100.0 * self.delivered_volume / self.requested_volume
+        } else {
+            100.0
+        }
+    }
+
+    /// Junctions that were deficient at some step, in index order.
+    pub fn deficient_nodes(&self) -> impl Iterator<Item = &NodeService> {
+        self.nodes.iter().filter(|node| node.deficient_steps > 0)
+    }
+}
@@ -0,0 +1,126 @@
+//! Network map rendering types: [`SvgOptions`], [`Coloring`] and [`Legend`].
+//!
+//! [`EPANET::render_svg`](crate::EPANET::render_svg) draws the network from node
+//! coordinates and link vertices as a standalone SVG document, so result maps can
+//! be produced without a GUI (e.g. in CI reports).
+
+use crate::epanet_error::*;
+use crate::types::link::LinkProperty;
+use crate::types::node::NodeProperty;
+
+/// What determines the colour of nodes or links.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Coloring<P> {
+    /// A single neutral colour.
+    None,
+    /// The current value of a property, e.g. pressure after
+    /// [`solve_h`](crate::EPANET::solve_h) or at a solver step.
+    Property(P),
+    /// One value per element in index order, e.g. a time step of recorded
+    /// results. The legend title defaults to `value`.
+    Values(Vec<f64>),
+}
+
+/// Class breaks and colours for a coloured map.
+///
+/// Values below `breaks[0]` take `colors[0]`, values from `breaks[i - 1]` up to
+/// `breaks[i]` take `colors[i]`, and values at or above the last break take the
+/// last colour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Legend {
+    /// Ascending class breaks.
+    pub breaks: Vec<f64>,
+    /// SVG colours, one more than there are breaks.
+    pub colors: Vec<String>,
+    /// Legend heading; defaults to the property name.
+    pub title: Option<String>,
+}
+
+impl Legend {
+    /// Blue-to-red ramp used for automatic legends.
+    pub const DEFAULT_COLORS: [&'static str; 5] =
+        ["#2b83ba", "#abdda4", "#ffbf00", "#fdae61", "#d7191c"];
+
+    /// A legend dividing `min` to `max` into `colors.len()` equal classes.
+    pub fn equal_intervals(min: f64, max: f64, colors: &[&str]) -> Self {
+        let classes = colors.len().max(1);
+        let step = (max - min) / classes as f64;
+        Legend {
+            breaks: (1..classes).map(|i| min + step * i as f64).collect(),
+            colors: colors.iter().map(|c| c.to_string()).collect(),
+            title: None,
+        }
+    }
+
+    /// Colour of `value`; `None` for NaN.
+    pub fn color(&self, value: f64) -> Option<&str> {
+        if value.is_nan() {
+            return None;
+        }
+        let class = self.breaks.iter().take_while(|b| value >= **b).count();
+        self.colors.get(class).map(String::as_str)
+    }
+
+    /// Checks that the breaks ascend and there is one more colour than breaks.
+    ///
+    /// # Errors
+    ///
+    /// Returns error 250 if the legend is malformed.
+    pub fn validate(&self) -> Result<()> {
+        if self.colors.len() != self.breaks.len() + 1 {
+            return Err(EPANETError::from(250).with_context(format!(
+                "legend has {} breaks but {} colours",
+                self.breaks.len(),
+                self.colors.len()
+            )));
+        }
+        if self.breaks.windows(2).any(|w| w[0] > w[1]) {
+            return Err(EPANETError::from(250).with_context("legend breaks must ascend"));
+        }
+        Ok(())
+    }
+}
+
+/// Settings for [`EPANET::render_svg`](crate::EPANET::render_svg).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    /// Image width in pixels.
+    pub width: f64,
+    /// Image height in pixels.
+    pub height: f64,
+    /// Blank border around the network, in pixels.
+    pub margin: f64,
+    /// Heading drawn at the top left.
+    pub title: Option<String>,
+    pub node_coloring: Coloring<NodeProperty>,
+    pub link_coloring: Coloring<LinkProperty>,
+    /// Node classes, or `None` for equal intervals over the node values.
+    pub node_legend: Option<Legend>,
+    /// Link classes, or `None` for equal intervals over the link values.
+    pub link_legend: Option<Legend>,
+    /// Junction radius in pixels; tank, reservoir, pump and valve symbols are
+    /// scaled from it.
+    pub node_size: f64,
+    /// Link stroke width in pixels.
+    pub link_width: f64,
+    /// Draw a legend for each coloured element type.
+    pub show_legend: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            width: 800.0,
+            height: 600.0,
+            margin: 40.0,
+            title: None,
+            node_coloring: Coloring::None,
+            link_coloring: Coloring::None,
+            node_legend: None,
+            link_legend: None,
+            node_size: 3.0,
+            link_width: 2.0,
+            show_legend: true,
+        }
+    }
+}
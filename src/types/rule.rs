@@ -1,6 +1,7 @@
 //! Rule-based control types: [`Rule`] RAII struct, [`Premise`], [`ActionClause`],
 //! and rule clause enumerations.
 use crate::bindings::*;
+use crate::types::index::{LinkIndex, NodeIndex, RuleIndex};
 use crate::EPANET;
 use num_derive::FromPrimitive;
 
@@ -82,7 +83,7 @@ pub struct Rule<'a> {
     /// Reference to the owning EPANET project.
     pub(crate) project: &'a EPANET,
     /// EPANET project index of the rule.
-    pub(crate) index: i32,
+    pub(crate) index: RuleIndex,
     pub rule_id: String,
     pub premises: Vec<Premise>,
     pub then_actions: Vec<ActionClause>,
@@ -93,7 +94,7 @@ pub struct Rule<'a> {
 
 impl<'a> Rule<'a> {
     /// Returns the EPANET project index of the rule.
-    pub fn index(&self) -> i32 {
+    pub fn index(&self) -> RuleIndex {
         self.index
     }
 
@@ -113,22 +114,85 @@ impl<'a> Rule<'a> {
     pub fn delete(self) -> crate::epanet_error::Result<()> {
         self.project.delete_rule(self.index)
     }
+
+    /// Re-reads all cached fields from the engine, discarding local changes.
+    pub fn refresh(&mut self) -> crate::epanet_error::Result<()> {
+        *self = self.project.get_rule(self.index)?;
+        Ok(())
+    }
+
+    /// Returns whether any cached field differs from the engine.
+    pub fn is_stale(&self) -> crate::epanet_error::Result<bool> {
+        Ok(!self.diff_from_engine()?.is_empty())
+    }
+
+    /// Returns the names of the cached fields that differ from the engine.
+    /// Premises and actions are compared as whole lists.
+    pub fn diff_from_engine(&self) -> crate::epanet_error::Result<Vec<&'static str>> {
+        let engine = self.project.get_rule(self.index)?;
+        Ok(changed_fields!(
+            self,
+            engine,
+            rule_id,
+            premises,
+            then_actions,
+            else_actions,
+            priority,
+            enabled
+        ))
+    }
 }
 
-#[derive(Debug, Clone)]
+/// The element a [`Premise`] tests.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PremiseObject {
+    Node(NodeIndex),
+    Link(LinkIndex),
+    /// A system parameter such as the clock time.
+    System,
+}
+
+impl PremiseObject {
+    pub(crate) fn new(rule_object: RuleObject, index: i32) -> Self {
+        match rule_object {
+            RuleObject::Node => PremiseObject::Node(NodeIndex(index)),
+            RuleObject::Link => PremiseObject::Link(LinkIndex(index)),
+            RuleObject::System => PremiseObject::System,
+        }
+    }
+
+    /// The kind of element.
+    pub fn rule_object(self) -> RuleObject {
+        match self {
+            PremiseObject::Node(_) => RuleObject::Node,
+            PremiseObject::Link(_) => RuleObject::Link,
+            PremiseObject::System => RuleObject::System,
+        }
+    }
+
+    /// Raw index passed to the engine; 0 for the system.
+    pub(crate) fn index(self) -> i32 {
+        match self {
+            PremiseObject::Node(index) => index.0,
+            PremiseObject::Link(index) => index.0,
+            PremiseObject::System => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Premise {
     pub logical_operator: LogicalOperator,
-    pub rule_object: RuleObject,
-    pub object_index: i32,
+    pub object: PremiseObject,
     pub variable: RuleVariable,
     pub rule_operator: RuleOperator,
     pub status: Option<RuleStatus>,
     pub value: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ActionClause {
-    pub link_index: i32,
+    pub link_index: LinkIndex,
     pub status: RuleStatus,
     pub setting: f64,
 }
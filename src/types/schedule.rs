@@ -0,0 +1,116 @@
+//! Pump scheduling types: [`PumpScheduleOptions`], [`PumpSchedule`],
+//! [`ScheduleEvaluation`], [`PumpScheduleResult`] and [`ScheduleOutput`].
+
+use crate::types::index::{LinkIndex, NodeIndex};
+
+/// Settings for [`EPANET::optimize_pump_schedule`](crate::EPANET::optimize_pump_schedule)
+/// and [`EPANET::evaluate_pump_schedule`](crate::EPANET::evaluate_pump_schedule).
+///
+/// The simulation duration is split into equal periods of `period` seconds and
+/// each scheduled pump is given one setting per period, chosen from `settings`.
+/// Use `vec![0.0, 1.0]` for on/off scheduling or a list of relative speeds
+/// (e.g. `vec![0.0, 0.8, 0.9, 1.0]`) for variable speed pumps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PumpScheduleOptions {
+    /// Indices of the pumps to schedule. An empty list schedules every pump in the model.
+    pub pumps: Vec<LinkIndex>,
+    /// Length of a scheduling period in seconds. Must be a multiple of the pattern time step.
+    pub period: i32,
+    /// Allowed pump settings. `0.0` turns a pump off, other values are relative speeds.
+    pub settings: Vec<f64>,
+    /// Minimum acceptable pressure at the monitored nodes, in project pressure units.
+    pub min_pressure: f64,
+    /// Indices of the nodes whose pressure is checked. An empty list checks every junction.
+    pub pressure_nodes: Vec<NodeIndex>,
+    /// Allowed difference between the initial and final level of every tank.
+    pub tank_level_tolerance: f64,
+    /// Weight applied to constraint violations when comparing candidate schedules.
+    pub penalty: f64,
+    /// Maximum number of hydraulic simulations the optimizer may run.
+    pub max_evaluations: usize,
+}
+
+impl Default for PumpScheduleOptions {
+    fn default() -> Self {
+        Self {
+            pumps: Vec::new(),
+            period: 3600,
+            settings: vec![0.0, 1.0],
+            min_pressure: 0.0,
+            pressure_nodes: Vec::new(),
+            tank_level_tolerance: 1.0,
+            penalty: 1000.0,
+            max_evaluations: 500,
+        }
+    }
+}
+
+/// Settings of a single pump for every scheduling period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PumpTimetable {
+    /// Index of the scheduled pump.
+    pub link_index: LinkIndex,
+    /// Pump setting for each period (`0.0` = off, otherwise relative speed).
+    pub settings: Vec<f64>,
+}
+
+/// A pump operating schedule made of fixed-length periods.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PumpSchedule {
+    /// Length of each period in seconds.
+    pub period: i32,
+    /// One timetable per scheduled pump.
+    pub pumps: Vec<PumpTimetable>,
+}
+
+/// Outcome of simulating a [`PumpSchedule`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleEvaluation {
+    /// Total pumping energy cost over the simulation.
+    pub energy_cost: f64,
+    /// Total pumping energy in kWh.
+    pub energy: f64,
+    /// Lowest pressure observed at the monitored nodes.
+    pub min_pressure: f64,
+    /// Sum over all time steps and monitored nodes of the pressure shortfall
+    /// below [`PumpScheduleOptions::min_pressure`].
+    pub pressure_deficit: f64,
+    /// Sum over all tanks of the final level deviation beyond
+    /// [`PumpScheduleOptions::tank_level_tolerance`].
+    pub tank_level_violation: f64,
+    /// Number of hydraulic warnings raised by the solver.
+    pub warnings: u32,
+    /// `true` when the schedule satisfies every constraint.
+    pub feasible: bool,
+}
+
+impl ScheduleEvaluation {
+    /// Returns the energy cost plus `penalty` times the constraint violations.
+    ///
+    /// This is the quantity minimized by the pump schedule optimizer.
+    pub fn objective(&self, penalty: f64) -> f64 {
+        self.energy_cost
+            + penalty * (self.pressure_deficit + self.tank_level_violation + self.warnings as f64)
+    }
+}
+
+/// Result of [`EPANET::optimize_pump_schedule`](crate::EPANET::optimize_pump_schedule).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PumpScheduleResult {
+    /// Best schedule found.
+    pub schedule: PumpSchedule,
+    /// Evaluation of the best schedule.
+    pub evaluation: ScheduleEvaluation,
+    /// Number of hydraulic simulations performed.
+    pub evaluations: usize,
+}
+
+/// How a schedule is written back to the project by
+/// [`EPANET::apply_pump_schedule`](crate::EPANET::apply_pump_schedule).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ScheduleOutput {
+    /// Assign each pump a speed time pattern.
+    Patterns,
+    /// Add a timer control at each period where a pump setting changes.
+    Controls,
+}
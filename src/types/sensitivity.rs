@@ -0,0 +1,106 @@
+//! Sensitivity analysis types: [`SensitivityParameter`], [`ParameterRange`],
+//! [`SensitivityOutput`], [`SensitivityMethod`], [`SensitivityOptions`] and
+//! [`SensitivityResult`].
+
+use crate::types::index::{LinkIndex, NodeIndex};
+
+/// A model parameter that can be varied by a sensitivity analysis.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SensitivityParameter {
+    /// Roughness coefficient of a pipe.
+    Roughness { link_index: LinkIndex },
+    /// Primary base demand of a node.
+    BaseDemand { node_index: NodeIndex },
+    /// Initial water level of a tank.
+    TankInitLevel { node_index: NodeIndex },
+    /// Initial relative speed of a pump.
+    PumpSpeed { link_index: LinkIndex },
+    /// Global demand multiplier ([`Option::DemandMult`](crate::types::options::Option::DemandMult)).
+    DemandMultiplier,
+}
+
+/// A parameter together with the interval it is sampled from.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ParameterRange {
+    /// The parameter to vary.
+    pub parameter: SensitivityParameter,
+    /// Lower bound of the parameter value.
+    pub min: f64,
+    /// Upper bound of the parameter value.
+    pub max: f64,
+}
+
+/// A scalar simulation result whose sensitivity is measured.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SensitivityOutput {
+    /// Lowest pressure at a node over the simulation.
+    MinPressure { node_index: NodeIndex },
+    /// Total energy used by all pumps over the simulation, in kWh.
+    TotalEnergy,
+    /// Lowest water level of a tank over the simulation.
+    MinTankLevel { node_index: NodeIndex },
+}
+
+/// Sampling scheme used by a sensitivity analysis.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SensitivityMethod {
+    /// Morris elementary effects screening. Runs `trajectories * (k + 1)` simulations
+    /// for `k` parameters, each sampled on a grid with `levels` levels.
+    Morris { trajectories: usize, levels: usize },
+    /// Sobol variance-based indices estimated with the Saltelli scheme. Runs
+    /// `samples * (k + 2)` simulations for `k` parameters.
+    Sobol { samples: usize },
+}
+
+/// Settings for [`EPANET::sensitivity_analysis`](crate::EPANET::sensitivity_analysis).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensitivityOptions {
+    /// Parameters to vary and their ranges.
+    pub parameters: Vec<ParameterRange>,
+    /// Outputs to analyse.
+    pub outputs: Vec<SensitivityOutput>,
+    /// Sampling scheme.
+    pub method: SensitivityMethod,
+    /// Seed of the pseudo-random sampler, so runs are reproducible.
+    pub seed: u64,
+}
+
+/// Sensitivity of one output to one parameter.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SensitivityIndex {
+    /// Morris statistics of the elementary effects, computed on parameters scaled to `[0, 1]`.
+    Morris {
+        /// Mean elementary effect.
+        mu: f64,
+        /// Mean absolute elementary effect, used to rank parameters.
+        mu_star: f64,
+        /// Standard deviation of the elementary effects (non-linearity and interactions).
+        sigma: f64,
+    },
+    /// Sobol variance-based indices.
+    Sobol {
+        /// Share of the output variance explained by the parameter alone.
+        first_order: f64,
+        /// Share of the output variance involving the parameter, including interactions.
+        total_order: f64,
+    },
+}
+
+/// Sensitivity indices of a single output, one per parameter in
+/// [`SensitivityOptions::parameters`] order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputSensitivity {
+    /// The analysed output.
+    pub output: SensitivityOutput,
+    /// Index of each parameter.
+    pub indices: Vec<SensitivityIndex>,
+}
+
+/// Result of [`EPANET::sensitivity_analysis`](crate::EPANET::sensitivity_analysis).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensitivityResult {
+    /// Indices for each output, in [`SensitivityOptions::outputs`] order.
+    pub outputs: Vec<OutputSensitivity>,
+    /// Number of simulations run.
+    pub evaluations: usize,
+}
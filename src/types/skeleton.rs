@@ -0,0 +1,78 @@
+//! Network skeletonization types: [`SkeletonOptions`], [`Reduction`] and
+//! [`SkeletonReport`].
+
+/// Settings for [`EPANET::skeletonize`](crate::EPANET::skeletonize).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkeletonOptions {
+    /// Only pipes with a diameter at or below this value are removed or merged.
+    pub max_diameter: f64,
+    /// Remove dead-end branches, moving their demand to the upstream junction.
+    pub trim_branches: bool,
+    /// Merge pairs of pipes joined by a junction with no other connections.
+    pub merge_series: bool,
+    /// Merge pipes that connect the same pair of nodes.
+    pub merge_parallel: bool,
+    /// IDs of nodes that must be kept (e.g. monitoring or critical nodes).
+    pub keep_nodes: Vec<String>,
+    /// Largest acceptable pressure change at the remaining nodes.
+    pub pressure_tolerance: f64,
+}
+
+impl Default for SkeletonOptions {
+    fn default() -> Self {
+        Self {
+            max_diameter: 8.0,
+            trim_branches: true,
+            merge_series: true,
+            merge_parallel: true,
+            keep_nodes: Vec::new(),
+            pressure_tolerance: 1.0,
+        }
+    }
+}
+
+/// A single simplification performed by the skeletonizer. Elements are referred
+/// to by ID since indices change as elements are deleted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reduction {
+    /// A dead-end junction and its pipe were removed.
+    BranchTrimmed {
+        /// ID of the removed junction.
+        node_id: String,
+        /// ID of the removed pipe.
+        pipe_id: String,
+        /// ID of the junction that received the demand.
+        demand_moved_to: String,
+        /// Total base demand moved.
+        demand: f64,
+    },
+    /// Two pipes in series were replaced by an equivalent pipe.
+    SeriesMerged {
+        /// ID of the removed junction between the two pipes.
+        node_id: String,
+        /// ID of the pipe that now spans both.
+        kept_pipe_id: String,
+        /// ID of the removed pipe.
+        removed_pipe_id: String,
+    },
+    /// Two parallel pipes were replaced by an equivalent pipe.
+    ParallelMerged {
+        /// ID of the pipe resized to carry the combined flow.
+        kept_pipe_id: String,
+        /// ID of the removed pipe.
+        removed_pipe_id: String,
+    },
+}
+
+/// Result of [`EPANET::skeletonize`](crate::EPANET::skeletonize).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkeletonReport {
+    /// Every reduction performed, in order.
+    pub reductions: Vec<Reduction>,
+    /// Largest pressure difference at any remaining node and time step between the
+    /// original and reduced models.
+    pub max_pressure_difference: f64,
+    /// `true` when `max_pressure_difference` is within
+    /// [`SkeletonOptions::pressure_tolerance`].
+    pub within_tolerance: bool,
+}
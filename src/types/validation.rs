@@ -0,0 +1,78 @@
+//! Model validation types: [`Severity`], [`FindingKind`] and [`Finding`].
+//!
+//! Findings are produced by [`EPANET::validate`](crate::EPANET::validate), which
+//! checks the network for input problems without running a simulation.
+
+use crate::types::ObjectType;
+use std::fmt::{self, Display};
+
+/// How serious a finding is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Unusual but possibly intended.
+    Info,
+    /// Likely to give wrong or unstable results.
+    Warning,
+    /// The model is invalid or cannot be solved as intended.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// The check that produced a finding.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FindingKind {
+    /// A node with no links.
+    UnconnectedNode,
+    /// A node with no path to any tank or reservoir.
+    NoSourcePath,
+    /// A pipe with a zero or negative length.
+    NonPositiveLength,
+    /// A pipe or valve with a zero or negative diameter.
+    NonPositiveDiameter,
+    /// A pipe roughness outside the plausible range for the head loss formula.
+    ImplausibleRoughness,
+    /// A tank whose minimum level is above its maximum level.
+    TankLevelRange,
+    /// A tank whose initial level is outside its minimum and maximum levels.
+    TankInitialLevel,
+    /// A pump without a head curve.
+    PumpWithoutCurve,
+    /// Two valves joined by a node with no other links.
+    ValvesInSeries,
+    /// A node at the same coordinates as another node.
+    DuplicateCoordinates,
+    /// A pattern that is referenced but has no factors or only zero factors.
+    EmptyPattern,
+    /// A control or rule that refers to a node or link that does not exist.
+    DanglingReference,
+    /// A pipe connecting the same pair of nodes as another pipe.
+    DuplicatePipe,
+}
+
+/// A single problem found by [`EPANET::validate`](crate::EPANET::validate).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub kind: FindingKind,
+    /// Type of the offending element.
+    pub object: ObjectType,
+    /// ID of the offending element; controls, which have no ID, use their index.
+    pub id: String,
+    /// Human-readable description.
+    pub message: String,
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
@@ -0,0 +1,159 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+const NET1: &str = "src/impls/test_utils/net1.inp";
+
+static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// A temporary file path, removed when dropped.
+struct TempFile(PathBuf);
+
+impl std::ops::Deref for TempFile {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempFile {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn temp_path(extension: &str) -> TempFile {
+    let id = TEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+    TempFile(std::env::temp_dir().join(format!(
+        "epanet_cli_{}_{id}.{extension}",
+        std::process::id()
+    )))
+}
+
+fn epanet(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_epanet"))
+        .args(args)
+        .output()
+        .expect("failed to run epanet")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn test_info() {
+    let output = epanet(&["info", NET1]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.contains("Nodes:          11 (9 junctions, 1 tanks, 1 reservoirs)"));
+    assert!(text.contains("Flow units:     GPM"));
+    assert!(text.contains("Duration:       24:00:00"));
+}
+
+#[test]
+fn test_run_writes_report() {
+    let report = temp_path("rpt");
+    let output = epanet(&["run", NET1, "--report", report.to_str().unwrap(), "--quiet"]);
+    assert!(output.status.success());
+    let text = std::fs::read_to_string(&report).unwrap();
+    assert!(text.contains("Water Quality Mass Balance"));
+}
+
+#[test]
+fn test_export_csv() {
+    let path = temp_path("csv");
+    let output = epanet(&[
+        "export",
+        NET1,
+        "--output",
+        path.to_str().unwrap(),
+        "--nodes",
+        "pressure",
+        "--links",
+        "flow",
+    ]);
+    assert!(output.status.success());
+    let text = std::fs::read_to_string(&path).unwrap();
+    let mut lines = text.lines();
+    assert_eq!(lines.next(), Some("time,element,id,property,value"));
+    // 25 hourly reporting times x (11 nodes + 13 links).
+    assert_eq!(lines.count(), 25 * 24);
+}
+
+#[test]
+fn test_convert_and_diff() {
+    let converted = temp_path("inp");
+    let output = epanet(&[
+        "convert",
+        NET1,
+        converted.to_str().unwrap(),
+        "--flow-units",
+        "LPS",
+    ]);
+    assert!(output.status.success());
+
+    let same = epanet(&["diff", NET1, NET1]);
+    assert!(same.status.success());
+    assert!(stdout(&same).is_empty());
+
+    let changed = epanet(&["diff", NET1, converted.to_str().unwrap()]);
+    assert_eq!(changed.status.code(), Some(1));
+    assert!(stdout(&changed).contains("~ options flow units: GPM -> LPS"));
+
+    let json = epanet(&[
+        "diff",
+        NET1,
+        converted.to_str().unwrap(),
+        "--format",
+        "json",
+    ]);
+    assert_eq!(json.status.code(), Some(1));
+    assert!(stdout(&json).starts_with(
+        "{\"changes\":[{\"change\":\"modified\",\"object\":\"options\",\"id\":\"\""
+    ));
+}
+
+#[test]
+fn test_validate() {
+    let output = epanet(&["validate", NET1]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("0 error(s)"));
+}
+
+#[test]
+fn test_usage_errors() {
+    assert_eq!(epanet(&[]).status.code(), Some(2));
+    assert_eq!(epanet(&["bogus"]).status.code(), Some(2));
+    assert_eq!(epanet(&["info"]).status.code(), Some(2));
+    assert_eq!(epanet(&["info", NET1, "--nope"]).status.code(), Some(2));
+    assert_eq!(epanet(&["info", "missing.inp"]).status.code(), Some(1));
+}
+
+#[test]
+fn test_export_json_wide() {
+    let path = temp_path("json");
+    let output = epanet(&[
+        "export",
+        NET1,
+        "--output",
+        path.to_str().unwrap(),
+        "--format",
+        "json",
+        "--layout",
+        "wide",
+        "--time",
+        "clock",
+    ]);
+    assert!(output.status.success());
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(text.lines().count(), 25);
+    assert!(text.starts_with("{\"time\":\"0:00:00\",\"nodes\":{\"10\":{\"pressure\":"));
+}